@@ -0,0 +1,149 @@
+use serde::{Serialize, Deserialize};
+
+/// The number of Newton steps used to invert a cubic Bézier curve before falling back to bisection
+const NEWTON_STEPS: usize = 8;
+
+/// How close the inverted time of a cubic Bézier curve must be
+const EPSILON: f32 = 1e-5;
+
+/// A curve mapping the linear progress of an animation to the progress of the animated value
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Easing {
+    /// The value moves at a constant speed
+    #[default]
+    Linear,
+    /// The value starts slow and speeds up
+    EaseIn,
+    /// The value starts fast and slows down
+    EaseOut,
+    /// The value starts slow, speeds up and slows down again
+    EaseInOut,
+    /// A cubic Bézier curve from (0, 0) to (1, 1) with the control points (x1, y1) and (x2, y2), like CSS cubic-bezier().
+    /// The x-coordinates are clamped to 0 to 1, the y-coordinates may go outside of it to overshoot
+    CubicBezier(f32, f32, f32, f32),
+}
+
+impl Easing {
+    /// Gets the progress of the value, 0 at the start and 1 at the end
+    ///
+    /// # Parameters
+    ///
+    /// t: The linear progress of the animation, it is clamped to 0 to 1
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = if t.is_nan() { 1.0 } else { t.clamp(0.0, 1.0) };
+
+        // The named curves are the same as in CSS
+        match *self {
+            Self::Linear => t,
+            Self::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            Self::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            Self::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            Self::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1.clamp(0.0, 1.0), y1, x2.clamp(0.0, 1.0), y2, t),
+        }
+    }
+}
+
+/// Evaluates a cubic Bézier curve from (0, 0) to (1, 1) at an x-coordinate
+///
+/// # Parameters
+///
+/// x1, y1: The first control point
+///
+/// x2, y2: The second control point
+///
+/// x: The x-coordinate to get the y-coordinate of
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    if x <= 0.0 || x >= 1.0 {
+        return x;
+    }
+
+    // Find the curve parameter for the x-coordinate, Newton's method converges fast unless the slope is flat
+    let mut s = x;
+    for _ in 0..NEWTON_STEPS {
+        let error = bezier(x1, x2, s) - x;
+        if error.abs() < EPSILON {
+            return bezier(y1, y2, s);
+        }
+        let slope = bezier_slope(x1, x2, s);
+        if slope.abs() < EPSILON {
+            break;
+        }
+        s -= error / slope;
+    }
+
+    // The x-coordinate always grows with the parameter since the control points are inside 0 to 1
+    let (mut low, mut high) = (0.0, 1.0);
+    s = x;
+    while high - low > EPSILON {
+        if bezier(x1, x2, s) < x {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+
+    bezier(y1, y2, s)
+}
+
+/// Evaluates one coordinate of a cubic Bézier curve which starts at 0 and ends at 1
+///
+/// # Parameters
+///
+/// p1: The coordinate of the first control point
+///
+/// p2: The coordinate of the second control point
+///
+/// s: The curve parameter
+fn bezier(p1: f32, p2: f32, s: f32) -> f32 {
+    let inv = 1.0 - s;
+    3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s
+}
+
+/// Gets the derivative of one coordinate of a cubic Bézier curve which starts at 0 and ends at 1
+///
+/// # Parameters
+///
+/// p1: The coordinate of the first control point
+///
+/// p2: The coordinate of the second control point
+///
+/// s: The curve parameter
+fn bezier_slope(p1: f32, p2: f32, s: f32) -> f32 {
+    let inv = 1.0 - s;
+    3.0 * inv * inv * p1 + 6.0 * inv * s * (p2 - p1) + 3.0 * s * s * (1.0 - p2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut, Easing::CubicBezier(0.3, -0.5, 0.7, 1.5)] {
+            assert_eq!(0.0, easing.apply(0.0));
+            assert_eq!(1.0, easing.apply(1.0));
+            assert_eq!(1.0, easing.apply(2.0));
+            assert_eq!(0.0, easing.apply(-1.0));
+        }
+
+        assert_eq!(0.25, Easing::Linear.apply(0.25));
+        assert!(Easing::EaseIn.apply(0.25) < 0.25);
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+        assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-4);
+
+        // Overshooting control points leave 0 to 1 on the way
+        assert!(Easing::CubicBezier(0.3, -0.5, 0.7, 1.5).apply(0.1) < 0.0);
+        assert!(Easing::CubicBezier(0.3, -0.5, 0.7, 1.5).apply(0.9) > 1.0);
+    }
+
+    #[test]
+    fn bezier_matches_linear() {
+        // Control points on the diagonal give a straight line
+        let easing = Easing::CubicBezier(0.25, 0.25, 0.75, 0.75);
+        for step in 0..=10 {
+            let t = step as f32 / 10.0;
+            assert!((easing.apply(t) - t).abs() < 1e-4);
+        }
+    }
+}
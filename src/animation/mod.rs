@@ -0,0 +1,106 @@
+mod easing;
+mod timeline;
+mod spring;
+
+use std::time::Duration;
+use serde::{Serialize, Deserialize};
+
+pub use easing::Easing;
+pub use timeline::{Timeline, TimelineController, TimelineId, TimelineError, Keyframe, Property, Value, Repeat};
+pub(crate) use timeline::complete;
+pub use spring::{Spring, SpringValue};
+
+/// Describes how a value moves when its target changes
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Motion {
+    /// The value moves along an easing curve over a fixed duration
+    Transition(Transition),
+    /// The value is pulled towards the target by a spring and keeps its speed when the target changes
+    Spring(Spring),
+}
+
+/// Describes how a value moves from an old value to a new one
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Transition {
+    /// The time it takes to reach the new value
+    pub duration: Duration,
+    /// The curve the value follows
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+impl Transition {
+    /// Creates a new transition
+    ///
+    /// # Parameters
+    ///
+    /// duration: The time it takes to reach the new value
+    ///
+    /// easing: The curve the value follows
+    pub fn new(duration: Duration, easing: Easing) -> Self {
+        Self { duration, easing }
+    }
+
+    /// Gets how far the value has moved as a fraction, it is 0 at the start and 1 once the transition is done.
+    /// Some easing curves overshoot so the fraction may go outside of 0 to 1 while the transition runs
+    ///
+    /// # Parameters
+    ///
+    /// elapsed: The time since the transition started
+    pub fn get_progress(&self, elapsed: Duration) -> f32 {
+        if elapsed >= self.duration {
+            return 1.0;
+        }
+
+        self.easing.apply(elapsed.as_secs_f32() / self.duration.as_secs_f32())
+    }
+
+    /// Checks if the transition is done
+    ///
+    /// # Parameters
+    ///
+    /// elapsed: The time since the transition started
+    pub fn is_done(&self, elapsed: Duration) -> bool {
+        elapsed >= self.duration
+    }
+}
+
+/// Interpolates linearly between two values
+///
+/// # Parameters
+///
+/// from: The value at a progress of 0
+///
+/// to: The value at a progress of 1
+///
+/// progress: How far to move from the first value to the second
+pub(crate) fn lerp(from: f32, to: f32, progress: f32) -> f32 {
+    from + (to - from) * progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress() {
+        let transition = Transition::new(Duration::from_millis(200), Easing::Linear);
+        assert_eq!(0.0, transition.get_progress(Duration::ZERO));
+        assert_eq!(0.5, transition.get_progress(Duration::from_millis(100)));
+        assert_eq!(1.0, transition.get_progress(Duration::from_millis(300)));
+        assert!(!transition.is_done(Duration::from_millis(100)));
+        assert!(transition.is_done(Duration::from_millis(200)));
+
+        // A transition without a duration is done right away
+        let instant = Transition::new(Duration::ZERO, Easing::EaseInOut);
+        assert_eq!(1.0, instant.get_progress(Duration::ZERO));
+        assert!(instant.is_done(Duration::ZERO));
+    }
+
+    #[test]
+    fn interpolate() {
+        assert_eq!(2.0, lerp(2.0, 4.0, 0.0));
+        assert_eq!(3.0, lerp(2.0, 4.0, 0.5));
+        assert_eq!(5.0, lerp(2.0, 4.0, 1.5));
+    }
+}
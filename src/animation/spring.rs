@@ -0,0 +1,220 @@
+use serde::{Serialize, Deserialize};
+use std::time::Duration;
+
+/// The longest time simulated in a single step, shorter steps keep stiff springs stable
+const MAX_STEP: f32 = 1.0 / 240.0;
+
+/// The physical parameters of a damped spring
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Spring {
+    /// How hard the spring pulls towards the target, higher is faster
+    pub stiffness: f32,
+    /// How much the motion is slowed down, low damping bounces around the target
+    pub damping: f32,
+    /// The mass being moved, a heavier mass is slower and bounces more
+    pub mass: f32,
+    /// The distance to the target and the speed below which the motion has settled, in the units of the value
+    pub precision: f32,
+}
+
+impl Spring {
+    /// Creates a spring which settles without bouncing at a precision of 0.001
+    ///
+    /// # Parameters
+    ///
+    /// stiffness: How hard the spring pulls towards the target
+    ///
+    /// mass: The mass being moved
+    pub fn critically_damped(stiffness: f32, mass: f32) -> Self {
+        Self { stiffness, damping: 2.0 * (stiffness * mass).sqrt(), mass, precision: 0.001 }
+    }
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self { stiffness: 170.0, damping: 26.0, mass: 1.0, precision: 0.001 }
+    }
+}
+
+/// A value pulled towards a target by a spring, it can be used for anything which should move naturally such as scroll offsets
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpringValue {
+    /// The parameters of the spring
+    spring: Spring,
+    /// The current value
+    value: f32,
+    /// The current speed in units per second
+    velocity: f32,
+    /// The value the spring pulls towards
+    target: f32,
+}
+
+impl SpringValue {
+    /// Creates a value at rest at its target
+    ///
+    /// # Parameters
+    ///
+    /// spring: The parameters of the spring
+    ///
+    /// value: The starting value and target
+    pub fn new(spring: Spring, value: f32) -> Self {
+        Self { spring, value, velocity: 0.0, target: value }
+    }
+
+    /// Gets the parameters of the spring
+    pub fn get_spring(&self) -> Spring {
+        self.spring
+    }
+
+    /// Sets the parameters of the spring, the value keeps its speed
+    ///
+    /// # Parameters
+    ///
+    /// spring: The new parameters
+    pub fn set_spring(&mut self, spring: Spring) {
+        self.spring = spring;
+    }
+
+    /// Gets the current value
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+
+    /// Moves the value without changing its speed or target
+    ///
+    /// # Parameters
+    ///
+    /// value: The new value
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value;
+    }
+
+    /// Gets the current speed in units per second
+    pub fn get_velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    /// Sets the current speed, for example to hand off the speed of a fling gesture
+    ///
+    /// # Parameters
+    ///
+    /// velocity: The new speed in units per second
+    pub fn set_velocity(&mut self, velocity: f32) {
+        self.velocity = velocity;
+    }
+
+    /// Gets the value the spring pulls towards
+    pub fn get_target(&self) -> f32 {
+        self.target
+    }
+
+    /// Sets the value the spring pulls towards, the value keeps its speed so the motion never jumps
+    ///
+    /// # Parameters
+    ///
+    /// target: The new target
+    pub fn set_target(&mut self, target: f32) {
+        self.target = target;
+    }
+
+    /// Checks if the value is close enough to the target and slow enough to be considered at rest
+    pub fn is_settled(&self) -> bool {
+        (self.target - self.value).abs() <= self.spring.precision && self.velocity.abs() <= self.spring.precision
+    }
+
+    /// Moves the value forward in time, once it settles it is snapped to the target and stops.
+    /// Returns true if the value is settled
+    ///
+    /// # Parameters
+    ///
+    /// elapsed: The time to simulate
+    pub fn step(&mut self, elapsed: Duration) -> bool {
+        // A spring without mass or with broken parameters cannot move, it jumps to the target
+        let valid = self.spring.mass > 0.0 && self.spring.stiffness >= 0.0 && self.spring.damping >= 0.0;
+        if !valid || !self.value.is_finite() || !self.velocity.is_finite() || !self.target.is_finite() {
+            self.value = self.target;
+            self.velocity = 0.0;
+            return true;
+        }
+
+        // Semi-implicit Euler in small steps, it keeps the energy from growing
+        let elapsed = elapsed.as_secs_f32();
+        let steps = (elapsed / MAX_STEP).ceil().max(1.0) as u32;
+        let dt = elapsed / steps as f32;
+        for _ in 0..steps {
+            let force = -self.spring.stiffness * (self.value - self.target) - self.spring.damping * self.velocity;
+            self.velocity += force / self.spring.mass * dt;
+            self.value += self.velocity * dt;
+        }
+
+        if self.is_settled() {
+            self.value = self.target;
+            self.velocity = 0.0;
+        }
+
+        self.is_settled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Steps a value at 60 frames per second until it settles, returns the number of frames and the largest value seen
+    fn settle(value: &mut SpringValue) -> (usize, f32) {
+        let mut max = value.get_value();
+        for frame in 1..1000 {
+            let settled = value.step(Duration::from_micros(16_667));
+            max = max.max(value.get_value());
+            if settled {
+                return (frame, max);
+            }
+        }
+
+        panic!("The spring never settled");
+    }
+
+    #[test]
+    fn settles() {
+        // A critically damped spring reaches the target without overshooting
+        let mut value = SpringValue::new(Spring::critically_damped(200.0, 1.0), 0.0);
+        value.set_target(1.0);
+        let (frames, max) = settle(&mut value);
+        assert!(frames < 120);
+        assert_eq!(1.0, max);
+        assert_eq!(1.0, value.get_value());
+        assert_eq!(0.0, value.get_velocity());
+        assert!(value.is_settled());
+
+        // An underdamped spring bounces past the target
+        let mut value = SpringValue::new(Spring { damping: 5.0, ..Spring::default() }, 0.0);
+        value.set_target(1.0);
+        let (_, max) = settle(&mut value);
+        assert!(max > 1.1);
+
+        // A heavier mass is slower
+        let mut light = SpringValue::new(Spring::critically_damped(200.0, 1.0), 0.0);
+        let mut heavy = SpringValue::new(Spring::critically_damped(200.0, 4.0), 0.0);
+        light.set_target(1.0);
+        heavy.set_target(1.0);
+        assert!(settle(&mut light).0 < settle(&mut heavy).0);
+    }
+
+    #[test]
+    fn velocity() {
+        // A fling at the target carries the value past it before it comes back
+        let mut value = SpringValue::new(Spring::default(), 0.0);
+        value.set_velocity(10.0);
+        assert!(!value.is_settled());
+        let (_, max) = settle(&mut value);
+        assert!(max > 0.1);
+        assert_eq!(0.0, value.get_value());
+
+        // Broken parameters jump to the target
+        let mut value = SpringValue::new(Spring { mass: 0.0, ..Spring::default() }, 0.0);
+        value.set_target(5.0);
+        assert!(value.step(Duration::from_millis(1)));
+        assert_eq!(5.0, value.get_value());
+    }
+}
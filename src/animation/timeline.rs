@@ -0,0 +1,605 @@
+use super::{Easing, lerp};
+use crate::{view::{View, extent, style}, render::Color};
+use std::{cell::RefCell, rc::{Rc, Weak}, fmt, time::{Duration, Instant}};
+use thiserror::Error;
+
+/// A property of a view which can be animated by a timeline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Property {
+    /// The offset of the extent relative to its own size in a dimension
+    OffsetRel(extent::Dim),
+    /// The offset of the extent as a fraction of the size of the parent in a dimension
+    OffsetAbs(extent::Dim),
+    /// The factor the size of the extent is scaled by in a dimension
+    ScaleRel(extent::Dim),
+    /// The amount added to the size of the extent as a fraction of the size of the parent in a dimension
+    ScaleAbs(extent::Dim),
+    /// The opacity of the view, it is clamped to 0 to 1
+    Opacity,
+    /// The background color
+    Background,
+    /// The color of the border
+    BorderColor,
+    /// The color of text
+    TextColor,
+}
+
+impl Property {
+    /// Checks if the property is a color, all other properties are numbers
+    pub fn is_color(&self) -> bool {
+        matches!(self, Self::Background | Self::BorderColor | Self::TextColor)
+    }
+}
+
+/// The value of an animated property
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    /// The value of a number property
+    Number(f32),
+    /// The value of a color property
+    Color(Color),
+}
+
+impl Value {
+    /// Interpolates between two values, the colors are interpolated per component and clamped
+    ///
+    /// # Parameters
+    ///
+    /// to: The value at a progress of 1
+    ///
+    /// progress: How far to move from this value to the other
+    fn lerp(&self, to: &Value, progress: f32) -> Value {
+        match (*self, *to) {
+            (Self::Number(from), Self::Number(to)) => Self::Number(lerp(from, to, progress)),
+            (Self::Color(from), Self::Color(to)) => {
+                let channel = |from: u8, to: u8| lerp(from as f32, to as f32, progress).round().clamp(0.0, 255.0) as u8;
+                Self::Color(Color::new(channel(from.r, to.r), channel(from.g, to.g), channel(from.b, to.b), channel(from.a, to.a)))
+            }
+
+            // The types of all the keyframes of a track are checked when they are added
+            _ => *to,
+        }
+    }
+}
+
+/// A value a property must have at a time of a timeline
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keyframe {
+    /// The time since the start of an iteration of the timeline
+    pub time: Duration,
+    /// The value of the property
+    pub value: Value,
+    /// The curve the value follows from the previous keyframe to this one
+    pub easing: Easing,
+}
+
+impl Keyframe {
+    /// Creates a new keyframe
+    ///
+    /// # Parameters
+    ///
+    /// time: The time since the start of an iteration of the timeline
+    ///
+    /// value: The value of the property
+    ///
+    /// easing: The curve the value follows from the previous keyframe to this one
+    pub fn new(time: Duration, value: Value, easing: Easing) -> Self {
+        Self { time, value, easing }
+    }
+}
+
+/// How many times a timeline is played
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Repeat {
+    /// The timeline is played a number of times, with 0 it is done right away without changing anything
+    Count(u32),
+    /// The timeline is played until it is cancelled
+    Forever,
+}
+
+impl Default for Repeat {
+    fn default() -> Self {
+        Self::Count(1)
+    }
+}
+
+/// The keyframes of a single property
+#[derive(Clone, Debug, PartialEq)]
+struct Track {
+    /// The animated property
+    property: Property,
+    /// The keyframes sorted by time, there is always at least one
+    keyframes: Vec<Keyframe>,
+}
+
+impl Track {
+    /// Gets the value of the property at a time of an iteration, before the first keyframe it has the first value and after the last it has the last value
+    ///
+    /// # Parameters
+    ///
+    /// time: The time since the start of the iteration
+    fn get(&self, time: Duration) -> Value {
+        let last = self.keyframes.len() - 1;
+        match self.keyframes.iter().position(|keyframe| keyframe.time > time) {
+            None => self.keyframes[last].value,
+            Some(0) => self.keyframes[0].value,
+            Some(pos) => {
+                let (prev, next) = (&self.keyframes[pos - 1], &self.keyframes[pos]);
+                let t = (time - prev.time).as_secs_f32() / (next.time - prev.time).as_secs_f32();
+                prev.value.lerp(&next.value, next.easing.apply(t))
+            }
+        }
+    }
+}
+
+/// Animates properties of a single view along keyframes, it starts at the first frame after it is added to a timeline controller
+pub struct Timeline {
+    /// The extent controller of the animated view
+    extent: Rc<RefCell<extent::ExtentController>>,
+    /// The style controller of the animated view
+    style: Rc<RefCell<style::StyleController>>,
+    /// The token of the animated view, the timeline is removed once the view is deleted
+    owner: Weak<()>,
+    /// The keyframes of every animated property
+    tracks: Vec<Track>,
+    /// The time to wait before the first iteration starts
+    delay: Duration,
+    /// How many times the timeline is played
+    repeat: Repeat,
+    /// True if every other iteration is played backwards
+    ping_pong: bool,
+    /// The functions to run once the timeline is done
+    callbacks: Vec<Box<dyn FnMut()>>,
+    /// The frame time of the first frame, None if it has not been played yet
+    start: Option<Instant>,
+}
+
+impl Timeline {
+    /// Creates a new timeline without any keyframes which is played once without a delay
+    ///
+    /// # Parameters
+    ///
+    /// view: The view to animate
+    pub fn new(view: &View) -> Self {
+        Self { extent: view.get_extent_controller(), style: view.get_style_controller(), owner: view.get_owner(), tracks: Vec::new(), delay: Duration::ZERO, repeat: Repeat::default(), ping_pong: false, callbacks: Vec::new(), start: None }
+    }
+
+    /// Adds a keyframe for a property, a keyframe at the same time as an existing one comes after it so the value jumps.
+    /// Returns an error if the value does not fit the property
+    ///
+    /// # Parameters
+    ///
+    /// property: The property to animate
+    ///
+    /// keyframe: The keyframe to add
+    pub fn add_keyframe(&mut self, property: Property, keyframe: Keyframe) -> Result<(), TimelineError> {
+        if property.is_color() != matches!(keyframe.value, Value::Color(_)) {
+            return Err(TimelineError::WrongValue(property, keyframe.value));
+        }
+
+        match self.tracks.iter_mut().find(|track| track.property == property) {
+            Some(track) => {
+                let pos = track.keyframes.partition_point(|other| other.time <= keyframe.time);
+                track.keyframes.insert(pos, keyframe);
+            }
+            None => self.tracks.push(Track { property, keyframes: vec![keyframe] }),
+        }
+
+        Ok(())
+    }
+
+    /// Gets the time to wait before the first iteration starts
+    pub fn get_delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Sets the time to wait before the first iteration starts, the properties are not changed while waiting
+    ///
+    /// # Parameters
+    ///
+    /// delay: The new delay
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    /// Gets how many times the timeline is played
+    pub fn get_repeat(&self) -> Repeat {
+        self.repeat
+    }
+
+    /// Sets how many times the timeline is played
+    ///
+    /// # Parameters
+    ///
+    /// repeat: The new number of iterations
+    pub fn set_repeat(&mut self, repeat: Repeat) {
+        self.repeat = repeat;
+    }
+
+    /// Checks if every other iteration is played backwards
+    pub fn get_ping_pong(&self) -> bool {
+        self.ping_pong
+    }
+
+    /// Sets whether every other iteration is played backwards, the first iteration is always played forwards
+    ///
+    /// # Parameters
+    ///
+    /// ping_pong: True to play every other iteration backwards
+    pub fn set_ping_pong(&mut self, ping_pong: bool) {
+        self.ping_pong = ping_pong;
+    }
+
+    /// Adds a function to run once the timeline is done, it is not run if the timeline is cancelled or repeats forever
+    ///
+    /// # Parameters
+    ///
+    /// callback: The function to run
+    pub fn add_completion_callback<F: FnMut() + 'static>(&mut self, callback: F) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    /// Gets the length of a single iteration, this is the time of the last keyframe
+    pub fn get_duration(&self) -> Duration {
+        self.tracks.iter().filter_map(|track| track.keyframes.last()).map(|keyframe| keyframe.time).max().unwrap_or(Duration::ZERO)
+    }
+
+    /// Checks if the animated view has been deleted
+    fn is_orphaned(&self) -> bool {
+        self.owner.strong_count() == 0
+    }
+
+    /// Sets the properties to their values at a frame time, returns true if the timeline is done.
+    /// The first call decides the start of the timeline
+    ///
+    /// # Parameters
+    ///
+    /// time: The frame time
+    fn advance(&mut self, time: Instant) -> bool {
+        let start = *self.start.get_or_insert(time);
+        let active = match time.saturating_duration_since(start).checked_sub(self.delay) {
+            Some(active) => active,
+            None => return false,
+        };
+
+        // Find the iteration and how far into it the timeline is, whole nanoseconds keep long running timelines exact
+        let duration = self.get_duration();
+        let (iteration, offset, done) = match self.repeat {
+            Repeat::Count(0) => return true,
+            Repeat::Count(count) if duration.is_zero() || active.as_nanos() >= duration.as_nanos() * count as u128 => (count as u128 - 1, duration, true),
+            Repeat::Forever if duration.is_zero() => (0, duration, true),
+            _ => (active.as_nanos() / duration.as_nanos(), Duration::from_nanos((active.as_nanos() % duration.as_nanos()) as u64), false),
+        };
+        let offset = if self.ping_pong && iteration % 2 == 1 { duration - offset } else { offset };
+
+        self.apply(offset);
+
+        done
+    }
+
+    /// Sets the properties to their values at a time of an iteration
+    ///
+    /// # Parameters
+    ///
+    /// time: The time since the start of the iteration
+    fn apply(&self, time: Duration) {
+        let mut extent_controller = self.extent.borrow_mut();
+        let mut style_controller = self.style.borrow_mut();
+        let mut update_info = extent_controller.get_update_info();
+        let mut animated = style_controller.get_animated();
+        let mut extent_changed = false;
+
+        for track in self.tracks.iter() {
+            match (track.property, track.get(time)) {
+                // Extent properties only move the view, they never change its references
+                (Property::OffsetRel(dim), Value::Number(value)) => (single(&mut update_info, dim).offset_rel, extent_changed) = (value, true),
+                (Property::OffsetAbs(dim), Value::Number(value)) => (single(&mut update_info, dim).offset_abs, extent_changed) = (value, true),
+                (Property::ScaleRel(dim), Value::Number(value)) => (single(&mut update_info, dim).scale_rel, extent_changed) = (value, true),
+                (Property::ScaleAbs(dim), Value::Number(value)) => (single(&mut update_info, dim).scale_abs, extent_changed) = (value, true),
+
+                // Style properties go on top of the style so a theme does not replace them, they mark the view dirty if they changed
+                (Property::Opacity, Value::Number(value)) => animated.opacity = Some(value.clamp(0.0, 1.0)),
+                (Property::Background, Value::Color(color)) => animated.background = Some(color),
+                (Property::BorderColor, Value::Color(color)) => animated.border_color = Some(color),
+                (Property::TextColor, Value::Color(color)) => animated.text_color = Some(color),
+                _ => (),
+            }
+        }
+
+        if extent_changed {
+            extent_controller.set_animated_update_info(update_info);
+        }
+        style_controller.set_animated(animated);
+    }
+
+    /// Runs all the completion callbacks
+    fn complete(&mut self) {
+        for callback in self.callbacks.iter_mut() {
+            callback();
+        }
+    }
+}
+
+impl fmt::Debug for Timeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timeline")
+            .field("tracks", &self.tracks)
+            .field("delay", &self.delay)
+            .field("repeat", &self.repeat)
+            .field("ping_pong", &self.ping_pong)
+            .field("callbacks", &self.callbacks.len())
+            .field("start", &self.start)
+            .finish()
+    }
+}
+
+/// Gets the update info of a single dimension
+///
+/// # Parameters
+///
+/// update_info: The update info of both dimensions
+///
+/// dim: The dimension to get
+fn single(update_info: &mut extent::ExtentUpdate, dim: extent::Dim) -> &mut extent::ExtentUpdateSingle {
+    match dim {
+        extent::Dim::X => &mut update_info.x,
+        extent::Dim::Y => &mut update_info.y,
+    }
+}
+
+/// Identifies a timeline added to a timeline controller
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimelineId(usize);
+
+/// Holds all the running timelines of an event loop, they are advanced every frame before the views are updated
+#[derive(Debug, Default)]
+pub struct TimelineController {
+    /// The running timelines in the order they were added
+    timelines: Vec<(TimelineId, Timeline)>,
+    /// The id given to the next timeline
+    next_id: usize,
+}
+
+impl TimelineController {
+    /// Adds a timeline, it starts at the next frame
+    ///
+    /// # Parameters
+    ///
+    /// timeline: The timeline to play
+    pub fn add(&mut self, timeline: Timeline) -> TimelineId {
+        let id = TimelineId(self.next_id);
+        self.next_id += 1;
+        self.timelines.push((id, timeline));
+
+        id
+    }
+
+    /// Stops a timeline without running its completion callbacks, the properties keep their current values.
+    /// Returns false if the timeline is already done
+    ///
+    /// # Parameters
+    ///
+    /// id: The timeline to stop
+    pub fn cancel(&mut self, id: TimelineId) -> bool {
+        let len = self.timelines.len();
+        self.timelines.retain(|(own, _)| *own != id);
+
+        self.timelines.len() != len
+    }
+
+    /// Checks if a timeline is still running
+    ///
+    /// # Parameters
+    ///
+    /// id: The timeline to check
+    pub fn is_running(&self, id: TimelineId) -> bool {
+        self.timelines.iter().any(|(own, _)| *own == id)
+    }
+
+    /// Checks if no timelines are running
+    pub fn is_empty(&self) -> bool {
+        self.timelines.is_empty()
+    }
+
+    /// Sets all the animated properties to their values at a frame time.
+    /// Returns the timelines which are done, their callbacks must be run once the controller is no longer borrowed so they can add new timelines.
+    /// Timelines of dropped views are removed without running their callbacks
+    ///
+    /// # Parameters
+    ///
+    /// time: The frame time
+    pub(crate) fn advance(&mut self, time: Instant) -> Vec<Timeline> {
+        self.timelines.retain(|(_, timeline)| !timeline.is_orphaned());
+
+        let mut done = Vec::new();
+        let mut pos = 0;
+        while pos < self.timelines.len() {
+            if self.timelines[pos].1.advance(time) {
+                done.push(self.timelines.remove(pos).1);
+            } else {
+                pos += 1;
+            }
+        }
+
+        done
+    }
+}
+
+/// Runs the completion callbacks of timelines which are done
+///
+/// # Parameters
+///
+/// timelines: The timelines returned by advancing a timeline controller
+pub(crate) fn complete(timelines: Vec<Timeline>) {
+    for mut timeline in timelines {
+        timeline.complete();
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum TimelineError {
+    #[error("The value {:?} cannot be used for the property {:?}", .1, .0)]
+    WrongValue(Property, Value),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{gen_set_update_info, gen_event_loop};
+    use crate::{view::children, theme::{Theme, StyleRule}, clock::ManualClock};
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn keyframes() {
+        let view = View::new(gen_set_update_info(0.0, 0.5), None);
+        let mut timeline = Timeline::new(&view);
+        timeline.add_keyframe(Property::Opacity, Keyframe::new(millis(100), Value::Number(0.0), Easing::Linear)).unwrap();
+        timeline.add_keyframe(Property::Opacity, Keyframe::new(millis(0), Value::Number(1.0), Easing::Linear)).unwrap();
+        timeline.add_keyframe(Property::Background, Keyframe::new(millis(200), Value::Color(Color::new(0, 0, 200, 255)), Easing::EaseIn)).unwrap();
+        assert_eq!(Err(TimelineError::WrongValue(Property::TextColor, Value::Number(1.0))), timeline.add_keyframe(Property::TextColor, Keyframe::new(millis(0), Value::Number(1.0), Easing::Linear)));
+        assert_eq!(millis(200), timeline.get_duration());
+
+        // The keyframes are sorted and the value holds after the last keyframe
+        timeline.apply(millis(50));
+        assert_eq!(0.5, view.get_style().opacity);
+        assert_eq!(Some(Color::new(0, 0, 200, 255)), view.get_style().background);
+        timeline.apply(millis(150));
+        assert_eq!(0.0, view.get_style().opacity);
+
+        // Extent properties are written to the update info
+        timeline.add_keyframe(Property::OffsetRel(extent::Dim::X), Keyframe::new(millis(0), Value::Number(0.0), Easing::Linear)).unwrap();
+        timeline.add_keyframe(Property::OffsetRel(extent::Dim::X), Keyframe::new(millis(100), Value::Number(0.5), Easing::Linear)).unwrap();
+        timeline.apply(millis(50));
+        assert_eq!(0.25, view.get_extent_controller().borrow().get_update_info().x.offset_rel);
+        assert_eq!(0.0, view.get_extent_controller().borrow().get_update_info().y.offset_rel);
+    }
+
+    #[test]
+    fn repeat() {
+        let view = View::new(gen_set_update_info(0.0, 0.5), None);
+        let mut timeline = Timeline::new(&view);
+        timeline.add_keyframe(Property::Opacity, Keyframe::new(millis(0), Value::Number(0.0), Easing::Linear)).unwrap();
+        timeline.add_keyframe(Property::Opacity, Keyframe::new(millis(100), Value::Number(1.0), Easing::Linear)).unwrap();
+        timeline.set_delay(millis(50));
+        timeline.set_repeat(Repeat::Count(2));
+        timeline.set_ping_pong(true);
+
+        // Nothing changes during the delay
+        let start = Instant::now();
+        assert!(!timeline.advance(start));
+        assert!(!timeline.advance(start + millis(40)));
+        assert_eq!(1.0, view.get_style().opacity);
+
+        // The second iteration goes backwards and the timeline ends where it started
+        assert!(!timeline.advance(start + millis(75)));
+        assert_eq!(0.25, view.get_style().opacity);
+        assert!(!timeline.advance(start + millis(175)));
+        assert_eq!(0.75, view.get_style().opacity);
+        assert!(timeline.advance(start + millis(300)));
+        assert_eq!(0.0, view.get_style().opacity);
+
+        // A timeline repeating forever never ends
+        timeline.set_repeat(Repeat::Forever);
+        timeline.set_ping_pong(false);
+        assert!(!timeline.advance(start + millis(10_075)));
+        assert_eq!(0.25, view.get_style().opacity);
+    }
+
+    #[test]
+    fn controller() {
+        let root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        let child = View::new(gen_set_update_info(0.0, 0.5), Some(Rc::clone(&scheduler)));
+        let mut controller = TimelineController::default();
+
+        let completed = Rc::new(RefCell::new(0));
+        let mut timeline = Timeline::new(&root);
+        timeline.add_keyframe(Property::Opacity, Keyframe::new(millis(100), Value::Number(0.5), Easing::Linear)).unwrap();
+        let counter = Rc::clone(&completed);
+        timeline.add_completion_callback(move || *counter.borrow_mut() += 1);
+        let first = controller.add(timeline);
+        let mut timeline = Timeline::new(&child);
+        timeline.add_keyframe(Property::Opacity, Keyframe::new(millis(100), Value::Number(0.5), Easing::Linear)).unwrap();
+        let counter = Rc::clone(&completed);
+        timeline.add_completion_callback(move || *counter.borrow_mut() += 10);
+        let second = controller.add(timeline);
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+
+        let start = Instant::now();
+        assert!(controller.advance(start).is_empty());
+        assert!(controller.is_running(first) && controller.is_running(second));
+
+        // Cancelling does not run the callbacks
+        assert!(controller.cancel(second));
+        assert!(!controller.cancel(second));
+        complete(controller.advance(start + millis(100)));
+        assert_eq!(1, *completed.borrow());
+        assert!(!controller.is_running(first));
+        assert!(controller.is_empty());
+        assert_eq!(0.5, root.get_style().opacity);
+
+        // A timeline of a dropped view is removed
+        let view = View::new(gen_set_update_info(0.0, 0.5), None);
+        let id = controller.add(Timeline::new(&view));
+        drop(view);
+        assert!(controller.advance(start).is_empty());
+        assert!(!controller.is_running(id));
+    }
+
+    #[test]
+    fn deleted() {
+        let mut root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        let child = View::new(gen_set_update_info(0.0, 0.5), Some(Rc::clone(&scheduler)));
+        let mut controller = TimelineController::default();
+        for property in [Property::Opacity, Property::OffsetRel(extent::Dim::X)] {
+            let mut timeline = Timeline::new(&child);
+            timeline.add_keyframe(property, Keyframe::new(millis(100), Value::Number(0.5), Easing::Linear)).unwrap();
+            timeline.set_repeat(Repeat::Forever);
+            controller.add(timeline);
+        }
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+        root.resolve_children();
+        let start = Instant::now();
+        assert!(controller.advance(start).is_empty());
+
+        // Both timelines share the controllers of the view but are removed once it is deleted
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(0)).unwrap();
+        root.resolve_children();
+        assert!(controller.advance(start + millis(50)).is_empty());
+        assert!(controller.is_empty());
+    }
+
+    #[test]
+    fn theme() {
+        let clock = ManualClock::new();
+        let mut event_loop = gen_event_loop(&[gen_set_update_info(0.0, 0.5)]);
+        event_loop.set_clock(Rc::new(clock.clone()));
+        let rule = StyleRule { properties: style::StyleProperties { background: Some(Color::BLUE), opacity: Some(1.0), ..Default::default() }, ..Default::default() };
+        event_loop.get_theme_controller().borrow_mut().set_theme(Some(Theme { rules: vec![rule], ..Default::default() }));
+        event_loop.run_frame(&mut |_, _, _| ());
+
+        let mut timeline = Timeline::new(&event_loop.get_root().get_children()[0]);
+        timeline.add_keyframe(Property::Opacity, Keyframe::new(millis(100), Value::Number(0.5), Easing::Linear)).unwrap();
+        let id = event_loop.get_timeline_controller().borrow_mut().add(timeline);
+        event_loop.run_frame(&mut |_, _, _| ());
+        clock.advance(millis(100));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(!event_loop.get_timeline_controller().borrow().is_running(id));
+
+        // The final value stays on top of the theme when the view is restyled
+        let style = event_loop.get_root().get_children()[0].get_style_controller();
+        style.borrow_mut().add_class("restyled");
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!(0.5, style.borrow().get_style().opacity);
+        assert_eq!(Some(Color::BLUE), style.borrow().get_style().background);
+
+        // Clearing the animated properties shows the theme again
+        style.borrow_mut().set_animated(style::StyleProperties::default());
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!(1.0, event_loop.get_root().get_children()[0].get_style().opacity);
+    }
+}
@@ -0,0 +1,92 @@
+use std::{cell::Cell, fmt, rc::Rc, time::{Duration, Instant}};
+
+/// A source of the current time, every time-dependent part of an event loop reads the time from its clock
+pub trait Clock: fmt::Debug {
+    /// Gets the current time
+    fn now(&self) -> Instant;
+}
+
+/// The clock of the system, this is the default clock of an event loop
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock which only moves when it is told to, animations and timers can then be tested frame by frame without sleeping.
+/// Clones share the same time so a test can keep a clone to advance the clock given to an event loop
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManualClock {
+    /// The current time
+    time: Rc<Cell<Instant>>,
+}
+
+impl ManualClock {
+    /// Creates a manual clock starting at the current time of the system
+    pub fn new() -> Self {
+        Self::starting_at(Instant::now())
+    }
+
+    /// Creates a manual clock starting at a time
+    ///
+    /// # Parameters
+    ///
+    /// time: The time to start at
+    pub fn starting_at(time: Instant) -> Self {
+        Self { time: Rc::new(Cell::new(time)) }
+    }
+
+    /// Moves the clock forward
+    ///
+    /// # Parameters
+    ///
+    /// duration: The time to move forward by
+    pub fn advance(&self, duration: Duration) {
+        self.time.set(self.time.get() + duration);
+    }
+
+    /// Moves the clock to a time, it may not move backwards so earlier times are ignored
+    ///
+    /// # Parameters
+    ///
+    /// time: The new time
+    pub fn set(&self, time: Instant) {
+        self.time.set(self.time.get().max(time));
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.time.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual() {
+        let clock = ManualClock::new();
+        let shared = clock.clone();
+        let start = clock.now();
+        assert_eq!(start, clock.now());
+
+        // Clones move together and the clock never goes backwards
+        shared.advance(Duration::from_millis(16));
+        assert_eq!(start + Duration::from_millis(16), clock.now());
+        clock.set(start);
+        assert_eq!(start + Duration::from_millis(16), shared.now());
+        clock.set(start + Duration::from_secs(1));
+        assert_eq!(start + Duration::from_secs(1), shared.now());
+    }
+}
@@ -0,0 +1,553 @@
+use crate::{view::{View, children, extent, style}, render};
+use std::{cell::RefCell, rc::{Rc, Weak}, fmt};
+
+/// The function run when a dragged view was dropped
+type Callback = Rc<RefCell<Box<dyn FnMut(&DropEvent)>>>;
+
+/// The distance in pixels the pointer has to move before a press turns into a drag
+const DEFAULT_THRESHOLD: f32 = 4.0;
+
+/// Lets the children of a view be reordered by dragging them with the primary mouse button along a dimension.
+/// The drop position is found from the centers of the siblings and a drop which would break the references of the siblings is rejected
+pub struct Reorder {
+    /// The owner token of the parent, it is used to find the parent and the reorder is removed once the parent is dropped
+    owner: Weak<()>,
+    /// The children scheduler of the parent, it is used to move the children
+    parent: Weak<RefCell<children::ChildrenScheduler>>,
+    /// The dimension the children are dragged along
+    dim: extent::Dim,
+    /// The distance in pixels the pointer has to move before the drag starts
+    threshold: f32,
+    /// The functions to run after a child was dropped
+    callbacks: Vec<Callback>,
+}
+
+impl Reorder {
+    /// Creates a reorder for the children of a view, it stops working once the view is dropped
+    ///
+    /// # Parameters
+    ///
+    /// parent: The view whose children can be reordered
+    ///
+    /// dim: The dimension the children are dragged along, the children should be laid out along it
+    pub fn new(parent: &View, dim: extent::Dim) -> Self {
+        Self { owner: parent.get_owner(), parent: Rc::downgrade(&parent.get_children_scheduler()), dim, threshold: DEFAULT_THRESHOLD, callbacks: Vec::new() }
+    }
+
+    /// Gets the distance in pixels the pointer has to move before the drag starts
+    pub fn get_threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    /// Sets the distance in pixels the pointer has to move before the drag starts, a shorter movement is a normal press
+    ///
+    /// # Parameters
+    ///
+    /// threshold: The new distance
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.threshold = threshold;
+    }
+
+    /// Adds a function to run after a child was dropped at a new position, it gets whether the child was moved or why the drop was rejected.
+    /// A move is scheduled already and takes effect at the next update
+    ///
+    /// # Parameters
+    ///
+    /// callback: The function to run
+    pub fn add_drop_callback<F: FnMut(&DropEvent) + 'static>(&mut self, callback: F) {
+        self.callbacks.push(Rc::new(RefCell::new(Box::new(callback))));
+    }
+
+    /// Checks if the parent has been dropped
+    fn is_orphaned(&self) -> bool {
+        self.owner.strong_count() == 0
+    }
+}
+
+impl fmt::Debug for Reorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reorder")
+            .field("dim", &self.dim)
+            .field("threshold", &self.threshold)
+            .field("callbacks", &self.callbacks.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Identifies a reorder added to a drag controller
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReorderId(usize);
+
+/// Where a dragged child would be dropped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DropTarget {
+    /// The reorder the child belongs to
+    pub reorder: ReorderId,
+    /// The position of the child before the drag
+    pub from: usize,
+    /// The position the child would be moved to, it is the position after the child is removed from the list
+    pub to: usize,
+    /// False if the move would break the references of the siblings, the child then goes back when it is released
+    pub accepted: bool,
+}
+
+/// What happened to a child which was dropped at a new position
+#[derive(Clone, Debug)]
+pub enum DropEvent {
+    /// The child was moved, the move takes effect at the next update
+    Moved {
+        /// The position of the child before the drag
+        from: usize,
+        /// The new position of the child
+        to: usize,
+    },
+    /// The move would break the references of the children so the child went back to its position
+    Rejected {
+        /// The position of the child
+        from: usize,
+        /// The position the child was dropped at
+        to: usize,
+        /// Why the move was rejected
+        error: children::ValidateError,
+    },
+}
+
+/// A child which is pressed or dragged
+#[derive(Debug)]
+struct Drag {
+    /// Where the child would be dropped
+    target: DropTarget,
+    /// True once the pointer moved past the threshold
+    dragging: bool,
+    /// The position of the pointer when the child was pressed
+    start: (f32, f32),
+    /// The extent controller of the child
+    extent: Rc<RefCell<extent::ExtentController>>,
+    /// The style controller of the child
+    style: Rc<RefCell<style::StyleController>>,
+    /// The update info of the child before the drag, the child is moved by offsetting it
+    update_info: extent::ExtentUpdate,
+    /// The size in pixels of the area the children are placed in along the dimension
+    size: f32,
+    /// The centers in pixels of all the children along the dimension when the child was pressed
+    centers: Vec<f32>,
+}
+
+/// Holds all the reorders of an event loop and the child being dragged, only a single child is dragged at a time
+#[derive(Debug, Default)]
+pub struct DragController {
+    /// The reorders which are active
+    reorders: Vec<(ReorderId, Reorder)>,
+    /// The child which is pressed or dragged, None if there is none
+    drag: Option<Drag>,
+    /// The id given to the next reorder
+    next_id: usize,
+}
+
+impl DragController {
+    /// Adds a reorder, its children can be dragged from the next press on
+    ///
+    /// # Parameters
+    ///
+    /// reorder: The reorder to add
+    pub fn add(&mut self, reorder: Reorder) -> ReorderId {
+        let id = ReorderId(self.next_id);
+        self.next_id += 1;
+        self.reorders.push((id, reorder));
+
+        id
+    }
+
+    /// Removes a reorder, a child of it which is being dragged goes back to its position.
+    /// Returns false if the reorder was already removed
+    ///
+    /// # Parameters
+    ///
+    /// id: The reorder to remove
+    pub fn remove(&mut self, id: ReorderId) -> bool {
+        if self.drag.as_ref().is_some_and(|drag| drag.target.reorder == id) {
+            self.cancel();
+        }
+
+        let len = self.reorders.len();
+        self.reorders.retain(|(own, _)| *own != id);
+
+        self.reorders.len() != len
+    }
+
+    /// Checks if a child is being dragged
+    pub fn is_dragging(&self) -> bool {
+        self.drag.as_ref().is_some_and(|drag| drag.dragging)
+    }
+
+    /// Gets where the dragged child would be dropped, None if no child is being dragged
+    pub fn get_drop_target(&self) -> Option<DropTarget> {
+        self.drag.as_ref().filter(|drag| drag.dragging).map(|drag| drag.target)
+    }
+
+    /// Stops the drag, the child goes back to its position without being moved
+    pub fn cancel(&mut self) {
+        if let Some(drag) = self.drag.take() {
+            drag.finish();
+        }
+    }
+
+    /// Starts pressing the child of a reorder below the pointer, the reorder with the deepest parent is used
+    ///
+    /// # Parameters
+    ///
+    /// root: The root view
+    ///
+    /// pointer: The position of the pointer in pixels of the window
+    fn press(&mut self, root: &View, pointer: (f32, f32)) {
+        self.cancel();
+        self.reorders.retain(|(_, reorder)| !reorder.is_orphaned());
+
+        let mut best: Option<(usize, Drag)> = None;
+        for (id, reorder) in &self.reorders {
+            let parent = match find(root, &reorder.owner, 0) {
+                Some(parent) => parent,
+                None => continue,
+            };
+            if best.as_ref().is_some_and(|(depth, _)| *depth >= parent.1) {
+                continue;
+            }
+
+            // The youngest child is drawn on top so it is found first
+            let (parent, depth) = parent;
+            let children = parent.get_children();
+            let pressed = children.iter().rposition(|child| child.get_rect().is_some_and(|rect| rect.contains(pointer.0, pointer.1)));
+            let (pos, content) = match (pressed, parent.get_content_rect()) {
+                (Some(pos), Some(content)) => (pos, content),
+                _ => continue,
+            };
+
+            let centers = children.iter().map(|child| child.get_rect().map_or(0.0, |rect| get_center(&rect, reorder.dim))).collect();
+            let size = match reorder.dim {
+                extent::Dim::X => content.w,
+                extent::Dim::Y => content.h,
+            };
+            let child = &children[pos];
+            let extent = child.get_extent_controller();
+            let update_info = extent.borrow().get_update_info();
+            let target = DropTarget { reorder: *id, from: pos, to: pos, accepted: true };
+            best = Some((depth, Drag { target, dragging: false, start: pointer, extent, style: child.get_style_controller(), update_info, size, centers }));
+        }
+
+        self.drag = best.map(|(_, drag)| drag);
+    }
+
+    /// Moves the pressed child with the pointer once it moved past the threshold and finds the drop position
+    ///
+    /// # Parameters
+    ///
+    /// pointer: The position of the pointer in pixels of the window
+    fn moved(&mut self, pointer: (f32, f32)) {
+        let (drag, reorder) = match &mut self.drag {
+            Some(drag) => match self.reorders.iter().find(|(id, _)| *id == drag.target.reorder) {
+                Some((_, reorder)) => (drag, reorder),
+                None => return,
+            },
+            None => return,
+        };
+        let scheduler = match reorder.parent.upgrade() {
+            Some(scheduler) => scheduler,
+            None => return,
+        };
+
+        // A short movement is still a press
+        let delta = (pointer.0 - drag.start.0, pointer.1 - drag.start.1);
+        if !drag.dragging {
+            if delta.0.hypot(delta.1) < reorder.threshold {
+                return;
+            }
+            drag.dragging = true;
+            drag.style.borrow_mut().set_state(style::State::DRAGGED, true);
+        }
+
+        // The child follows the pointer along the dimension
+        let (delta, mut update_info) = (match reorder.dim { extent::Dim::X => delta.0, extent::Dim::Y => delta.1 }, drag.update_info);
+        match reorder.dim {
+            extent::Dim::X => update_info.x.offset_abs += delta / drag.size.max(1.0),
+            extent::Dim::Y => update_info.y.offset_abs += delta / drag.size.max(1.0),
+        }
+        drag.extent.borrow_mut().set_animated_update_info(update_info);
+
+        // The child goes behind every sibling whose center it passed
+        let center = drag.centers[drag.target.from] + delta;
+        let to = drag.centers.iter().enumerate().filter(|(pos, other)| *pos != drag.target.from && **other < center).count();
+        let accepted = scheduler.borrow().check_operation(&children::ChildrenScheduleOperation::Move(drag.target.from, to)).is_ok();
+        drag.target.to = to;
+        drag.target.accepted = accepted;
+        drag.style.borrow_mut().set_state(style::State::REJECTED, !accepted);
+    }
+
+    /// Drops the dragged child, if the drop is accepted the child is moved.
+    /// Returns the callbacks to run with what happened to the child, they must be run once the controller is no longer borrowed
+    fn release(&mut self) -> Option<(Vec<Callback>, DropEvent)> {
+        let drag = self.drag.take()?;
+        let target = drag.target;
+        let dragging = drag.dragging;
+        drag.finish();
+        if !dragging || target.from == target.to {
+            return None;
+        }
+
+        // The children may have changed since the drop target was checked so the move can still fail
+        let reorder = self.reorders.iter().find(|(id, _)| *id == target.reorder).map(|(_, reorder)| reorder)?;
+        let scheduler = reorder.parent.upgrade()?;
+        let operation = children::ChildrenScheduleOperation::Move(target.from, target.to);
+        let result = match target.accepted {
+            true => scheduler.borrow_mut().push_operation(operation),
+            false => scheduler.borrow().check_operation(&operation),
+        };
+        let event = match result {
+            Ok(()) if target.accepted => DropEvent::Moved { from: target.from, to: target.to },
+            Ok(()) => return None,
+            Err(error) => DropEvent::Rejected { from: target.from, to: target.to, error },
+        };
+
+        Some((reorder.callbacks.clone(), event))
+    }
+}
+
+impl Drag {
+    /// Puts a dragged child back to where its update info places it and removes the drag states
+    fn finish(self) {
+        if !self.dragging {
+            return;
+        }
+
+        self.extent.borrow_mut().set_update_info(self.update_info);
+        self.style.borrow_mut().set_state(style::State::DRAGGED | style::State::REJECTED, false);
+    }
+}
+
+/// Starts pressing the child of a reorder below the pointer
+///
+/// # Parameters
+///
+/// controller: The drag controller
+///
+/// root: The root view
+///
+/// pointer: The position of the pointer in pixels of the window
+pub(crate) fn press(controller: &RefCell<DragController>, root: &View, pointer: (f32, f32)) {
+    controller.borrow_mut().press(root, pointer);
+}
+
+/// Moves the pressed child with the pointer
+///
+/// # Parameters
+///
+/// controller: The drag controller
+///
+/// pointer: The position of the pointer in pixels of the window
+pub(crate) fn moved(controller: &RefCell<DragController>, pointer: (f32, f32)) {
+    controller.borrow_mut().moved(pointer);
+}
+
+/// Drops the dragged child and runs the drop callbacks if it was moved or the drop was rejected, the callbacks may change the reorders
+///
+/// # Parameters
+///
+/// controller: The drag controller, it must not be borrowed
+pub(crate) fn release(controller: &RefCell<DragController>) {
+    let dropped = controller.borrow_mut().release();
+    if let Some((callbacks, event)) = dropped {
+        for callback in callbacks {
+            (callback.borrow_mut())(&event);
+        }
+    }
+}
+
+/// Finds the view with an owner token in a tree and its depth
+///
+/// # Parameters
+///
+/// view: The view to start at
+///
+/// owner: The owner token of the view to find
+///
+/// depth: The depth of the view to start at
+fn find<'a>(view: &'a View, owner: &Weak<()>, depth: usize) -> Option<(&'a View, usize)> {
+    if view.get_owner().ptr_eq(owner) {
+        return Some((view, depth));
+    }
+
+    view.get_children().iter().find_map(|child| find(child, owner, depth + 1))
+}
+
+/// Gets the center of a rectangle along a dimension
+///
+/// # Parameters
+///
+/// rect: The rectangle
+///
+/// dim: The dimension
+fn get_center(rect: &render::Rect, dim: extent::Dim) -> f32 {
+    match dim {
+        extent::Dim::X => rect.x + rect.w / 2.0,
+        extent::Dim::Y => rect.y + rect.h / 2.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{gen_locate, gen_set_update_info, gen_event_loop, gen_cursor_moved, gen_mouse_input};
+    use std::time::Instant;
+    use winit::event::ElementState;
+
+    /// Creates a root with four children in a column, all but the first are placed below the previous sibling
+    fn gen_root() -> Box<View> {
+        let mut root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        for pos in 0..4 {
+            let y = match pos {
+                0 => extent::PositionType::Set(0.0),
+                _ => extent::PositionType::Anchor(extent::AnchorPoint { ref_view: extent::RefView::Prev, ref_point: 1.0 }),
+            };
+            let x = gen_locate(extent::PositionType::Set(0.0), extent::SizeType::Set(1.0));
+            let y = gen_locate(y, extent::SizeType::Set(0.25));
+            let child = View::new(extent::ExtentUpdate { x, y }, Some(Rc::clone(&scheduler)));
+            scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+        }
+        update(&mut root);
+
+        root
+    }
+
+    fn update(root: &mut View) {
+        root.update((100.0, 400.0), Instant::now(), &mut render::Damage::new()).unwrap();
+    }
+
+    #[test]
+    fn reorder() {
+        let mut root = gen_root();
+        let controller = RefCell::new(DragController::default());
+        let drops = Rc::new(RefCell::new(Vec::new()));
+        let recorded = Rc::clone(&drops);
+        let mut reorder = Reorder::new(&root, extent::Dim::Y);
+        reorder.add_drop_callback(move |event| if let DropEvent::Moved { from, to } = event {
+            recorded.borrow_mut().push((*from, *to));
+        });
+        let id = controller.borrow_mut().add(reorder);
+        let second = root.get_children()[1].get_extent_controller();
+
+        // A short movement is only a press
+        press(&controller, &root, (50.0, 150.0));
+        moved(&controller, (50.0, 152.0));
+        assert!(!controller.borrow().is_dragging());
+        assert_eq!(None, controller.borrow().get_drop_target());
+
+        // The child follows the pointer and goes behind the siblings whose centers it passed
+        moved(&controller, (50.0, 260.0));
+        assert_eq!(Some(DropTarget { reorder: id, from: 1, to: 2, accepted: true }), controller.borrow().get_drop_target());
+        assert!(root.get_children()[1].get_style_controller().borrow().get_state().contains(style::State::DRAGGED));
+        update(&mut root);
+        assert_eq!((0.0, 0.525, 1.0, 0.25), root.get_children()[1].get_extent());
+
+        // Releasing moves it and the list closes up around it
+        release(&controller);
+        assert_eq!(vec![(1, 2)], *drops.borrow());
+        update(&mut root);
+        assert!(Rc::ptr_eq(&second, &root.get_children()[2].get_extent_controller()));
+        assert_eq!((0.0, 0.5, 1.0, 0.25), root.get_children()[2].get_extent());
+        assert_eq!((0.0, 0.25, 1.0, 0.25), root.get_children()[1].get_extent());
+        assert!(root.get_children()[2].get_style_controller().borrow().get_state().is_empty());
+    }
+
+    #[test]
+    fn deleted() {
+        let mut root = gen_root();
+        let controller = RefCell::new(DragController::default());
+        controller.borrow_mut().add(Reorder::new(&root.get_children()[3], extent::Dim::Y));
+        let scheduler = root.get_children()[3].get_children_scheduler();
+
+        // The reorder is removed once its parent is deleted even while its children scheduler is still held
+        root.get_children_scheduler().borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(3)).unwrap();
+        update(&mut root);
+        press(&controller, &root, (50.0, 350.0));
+        assert!(controller.borrow().drag.is_none());
+        assert!(controller.borrow().reorders.is_empty());
+        drop(scheduler);
+    }
+
+    #[test]
+    fn reject() {
+        let mut root = gen_root();
+        let controller = RefCell::new(DragController::default());
+        let rejected = Rc::new(RefCell::new(Vec::new()));
+        let recorded = Rc::clone(&rejected);
+        let mut reorder = Reorder::new(&root, extent::Dim::Y);
+        reorder.add_drop_callback(move |event| match event {
+            DropEvent::Rejected { from, to, error } => recorded.borrow_mut().push((*from, *to, error.to_string())),
+            DropEvent::Moved { .. } => panic!("The drop should be rejected"),
+        });
+        controller.borrow_mut().add(reorder);
+
+        // The children reference the previous sibling so none of them can become the first one
+        press(&controller, &root, (50.0, 150.0));
+        moved(&controller, (50.0, 40.0));
+        let target = controller.borrow().get_drop_target().unwrap();
+        assert_eq!((1, 0, false), (target.from, target.to, target.accepted));
+        assert!(root.get_children()[1].get_style_controller().borrow().get_state().contains(style::State::DRAGGED | style::State::REJECTED));
+
+        // Moving back to a valid position accepts the drop again
+        moved(&controller, (50.0, 160.0));
+        assert!(controller.borrow().get_drop_target().unwrap().accepted);
+        assert!(!root.get_children()[1].get_style_controller().borrow().get_state().contains(style::State::REJECTED));
+
+        // A rejected drop puts the child back and tells the owner why
+        moved(&controller, (50.0, 40.0));
+        release(&controller);
+        assert!(!root.get_children_scheduler().borrow().has_operations());
+        assert_eq!(vec![(1, 0, children::ValidateError::NoPrev(1).to_string())], *rejected.borrow());
+        update(&mut root);
+        assert_eq!((0.0, 0.25, 1.0, 0.25), root.get_children()[1].get_extent());
+        assert!(root.get_children()[1].get_style_controller().borrow().get_state().is_empty());
+
+        // Neither can the first child be moved down since the second would become the first
+        press(&controller, &root, (50.0, 50.0));
+        moved(&controller, (50.0, 180.0));
+        assert!(!controller.borrow().get_drop_target().unwrap().accepted);
+        controller.borrow_mut().cancel();
+        assert!(!controller.borrow().is_dragging());
+
+        // An accepted drop fails if the children changed before the release
+        rejected.borrow_mut().clear();
+        press(&controller, &root, (50.0, 150.0));
+        moved(&controller, (50.0, 260.0));
+        assert!(controller.borrow().get_drop_target().unwrap().accepted);
+        let scheduler = root.get_children_scheduler();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(3)).unwrap();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(2)).unwrap();
+        release(&controller);
+        assert_eq!(vec![(1, 2, children::ValidateError::OutOfRange(2, 1).to_string())], *rejected.borrow());
+        update(&mut root);
+        assert_eq!(2, root.get_children().len());
+    }
+
+    #[test]
+    fn event_loop() {
+        // The second child sits below the first one
+        let mut event_loop = gen_event_loop(&[gen_set_update_info(0.0, 0.5), gen_set_update_info(0.5, 0.5)]);
+        event_loop.run_frame(&mut |_, _, _| ());
+        let second_extent = event_loop.get_root().get_children()[1].get_extent_controller();
+        event_loop.get_drag_controller().borrow_mut().add(Reorder::new(event_loop.get_root(), extent::Dim::Y));
+
+        // Dragging the second child above the first one swaps them
+        event_loop.get_backend_mut().push_window_event(gen_cursor_moved(75.0, 75.0));
+        event_loop.get_backend_mut().push_window_event(gen_mouse_input(ElementState::Pressed));
+        event_loop.get_backend_mut().push_window_event(gen_cursor_moved(75.0, 10.0));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(event_loop.get_drag_controller().borrow().is_dragging());
+        assert_eq!(0.5 - 0.65, event_loop.get_root().get_children()[1].get_extent().1);
+        event_loop.get_backend_mut().push_window_event(gen_mouse_input(ElementState::Released));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(!event_loop.get_drag_controller().borrow().is_dragging());
+        assert!(Rc::ptr_eq(&second_extent, &event_loop.get_root().get_children()[0].get_extent_controller()));
+        assert_eq!((0.5, 0.5, 0.5, 0.5), event_loop.get_root().get_children()[0].get_extent());
+    }
+}
@@ -0,0 +1,27 @@
+use winit;
+
+/// A backend which produces events for the event loop
+pub trait Backend<T: 'static> {
+    /// The target given to the event handler together with each event, it is used to create windows and similar
+    type Target;
+
+    /// Runs the backend and calls the event handler for every event, this may never return
+    /// 
+    /// # Parameters
+    /// 
+    /// event_handler: The event handler to run for every event
+    fn run<F>(self, event_handler: F)
+    where
+        F: 'static + FnMut(winit::event::Event<'_, T>, &Self::Target, &mut winit::event_loop::ControlFlow);
+}
+
+impl<T: 'static> Backend<T> for winit::event_loop::EventLoop<T> {
+    type Target = winit::event_loop::EventLoopWindowTarget<T>;
+
+    fn run<F>(self, event_handler: F)
+    where
+        F: 'static + FnMut(winit::event::Event<'_, T>, &Self::Target, &mut winit::event_loop::ControlFlow),
+    {
+        winit::event_loop::EventLoop::run(self, event_handler)
+    }
+}
@@ -0,0 +1,504 @@
+use winit;
+use std::{collections::VecDeque, time::Instant};
+use super::Backend;
+use crate::clock;
+
+/// The raw id of the virtual window, it is never confused with a real window since there are no real windows
+const WINDOW_ID: u64 = 0;
+
+/// A backend without any display, it has a single virtual window and runs frames on demand from a queue of synthetic events.
+/// This allows the views to be used in tests and on servers
+#[derive(Debug)]
+pub struct Headless<T: 'static> {
+    /// The target given to the event handler
+    target: HeadlessTarget,
+    /// The events to send during the next frame, the first element is sent first
+    queue: VecDeque<winit::event::Event<'static, T>>,
+    /// The control flow set by the event handler
+    control_flow: winit::event_loop::ControlFlow,
+    /// True if the first frame has been run
+    started: bool,
+    /// The clock which is moved forward instead of sleeping when running, None to sleep
+    clock: Option<clock::ManualClock>,
+}
+
+impl<T: 'static> Headless<T> {
+    /// Creates a new headless backend, a resize event for the virtual window is queued so the views are laid out at the correct size
+    /// 
+    /// # Parameters
+    /// 
+    /// width: The width of the virtual window in pixels
+    /// 
+    /// height: The height of the virtual window in pixels
+    pub fn new(width: u32, height: u32) -> Self {
+        let window_id = winit::window::WindowId::from(WINDOW_ID);
+        let target = HeadlessTarget { window_id, window_size: winit::dpi::PhysicalSize::new(width, height) };
+        let queue = VecDeque::new();
+        let control_flow = winit::event_loop::ControlFlow::default();
+
+        let mut headless = Self { target, queue, control_flow, started: false, clock: None };
+        headless.push_window_event(winit::event::WindowEvent::Resized(headless.target.window_size));
+
+        headless
+    }
+
+    /// Gets the target given to the event handler
+    pub fn get_target(&self) -> &HeadlessTarget {
+        &self.target
+    }
+
+    /// Gets the control flow set by the event handler during the last frame
+    pub fn get_control_flow(&self) -> winit::event_loop::ControlFlow {
+        self.control_flow
+    }
+
+    /// Gets the clock which is moved forward instead of sleeping when running, None if the backend sleeps
+    pub fn get_clock(&self) -> Option<&clock::ManualClock> {
+        self.clock.as_ref()
+    }
+
+    /// Sets the clock which is moved forward instead of sleeping when running, it should be a clone of the clock of the event loop
+    /// 
+    /// # Parameters
+    /// 
+    /// clock: The clock to move forward, None to sleep until the event loop wants the next frame
+    pub fn set_clock(&mut self, clock: Option<clock::ManualClock>) {
+        self.clock = clock;
+    }
+
+    /// Checks if there are any events waiting for the next frame
+    pub fn has_events(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    /// Queues an event for the next frame
+    /// 
+    /// # Parameters
+    /// 
+    /// event: The event to queue
+    pub fn push_event(&mut self, event: winit::event::Event<'static, T>) {
+        self.queue.push_back(event);
+    }
+
+    /// Queues an event for the virtual window
+    /// 
+    /// # Parameters
+    /// 
+    /// event: The window event to queue
+    pub fn push_window_event(&mut self, event: winit::event::WindowEvent<'static>) {
+        self.push_event(winit::event::Event::WindowEvent { window_id: self.target.window_id, event });
+    }
+
+    /// Queues a user event
+    /// 
+    /// # Parameters
+    /// 
+    /// event: The user event to queue
+    pub fn push_user_event(&mut self, event: T) {
+        self.push_event(winit::event::Event::UserEvent(event));
+    }
+
+    /// Resizes the virtual window and queues the resize event
+    /// 
+    /// # Parameters
+    /// 
+    /// width: The new width of the virtual window in pixels
+    /// 
+    /// height: The new height of the virtual window in pixels
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.target.window_size = winit::dpi::PhysicalSize::new(width, height);
+        self.push_window_event(winit::event::WindowEvent::Resized(self.target.window_size));
+    }
+
+    /// Runs a single frame, all queued events are sent followed by the events which end a frame in winit.
+    /// 
+    /// Returns the control flow set by the event handler
+    /// 
+    /// # Parameters
+    /// 
+    /// event_handler: The event handler to run for every event
+    pub fn run_frame<F>(&mut self, event_handler: &mut F) -> winit::event_loop::ControlFlow
+    where
+        F: FnMut(winit::event::Event<'_, T>, &HeadlessTarget, &mut winit::event_loop::ControlFlow),
+    {
+        // Start the frame
+        let cause = if self.started {
+            winit::event::StartCause::Poll
+        } else {
+            winit::event::StartCause::Init
+        };
+        self.started = true;
+        self.send(winit::event::Event::NewEvents(cause), event_handler);
+
+        // Send all the queued events
+        while let Some(event) = self.queue.pop_front() {
+            self.send(event, event_handler);
+        }
+
+        // Finish the frame
+        self.send(winit::event::Event::MainEventsCleared, event_handler);
+        self.send(winit::event::Event::RedrawRequested(self.target.window_id), event_handler);
+        self.send(winit::event::Event::RedrawEventsCleared, event_handler);
+
+        self.control_flow
+    }
+
+    /// Sends a single event to the event handler
+    /// 
+    /// # Parameters
+    /// 
+    /// event: The event to send
+    /// 
+    /// event_handler: The event handler to send it to
+    fn send<F>(&mut self, event: winit::event::Event<'_, T>, event_handler: &mut F)
+    where
+        F: FnMut(winit::event::Event<'_, T>, &HeadlessTarget, &mut winit::event_loop::ControlFlow),
+    {
+        let mut control_flow = self.control_flow;
+        event_handler(event, &self.target, &mut control_flow);
+
+        // Just like winit, the control flow cannot be changed once it is set to exit
+        if !matches!(self.control_flow, winit::event_loop::ControlFlow::ExitWithCode(_)) {
+            self.control_flow = control_flow;
+        }
+    }
+}
+
+impl<T: 'static> Backend<T> for Headless<T> {
+    type Target = HeadlessTarget;
+
+    /// Runs frames until the control flow is set to exit or there is nothing left to do.
+    /// 
+    /// A control flow waiting until a time moves the clock of the backend to it or sleeps until then, so pending timers and animations are finished.
+    /// The loop stops once it waits without a time and no events are queued, a repeating timer or an endless animation keeps it running until the event handler exits
+    fn run<F>(mut self, mut event_handler: F)
+    where
+        F: 'static + FnMut(winit::event::Event<'_, T>, &Self::Target, &mut winit::event_loop::ControlFlow),
+    {
+        loop {
+            match self.run_frame(&mut event_handler) {
+                winit::event_loop::ControlFlow::ExitWithCode(_) => break,
+                _ if self.has_events() => (),
+                winit::event_loop::ControlFlow::Poll => (),
+                winit::event_loop::ControlFlow::WaitUntil(deadline) => match &self.clock {
+                    Some(clock) => clock.set(deadline),
+                    None => std::thread::sleep(deadline.saturating_duration_since(Instant::now())),
+                },
+                winit::event_loop::ControlFlow::Wait => break,
+            }
+        }
+
+        self.send(winit::event::Event::LoopDestroyed, &mut event_handler);
+    }
+}
+
+/// The target given to the event handler by the headless backend
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeadlessTarget {
+    /// The id of the virtual window
+    window_id: winit::window::WindowId,
+    /// The size of the virtual window in pixels
+    window_size: winit::dpi::PhysicalSize<u32>,
+}
+
+impl HeadlessTarget {
+    /// Gets the id of the virtual window
+    pub fn get_window_id(&self) -> winit::window::WindowId {
+        self.window_id
+    }
+
+    /// Gets the size of the virtual window in pixels
+    pub fn get_window_size(&self) -> winit::dpi::PhysicalSize<u32> {
+        self.window_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{gen_locate, gen_ratio};
+    use crate::{event_loop::EventLoop, view::{View, extent, children}};
+    use std::{cell::RefCell, rc::Rc};
+
+    fn gen_root() -> View {
+        let root = View::new_root();
+        let ratio = gen_ratio(extent::PositionType::Set(0.0), extent::RatioSource::Square);
+        let locate = gen_locate(extent::PositionType::Set(0.0), extent::SizeType::Set(0.5));
+        let scheduler = root.get_children_scheduler();
+        let child = View::new(extent::ExtentUpdate { x: ratio, y: locate }, Some(Rc::clone(&scheduler)));
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+
+        *root
+    }
+
+    fn is_user_event(event: &winit::event::Event<'_, u32>) -> Option<u32> {
+        match event {
+            winit::event::Event::UserEvent(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn run_frame() {
+        let mut event_loop = EventLoop::from_backend(gen_root(), Headless::<u32>::new(200, 100)).unwrap();
+        event_loop.get_backend_mut().push_user_event(5);
+
+        let mut events = Vec::new();
+        let control_flow = event_loop.run_frame(&mut |event, target, control_flow| {
+            assert_eq!(winit::dpi::PhysicalSize::new(200, 100), target.get_window_size());
+            events.push(format!("{:?}", event));
+            if let Some(value) = is_user_event(&event) {
+                assert_eq!(5, value);
+                control_flow.set_wait();
+            }
+        });
+        assert_eq!(winit::event_loop::ControlFlow::Wait, control_flow);
+        assert_eq!(6, events.len());
+        assert!(events[0].starts_with("NewEvents(Init)"));
+        assert!(events[1].contains("Resized"));
+        assert!(events[2].starts_with("UserEvent(5)"));
+        assert!(events[3].starts_with("MainEventsCleared"));
+
+        // The child is square on the screen, half the height of a window twice as wide as it is tall
+        assert_eq!((0.0, 0.0, 0.25, 0.5), event_loop.get_root().get_children()[0].get_extent());
+
+        // Resizing lays out the views again at the next frame
+        event_loop.get_backend_mut().resize(100, 100);
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!((0.0, 0.0, 0.5, 0.5), event_loop.get_root().get_children()[0].get_extent());
+    }
+
+    #[test]
+    fn layout_error() {
+        let mut event_loop = EventLoop::from_backend(gen_root(), Headless::<u32>::new(100, 100)).unwrap();
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(event_loop.get_layout_error().is_none());
+
+        // A missing reference is kept for the user and the view keeps its extent
+        let controller = event_loop.get_root().get_children()[0].get_extent_controller();
+        let update_info = controller.borrow().get_update_info();
+        let mut update_info_invalid = update_info;
+        update_info_invalid.y.extent_type = extent::ExtentUpdateType::Locate(extent::ExtentLocate { pos: extent::PositionType::Set(0.0), size: extent::SizeType::Relative(extent::RefView::Id(3)) });
+        controller.borrow_mut().set_update_info(update_info_invalid);
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(matches!(event_loop.get_layout_error(), Some(crate::view::UpdateError::InvalidReference(path, _)) if *path == vec![0]));
+        assert_eq!((0.0, 0.0, 0.5, 0.5), event_loop.get_root().get_children()[0].get_extent());
+
+        // The error is cleared once the views can be laid out again
+        controller.borrow_mut().set_update_info(update_info);
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(event_loop.get_layout_error().is_none());
+    }
+
+    #[test]
+    fn run() {
+        let mut headless = Headless::<u32>::new(100, 100);
+        headless.push_user_event(1);
+        headless.push_user_event(2);
+        let event_loop = EventLoop::from_backend(gen_root(), headless).unwrap();
+
+        // The loop ends once all events are handled
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let received_handler = Rc::clone(&received);
+        event_loop.run(move |event, _, _| {
+            match event {
+                winit::event::Event::UserEvent(value) => received_handler.borrow_mut().push(value),
+                winit::event::Event::LoopDestroyed => received_handler.borrow_mut().push(0),
+                _ => (),
+            }
+        });
+        assert_eq!(vec![1, 2, 0], *received.borrow());
+    }
+
+    #[test]
+    fn run_pending() {
+        use crate::{animation::{Motion, Transition, Easing}, clock::{Clock, ManualClock}, timer::Timer};
+        use std::time::Duration;
+
+        let clock = ManualClock::new();
+        let start = clock.now();
+        let mut headless = Headless::<u32>::new(100, 100);
+        headless.set_clock(Some(clock.clone()));
+        let mut event_loop = EventLoop::from_backend(gen_root(), headless).unwrap();
+        event_loop.set_clock(Rc::new(clock.clone()));
+        event_loop.run_frame(&mut |_, _, _| ());
+
+        // A timer and a transition are still pending once the queue is empty
+        let fired = Rc::new(RefCell::new(None));
+        let timer_fired = Rc::clone(&fired);
+        let timer_clock = clock.clone();
+        event_loop.get_timer_controller().borrow_mut().add(Timer::after(Duration::from_millis(50), move || *timer_fired.borrow_mut() = Some(timer_clock.now())));
+        let controller = event_loop.get_root().get_children()[0].get_extent_controller();
+        let mut update_info = controller.borrow().get_update_info();
+        update_info.y.offset_abs = 0.25;
+        controller.borrow_mut().set_motion(Some(Motion::Transition(Transition::new(Duration::from_millis(100), Easing::Linear))));
+        controller.borrow_mut().set_update_info(update_info);
+
+        // The backend moves the clock to every deadline and stops once nothing is left to do
+        let end = Rc::new(RefCell::new(None));
+        let (loop_end, loop_clock) = (Rc::clone(&end), clock.clone());
+        event_loop.run(move |event, _, _| if let winit::event::Event::LoopDestroyed = event {
+            *loop_end.borrow_mut() = Some(loop_clock.now());
+        });
+        assert_eq!(Some(start + Duration::from_millis(50)), *fired.borrow());
+        let end = end.borrow().unwrap();
+        assert!(end >= start + Duration::from_millis(100) && end < start + Duration::from_millis(100) + super::super::FRAME_INTERVAL);
+        assert!(!controller.borrow().is_animating());
+    }
+
+    #[test]
+    fn idle() {
+        use winit::event_loop::ControlFlow;
+
+        let clock = crate::clock::ManualClock::new();
+        let mut event_loop = EventLoop::from_backend(gen_root(), Headless::<u32>::new(100, 100)).unwrap();
+        event_loop.set_clock(Rc::new(clock.clone()));
+        assert_eq!(ControlFlow::Wait, event_loop.run_frame(&mut |_, _, _| ()));
+        assert!(!event_loop.take_damage().is_empty());
+
+        // Nothing changed so nothing is laid out again and the loop keeps waiting
+        let frame_time = event_loop.get_frame_time();
+        assert_eq!(ControlFlow::Wait, event_loop.run_frame(&mut |_, _, _| ()));
+        assert_eq!(frame_time, event_loop.get_frame_time());
+        assert!(event_loop.get_damage().is_empty());
+
+        // A changed style, content or window is laid out and damaged again
+        let child_style = event_loop.get_root().get_children()[0].get_style_controller();
+        child_style.borrow_mut().set_style(crate::view::style::Style { opacity: 0.5, ..Default::default() });
+        clock.advance(std::time::Duration::from_millis(1));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!(frame_time + std::time::Duration::from_millis(1), event_loop.get_frame_time());
+        assert!(!event_loop.take_damage().is_empty());
+        let drawable = event_loop.get_root().get_children()[0].get_drawable_controller();
+        drawable.borrow_mut().set_drawable(Some(Rc::new(crate::render::Color::RED)));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(!event_loop.take_damage().is_empty());
+        assert!(!drawable.borrow().is_dirty());
+        event_loop.get_backend_mut().resize(50, 100);
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!((0.0, 0.0, 1.0, 0.5), event_loop.get_root().get_children()[0].get_extent());
+
+        // The user can wake the loop up earlier
+        assert_eq!(ControlFlow::Poll, event_loop.run_frame(&mut |_, _, control_flow| control_flow.set_poll()));
+        assert_eq!(ControlFlow::Wait, event_loop.run_frame(&mut |_, _, _| ()));
+
+        // Or take over the control flow entirely
+        event_loop.set_auto_control_flow(false);
+        assert!(!event_loop.get_auto_control_flow());
+        assert_eq!(ControlFlow::Poll, event_loop.run_frame(&mut |_, _, control_flow| control_flow.set_poll()));
+        assert_eq!(ControlFlow::Poll, event_loop.run_frame(&mut |_, _, _| ()));
+    }
+
+    #[test]
+    fn timers() {
+        use crate::timer::Timer;
+        use std::time::Duration;
+
+        let mut event_loop = EventLoop::from_backend(gen_root(), Headless::<u32>::new(100, 100)).unwrap();
+        event_loop.run_frame(&mut |_, _, _| ());
+
+        // The loop sleeps until the next timer
+        let timers = event_loop.get_timer_controller();
+        let long = timers.borrow_mut().add(Timer::every(Duration::from_secs(3600), || ()));
+        let control_flow = event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!(Some(control_flow), timers.borrow().get_next_deadline().map(winit::event_loop::ControlFlow::WaitUntil));
+
+        // A due timer runs before the views are updated
+        let child_style = event_loop.get_root().get_children()[0].get_style_controller();
+        let mut timer = Timer::after(Duration::ZERO, move || child_style.borrow_mut().set_style(crate::view::style::Style { opacity: 0.5, ..Default::default() }));
+        timer.set_owner(&event_loop.get_root().get_children()[0]);
+        let short = timers.borrow_mut().add(timer);
+        assert!(timers.borrow().is_running(short));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(!timers.borrow().is_running(short));
+        assert_eq!(0.5, event_loop.get_root().get_children()[0].get_style().opacity);
+        assert!(!event_loop.get_damage().is_empty());
+
+        timers.borrow_mut().cancel(long);
+        assert_eq!(winit::event_loop::ControlFlow::Wait, event_loop.run_frame(&mut |_, _, _| ()));
+    }
+
+    #[test]
+    fn timelines() {
+        use crate::animation::{Timeline, Keyframe, Property, Value, Easing};
+        use std::time::Duration;
+
+        let mut event_loop = EventLoop::from_backend(gen_root(), Headless::<u32>::new(100, 100)).unwrap();
+        let timelines = event_loop.get_timeline_controller();
+        let mut timeline = Timeline::new(event_loop.get_root());
+        timeline.add_keyframe(Property::Opacity, Keyframe::new(Duration::from_secs(3600), Value::Number(0.0), Easing::Linear)).unwrap();
+        let id = timelines.borrow_mut().add(timeline);
+
+        // Waiting is replaced so the frames keep coming while the timeline runs
+        let control_flow = event_loop.run_frame(&mut |_, _, control_flow| control_flow.set_wait());
+        assert!(event_loop.is_animating());
+        assert!(matches!(control_flow, winit::event_loop::ControlFlow::WaitUntil(deadline) if deadline <= event_loop.get_frame_time() + Duration::from_millis(17)));
+
+        // Polling is already fast enough
+        assert_eq!(winit::event_loop::ControlFlow::Poll, event_loop.run_frame(&mut |_, _, control_flow| control_flow.set_poll()));
+
+        // Once nothing runs the loop goes idle again
+        event_loop.run_frame(&mut |_, _, control_flow| control_flow.set_wait());
+        timelines.borrow_mut().cancel(id);
+        assert_eq!(winit::event_loop::ControlFlow::Wait, event_loop.run_frame(&mut |_, _, _| ()));
+        assert!(!event_loop.is_animating());
+    }
+
+    #[test]
+    fn clock() {
+        use crate::{animation::{Motion, Transition, Easing}, clock::{Clock, ManualClock}, timer::Timer};
+        use std::time::Duration;
+
+        let clock = ManualClock::new();
+        let mut event_loop = EventLoop::from_backend(gen_root(), Headless::<u32>::new(100, 100)).unwrap();
+        event_loop.set_clock(Rc::new(clock.clone()));
+        assert_eq!(clock.now(), event_loop.get_clock().now());
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!((0.0, 0.0, 0.5, 0.5), event_loop.get_root().get_children()[0].get_extent());
+
+        // Start a transition and a timer in the same frame
+        let fired = Rc::new(RefCell::new(false));
+        let timer_fired = Rc::clone(&fired);
+        event_loop.get_timer_controller().borrow_mut().add(Timer::after(Duration::from_millis(30), move || *timer_fired.borrow_mut() = true));
+        let locate = gen_locate(extent::PositionType::Set(0.5), extent::SizeType::Set(0.5));
+        let controller = event_loop.get_root().get_children()[0].get_extent_controller();
+        controller.borrow_mut().set_motion(Some(Motion::Transition(Transition::new(Duration::from_millis(100), Easing::Linear))));
+        controller.borrow_mut().set_update_info(extent::ExtentUpdate { x: locate, y: locate });
+        let start = clock.now();
+        let control_flow = event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!(start, event_loop.get_frame_time());
+        assert_eq!(winit::event_loop::ControlFlow::WaitUntil(start + super::super::FRAME_INTERVAL), control_flow);
+        assert_eq!((0.0, 0.0, 0.5, 0.5), event_loop.get_root().get_children()[0].get_extent());
+
+        // Every step shows the exact state of the animation at the time of the clock
+        clock.advance(Duration::from_millis(25));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!((0.125, 0.125, 0.5, 0.5), event_loop.get_root().get_children()[0].get_extent());
+        assert!(!*fired.borrow());
+        clock.advance(Duration::from_millis(25));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!((0.25, 0.25, 0.5, 0.5), event_loop.get_root().get_children()[0].get_extent());
+        assert!(*fired.borrow());
+
+        // Without moving the clock nothing changes
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!((0.25, 0.25, 0.5, 0.5), event_loop.get_root().get_children()[0].get_extent());
+
+        clock.advance(Duration::from_millis(50));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!((0.5, 0.5, 0.5, 0.5), event_loop.get_root().get_children()[0].get_extent());
+        assert!(!event_loop.is_animating());
+        assert_eq!(winit::event_loop::ControlFlow::Wait, event_loop.run_frame(&mut |_, _, _| ()));
+    }
+
+    #[test]
+    fn exit() {
+        let mut headless = Headless::<u32>::new(100, 100);
+        let mut control_flow = headless.run_frame(&mut |_, _, control_flow| control_flow.set_exit());
+        assert_eq!(winit::event_loop::ControlFlow::Exit, control_flow);
+
+        // Exit cannot be undone
+        control_flow = headless.run_frame(&mut |_, _, control_flow| control_flow.set_poll());
+        assert_eq!(winit::event_loop::ControlFlow::Exit, control_flow);
+    }
+}
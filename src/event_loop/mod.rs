@@ -0,0 +1,506 @@
+mod backend;
+mod headless;
+
+use winit;
+use crate::{view::{self, View, children, focus}, theme, render, animation, timer, clock, shortcut, drag, gesture};
+use std::{cell::RefCell, rc::Rc, marker::PhantomData, time::{Duration, Instant}};
+
+pub use backend::Backend;
+pub use headless::{Headless, HeadlessTarget};
+
+/// The time between frames while anything is animating
+const FRAME_INTERVAL: Duration = Duration::from_micros(16_667);
+
+/// Defines a wrapper of an event loop backend which will deal with all the gui handling before passing events on to the user.
+/// By default the backend is a winit event loop
+#[derive(Debug)]
+pub struct EventLoop<T: 'static, B: Backend<T> = winit::event_loop::EventLoop<T>> {
+    /// The backend to run
+    backend: B,
+    /// The state of the views
+    state: ViewState,
+    /// The user event type
+    _user_event: PhantomData<T>,
+}
+
+impl EventLoop<()> {
+    /// Creates a new basic event loop
+    /// 
+    /// # Parameters
+    /// 
+    /// root: The root view for the window
+    pub fn new(root: View) -> Result<Self, children::ValidateError> {
+        // Create an event loop
+        let event_loop = winit::event_loop::EventLoop::new();
+
+        Self::from_backend(root, event_loop)
+    }
+}
+
+impl<T: 'static> EventLoop<T> {
+    /// Creates an event loop based on the winit event loop given
+    /// 
+    /// # Parameters
+    /// 
+    /// root: The root view for the window
+    /// 
+    /// event_loop: The winit event loop to run
+    pub fn from_winit_loop(root: View, event_loop: winit::event_loop::EventLoop<T>) -> Result<Self, children::ValidateError> {
+        Self::from_backend(root, event_loop)
+    }
+
+    /// Returns a reference to the winit event loop to use it to build windows and other things
+    pub fn get_winit_loop(&self) -> &winit::event_loop::EventLoop<T> {
+        &self.backend
+    }
+
+    /// Returns a mutable reference to the winit event loop
+    pub fn get_winit_loop_mut(&mut self) -> &mut winit::event_loop::EventLoop<T> {
+        &mut self.backend
+    }
+}
+
+impl<T: 'static> EventLoop<T, Headless<T>> {
+    /// Runs a single frame of the headless backend, all queued events are handled and the root is updated
+    /// 
+    /// Returns the control flow at the end of the frame, unless the control flow is left to the user it is managed by the event loop
+    /// 
+    /// # Parameters
+    /// 
+    /// event_handler: The event handler to run after the gui events has been handled
+    pub fn run_frame<F>(&mut self, event_handler: &mut F) -> winit::event_loop::ControlFlow
+    where
+        F: FnMut(winit::event::Event<'_, T>, &HeadlessTarget, &mut winit::event_loop::ControlFlow),
+    {
+        let state = &mut self.state;
+        self.backend.run_frame(&mut |event, target, control_flow| {
+            let (frame_end, user_flow) = (matches!(event, winit::event::Event::RedrawEventsCleared), *control_flow);
+            state.handle_event(&event);
+            event_handler(event, target, control_flow);
+            state.handle_control_flow(user_flow, frame_end, control_flow);
+        })
+    }
+}
+
+impl<T: 'static, B: Backend<T>> EventLoop<T, B> {
+    /// Creates an event loop running on the given backend
+    /// 
+    /// # Parameters
+    /// 
+    /// root: The root view for the window
+    /// 
+    /// backend: The backend to run
+    pub fn from_backend(root: View, backend: B) -> Result<Self, children::ValidateError> {
+        // Make sure the root is valid
+        root.validate(&[])?;
+
+        Ok(Self { backend, state: ViewState::new(root), _user_event: PhantomData })
+    }
+
+    /// Returns a reference to the backend
+    pub fn get_backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Returns a mutable reference to the backend
+    pub fn get_backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// Returns a reference to the root view
+    pub fn get_root(&self) -> &View {
+        &self.state.root
+    }
+
+    /// Gets the areas of the window which changed since the damage was last taken
+    pub fn get_damage(&self) -> &render::Damage {
+        &self.state.damage
+    }
+
+    /// Takes the areas of the window which changed since the damage was last taken, a renderer only has to repaint these areas
+    pub fn take_damage(&mut self) -> render::Damage {
+        std::mem::take(&mut self.state.damage)
+    }
+
+    /// Gets the clock all animations and timers read the time from
+    pub fn get_clock(&self) -> Rc<dyn clock::Clock> {
+        Rc::clone(&self.state.clock)
+    }
+
+    /// Replaces the clock all animations and timers read the time from, a manual clock makes them deterministic in tests.
+    /// The deadlines the loop waits for are times of this clock, so a manual clock should only be used with the headless backend
+    /// 
+    /// # Parameters
+    /// 
+    /// clock: The new clock
+    pub fn set_clock(&mut self, clock: Rc<dyn clock::Clock>) {
+        self.state.frame_time = clock.now();
+        self.state.clock = clock;
+    }
+
+    /// Gets the time of the last frame, animations are shown as they are at this time
+    pub fn get_frame_time(&self) -> Instant {
+        self.state.frame_time
+    }
+
+    /// Gets the error of the last update of the views, None if it succeeded.
+    /// The views which could not be laid out keep their last extents and the next update tries again
+    pub fn get_layout_error(&self) -> Option<&view::UpdateError> {
+        self.state.layout_error.as_ref()
+    }
+
+    /// Checks if any view or timeline is animating, the event loop keeps running frames until nothing is animating
+    pub fn is_animating(&self) -> bool {
+        self.state.is_animating()
+    }
+
+    /// Sets the window to request a redraw for whenever the views changed, without a window the renderer must check the damage itself
+    /// 
+    /// # Parameters
+    /// 
+    /// window: The window the views are drawn in, None to stop requesting redraws
+    pub fn set_window(&mut self, window: Option<Rc<winit::window::Window>>) {
+        self.state.window = window;
+    }
+
+    /// Checks if the control flow is managed by the event loop
+    pub fn get_auto_control_flow(&self) -> bool {
+        self.state.auto_control_flow
+    }
+
+    /// Sets whether the control flow is managed by the event loop, this is on by default.
+    /// 
+    /// When managed the loop waits for events while nothing changes and wakes up in time for the next frame of an animation.
+    /// A control flow set by the user during a frame is still used if it wakes the loop up earlier, for example polling or waiting until an earlier time
+    /// 
+    /// # Parameters
+    /// 
+    /// auto_control_flow: True to manage the control flow, false to leave it entirely to the user
+    pub fn set_auto_control_flow(&mut self, auto_control_flow: bool) {
+        self.state.auto_control_flow = auto_control_flow;
+    }
+
+    /// Gets the timer controller, timers can be added to it at any time including from the callbacks of other timers
+    pub fn get_timer_controller(&self) -> Rc<RefCell<timer::TimerController>> {
+        Rc::clone(&self.state.timers)
+    }
+
+    /// Gets the timeline controller, timelines can be added to it at any time including while the event loop runs
+    pub fn get_timeline_controller(&self) -> Rc<RefCell<animation::TimelineController>> {
+        Rc::clone(&self.state.timelines)
+    }
+
+    /// Gets the focus scheduler, the focus can be moved through it at any time and tab moves it through the tab order
+    pub fn get_focus_scheduler(&self) -> Rc<RefCell<focus::FocusScheduler>> {
+        Rc::clone(&self.state.focus)
+    }
+
+    /// Gets the shortcut controller, shortcuts can be bound to it at any time including from the actions of other shortcuts
+    pub fn get_shortcut_controller(&self) -> Rc<RefCell<shortcut::ShortcutController>> {
+        Rc::clone(&self.state.shortcuts)
+    }
+
+    /// Gets the drag controller, reorders can be added to it at any time and the children of their views can then be dragged with the primary mouse button
+    pub fn get_drag_controller(&self) -> Rc<RefCell<drag::DragController>> {
+        Rc::clone(&self.state.drags)
+    }
+
+    /// Gets the gesture controller, gestures can be added to it at any time and take part from the next press of the primary mouse button
+    pub fn get_gesture_controller(&self) -> Rc<RefCell<gesture::GestureController>> {
+        Rc::clone(&self.state.gestures)
+    }
+
+    /// Gets the theme controller, the theme can be switched through it at any time including while the event loop runs
+    pub fn get_theme_controller(&self) -> Rc<RefCell<theme::ThemeController>> {
+        Rc::clone(&self.state.theme)
+    }
+
+    /// Starts the event loop
+    /// 
+    /// # Parameters
+    /// 
+    /// event_handler: The event handler to run after the gui events has been handled
+    pub fn run<F>(self, mut event_handler: F)
+    where
+        F: 'static + FnMut(winit::event::Event<'_, T>, &B::Target, &mut winit::event_loop::ControlFlow),
+    {
+        let Self { backend, mut state, .. } = self;
+
+        // Create the event handler
+        let view_event_handler = move |event: winit::event::Event<'_, T>, target: &B::Target, control_flow: &mut winit::event_loop::ControlFlow| {
+            let (frame_end, user_flow) = (matches!(event, winit::event::Event::RedrawEventsCleared), *control_flow);
+            state.handle_event(&event);
+
+            // Run the user events
+            event_handler(event, target, control_flow);
+
+            // Sleep until something needs a frame
+            state.handle_control_flow(user_flow, frame_end, control_flow);
+        };
+
+        backend.run(view_event_handler)
+    }
+}
+
+/// The state of the views which is shared by all backends
+#[derive(Debug)]
+struct ViewState {
+    /// The root view
+    root: View,
+    /// The size of the window in pixels, it is updated whenever the window is resized
+    window_size: (f32, f32),
+    /// The theme the views are styled with
+    theme: Rc<RefCell<theme::ThemeController>>,
+    /// The areas of the window which changed since the damage was last taken
+    damage: render::Damage,
+    /// The clock the time is read from
+    clock: Rc<dyn clock::Clock>,
+    /// The time the last frame was updated at
+    frame_time: Instant,
+    /// The running timelines, they are advanced before every update
+    timelines: Rc<RefCell<animation::TimelineController>>,
+    /// The running timers, due timers run before every update
+    timers: Rc<RefCell<timer::TimerController>>,
+    /// The focus of the window, it is resolved at the start of every frame before the views are updated
+    focus: Rc<RefCell<focus::FocusScheduler>>,
+    /// The shortcuts of the window, they get the key presses the focused view and its ancestors did not handle
+    shortcuts: Rc<RefCell<shortcut::ShortcutController>>,
+    /// The modifier keys which are held down
+    modifiers: winit::event::ModifiersState,
+    /// The reorders of the window and the child being dragged
+    drags: Rc<RefCell<drag::DragController>>,
+    /// The gestures of the window and the sequence of presses they compete for
+    gestures: Rc<RefCell<gesture::GestureController>>,
+    /// The position of the pointer in pixels of the window
+    pointer: (f32, f32),
+    /// The error of the last update, None if it succeeded
+    layout_error: Option<view::UpdateError>,
+    /// True if the window changed so the views must be laid out again
+    relayout: bool,
+    /// The window to request redraws for, None if redraws are not requested
+    window: Option<Rc<winit::window::Window>>,
+    /// True if the control flow is managed by the event loop
+    auto_control_flow: bool,
+    /// The control flow the user set during the current frame, None if the user did not change it
+    user_flow: Option<winit::event_loop::ControlFlow>,
+}
+
+impl ViewState {
+    /// Creates a new view state
+    /// 
+    /// # Parameters
+    /// 
+    /// root: The root view for the window
+    fn new(root: View) -> Self {
+        let window_size = (1.0, 1.0);
+        let theme = Rc::new(RefCell::new(theme::ThemeController::default()));
+        let damage = render::Damage::new();
+        let clock: Rc<dyn clock::Clock> = Rc::new(clock::SystemClock);
+        let frame_time = clock.now();
+        let timelines = Rc::new(RefCell::new(animation::TimelineController::default()));
+        let timers = Rc::new(RefCell::new(timer::TimerController::default()));
+        let focus = Rc::new(RefCell::new(focus::FocusScheduler::default()));
+        let shortcuts = Rc::new(RefCell::new(shortcut::ShortcutController::default()));
+        let modifiers = winit::event::ModifiersState::empty();
+        let drags = Rc::new(RefCell::new(drag::DragController::default()));
+        let gestures = Rc::new(RefCell::new(gesture::GestureController::default()));
+
+        Self { root, window_size, theme, damage, clock, frame_time, timelines, timers, focus, shortcuts, modifiers, drags, gestures, pointer: (0.0, 0.0), layout_error: None, relayout: true, window: None, auto_control_flow: true, user_flow: None }
+    }
+
+    /// Checks if any view or timeline is animating
+    fn is_animating(&self) -> bool {
+        !self.timelines.borrow().is_empty() || self.root.is_animating()
+    }
+
+    /// Checks if the views must be updated during the next frame, a failed update is tried again
+    fn needs_update(&self) -> bool {
+        self.relayout || self.layout_error.is_some() || !self.timelines.borrow().is_empty() || self.root.needs_update()
+    }
+
+    /// Gets the control flow which wakes the loop up in time for the next frame of an animation, the next timer or the next gesture which may be recognized.
+    /// Changes made after the views were updated are picked up by the frame of the next event
+    fn get_control_flow(&self) -> winit::event_loop::ControlFlow {
+        let animation = self.is_animating().then(|| self.frame_time + FRAME_INTERVAL);
+        let deadline = [animation, self.timers.borrow().get_next_deadline(), self.gestures.borrow().get_next_deadline()].into_iter().flatten().min();
+
+        match deadline {
+            Some(deadline) => winit::event_loop::ControlFlow::WaitUntil(deadline),
+            None => winit::event_loop::ControlFlow::Wait,
+        }
+    }
+
+    /// Remembers changes the user made to the control flow and sets the control flow at the end of a frame, must be run after every event is handled by the user.
+    /// The timers added during the frame are started at its end
+    /// 
+    /// # Parameters
+    /// 
+    /// user_flow: The control flow before the user handled the event
+    /// 
+    /// frame_end: True if the event was the last event of a frame
+    /// 
+    /// control_flow: The control flow after the user handled the event
+    fn handle_control_flow(&mut self, user_flow: winit::event_loop::ControlFlow, frame_end: bool, control_flow: &mut winit::event_loop::ControlFlow) {
+        // Timers added during the frame start counting once it is done
+        if frame_end {
+            self.timers.borrow_mut().start(self.clock.now());
+        }
+        if !self.auto_control_flow {
+            return;
+        }
+
+        if *control_flow != user_flow {
+            self.user_flow = Some(*control_flow);
+        }
+
+        // The user can only make the loop wake up earlier
+        if frame_end {
+            let needed = self.get_control_flow();
+            *control_flow = match self.user_flow.take() {
+                Some(user) => earliest(user, needed),
+                None => needed,
+            };
+        }
+    }
+
+    /// Handles a key, it goes to the focused view and its ancestors first, then to the shortcuts and finally tab moves the focus
+    /// 
+    /// # Parameters
+    /// 
+    /// input: The key which was pressed or released
+    fn handle_key(&mut self, input: &winit::event::KeyboardInput) {
+        let path = self.focus.borrow().get_path(&self.root);
+        if focus::dispatch(&path, &focus::KeyEvent::Input(*input)) {
+            return;
+        }
+
+        // Only pressing a key which is not a modifier can complete a shortcut
+        let key = match (input.state, input.virtual_keycode) {
+            (winit::event::ElementState::Pressed, Some(key)) if !shortcut::is_modifier(key) => key,
+            _ => return,
+        };
+        let owners = self.focus.borrow().get_owner_path(&self.root);
+        if shortcut::press(&self.shortcuts, shortcut::Chord::new(self.modifiers, key), &owners) {
+            return;
+        }
+
+        // Tab moves the focus forwards and shift tab backwards
+        if key == winit::event::VirtualKeyCode::Tab {
+            let operation = if self.modifiers.shift() { focus::FocusOperation::Prev } else { focus::FocusOperation::Next };
+            // Moving through the tab order cannot fail
+            let _ = self.focus.borrow_mut().push_operation(operation);
+        }
+    }
+
+    /// Handles all the gui events, must be run before the event is passed on to the user
+    /// 
+    /// # Parameters
+    /// 
+    /// event: The event to handle
+    fn handle_event<T>(&mut self, event: &winit::event::Event<'_, T>) {
+        match event {
+            // Keep track of the window size, a minimized window keeps the old size
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::Resized(size), .. } if size.width > 0 && size.height > 0 => {
+                let window_size = (size.width as f32, size.height as f32);
+                self.relayout |= window_size != self.window_size;
+                self.window_size = window_size;
+            }
+
+            // Keyboard events go to the focused view and bubble up to its ancestors, the modifiers are kept for the shortcuts
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::ModifiersChanged(modifiers), .. } => {
+                self.modifiers = *modifiers;
+                focus::dispatch(&self.focus.borrow().get_path(&self.root), &focus::KeyEvent::Modifiers(*modifiers));
+            }
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::ReceivedCharacter(character), .. } => {
+                focus::dispatch(&self.focus.borrow().get_path(&self.root), &focus::KeyEvent::Character(*character));
+            }
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::KeyboardInput { input, .. }, .. } => self.handle_key(input),
+
+            // The primary mouse button drags the children of reorders and is recognized as gestures, a reorder which starts dragging takes the press from the gestures
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::CursorMoved { position, .. }, .. } => {
+                self.pointer = (position.x as f32, position.y as f32);
+                drag::moved(&self.drags, self.pointer);
+                match self.drags.borrow().is_dragging() {
+                    true => gesture::cancel(&self.gestures),
+                    false => gesture::moved(&self.gestures, self.pointer),
+                }
+            }
+            winit::event::Event::WindowEvent { event: winit::event::WindowEvent::MouseInput { state, button: winit::event::MouseButton::Left, .. }, .. } => match state {
+                winit::event::ElementState::Pressed => {
+                    drag::press(&self.drags, &self.root, self.pointer);
+                    gesture::press(&self.gestures, &self.root, self.pointer, self.clock.now());
+                }
+                winit::event::ElementState::Released => {
+                    drag::release(&self.drags);
+                    gesture::release(&self.gestures, &self.root, self.pointer, self.clock.now());
+                }
+            },
+
+            // All events are done and the root must be updated if anything changed, an idle window does no work
+            winit::event::Event::MainEventsCleared => {
+                // Timers run first since they usually change the views
+                timer::run_due(&self.timers, self.clock.now());
+                gesture::run_due(&self.gestures, self.clock.now());
+
+                // The focus is moved before the update so views can restyle themselves when they are notified, a deleted view passes the focus on right away
+                if self.focus.borrow().needs_resolve() || self.root.needs_update() {
+                    self.root.resolve_children();
+                    focus::resolve(&self.focus, &self.root);
+                }
+
+                let theme_changed = self.theme.borrow_mut().take_changed();
+                if !theme_changed && !self.needs_update() {
+                    return;
+                }
+                self.relayout = false;
+
+                // Restyle before the layout since the padding changes where the children go, a new theme restyles the entire tree
+                if let Some(theme) = self.theme.borrow().get_theme() {
+                    self.root.resolve_children();
+                    if theme_changed {
+                        theme.apply(&self.root);
+                    } else {
+                        theme.apply_changed(&self.root);
+                    }
+                }
+
+                // The timelines run before the layout so the animated extents are used right away
+                self.frame_time = self.clock.now();
+                let done = self.timelines.borrow_mut().advance(self.frame_time);
+                animation::complete(done);
+
+                // An invalid reference leaves the rest of the views as they were and is kept for the user, the next update tries again
+                let mut damage = render::Damage::new();
+                self.layout_error = self.root.update(self.window_size, self.frame_time, &mut damage).err();
+
+                // Only a changed window must be drawn again
+                if !damage.is_empty() {
+                    if let Some(window) = &self.window {
+                        window.request_redraw();
+                    }
+                }
+                self.damage.extend(&damage);
+            }
+
+            _ => (),
+        }
+    }
+}
+
+/// Gets the control flow which wakes the loop up first, exiting always wins
+/// 
+/// # Parameters
+/// 
+/// a: The first control flow
+/// 
+/// b: The second control flow
+fn earliest(a: winit::event_loop::ControlFlow, b: winit::event_loop::ControlFlow) -> winit::event_loop::ControlFlow {
+    use winit::event_loop::ControlFlow;
+
+    match (a, b) {
+        (ControlFlow::ExitWithCode(_), _) | (_, ControlFlow::Wait) => a,
+        (_, ControlFlow::ExitWithCode(_)) | (ControlFlow::Wait, _) => b,
+        (ControlFlow::Poll, _) | (_, ControlFlow::Poll) => ControlFlow::Poll,
+        (ControlFlow::WaitUntil(a), ControlFlow::WaitUntil(b)) => ControlFlow::WaitUntil(a.min(b)),
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{event_loop::{EventLoop, Headless}, view::{View, extent, children}};
+use std::rc::Rc;
+use winit::event::{WindowEvent, KeyboardInput, ElementState, MouseButton, VirtualKeyCode, ModifiersState, DeviceId};
+
+/// Creates the update info of a single dimension which places the view at a position with a size
+///
+/// # Parameters
+///
+/// pos: The position of the view
+///
+/// size: The size of the view
+pub(crate) fn gen_locate(pos: extent::PositionType, size: extent::SizeType) -> extent::ExtentUpdateSingle {
+    let locate = extent::ExtentLocate { pos, size };
+    extent::ExtentUpdateSingle { extent_type: extent::ExtentUpdateType::Locate(locate), scale_rel: 1.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0 }
+}
+
+/// Creates the update info of a single dimension which sizes the view from the other dimension
+///
+/// # Parameters
+///
+/// pos: The position of the view
+///
+/// source: Where the ratio comes from
+pub(crate) fn gen_ratio(pos: extent::PositionType, source: extent::RatioSource) -> extent::ExtentUpdateSingle {
+    let ratio = extent::ExtentRatio { pos, source };
+    extent::ExtentUpdateSingle { extent_type: extent::ExtentUpdateType::Ratio(ratio), scale_rel: 1.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0 }
+}
+
+/// Creates update info which places the view at the same position with the same size in both dimensions
+///
+/// # Parameters
+///
+/// pos: The position of the view
+///
+/// size: The size of the view
+pub(crate) fn gen_update_info(pos: extent::PositionType, size: extent::SizeType) -> extent::ExtentUpdate {
+    let update_single = gen_locate(pos, size);
+    extent::ExtentUpdate { x: update_single, y: update_single }
+}
+
+/// Creates update info which places the view at a set position with a set size in both dimensions
+///
+/// # Parameters
+///
+/// pos: The position of the view relative to the parent
+///
+/// size: The size of the view relative to the parent
+pub(crate) fn gen_set_update_info(pos: f32, size: f32) -> extent::ExtentUpdate {
+    gen_update_info(extent::PositionType::Set(pos), extent::SizeType::Set(size))
+}
+
+/// Creates a headless event loop with a window of 100 by 100 pixels whose root has a child for each update info, no frame has run yet
+///
+/// # Parameters
+///
+/// children: The update info of each child of the root in order
+pub(crate) fn gen_event_loop(children: &[extent::ExtentUpdate]) -> EventLoop<u32, Headless<u32>> {
+    let root = View::new_root();
+    let scheduler = root.get_children_scheduler();
+    for update_info in children {
+        let child = View::new(*update_info, Some(Rc::clone(&scheduler)));
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+    }
+
+    EventLoop::from_backend(*root, Headless::new(100, 100)).unwrap()
+}
+
+/// Creates a movement of the cursor
+///
+/// # Parameters
+///
+/// x: The new x position in pixels
+///
+/// y: The new y position in pixels
+#[allow(deprecated)]
+pub(crate) fn gen_cursor_moved(x: f64, y: f64) -> WindowEvent<'static> {
+    WindowEvent::CursorMoved { device_id: gen_device_id(), position: winit::dpi::PhysicalPosition::new(x, y), modifiers: ModifiersState::empty() }
+}
+
+/// Creates a press or release of the left mouse button
+///
+/// # Parameters
+///
+/// state: Whether the button is pressed or released
+#[allow(deprecated)]
+pub(crate) fn gen_mouse_input(state: ElementState) -> WindowEvent<'static> {
+    WindowEvent::MouseInput { device_id: gen_device_id(), state, button: MouseButton::Left, modifiers: ModifiersState::empty() }
+}
+
+/// Creates a press or release of a key, the modifiers are sent separately
+///
+/// # Parameters
+///
+/// key: The key
+///
+/// state: Whether the key is pressed or released
+#[allow(deprecated)]
+pub(crate) fn gen_key_input(key: VirtualKeyCode, state: ElementState) -> WindowEvent<'static> {
+    let input = KeyboardInput { scancode: 0, state, virtual_keycode: Some(key), modifiers: ModifiersState::empty() };
+    WindowEvent::KeyboardInput { device_id: gen_device_id(), input, is_synthetic: false }
+}
+
+/// Creates the id of the device sending the events
+fn gen_device_id() -> DeviceId {
+    // The dummy id is only compared against other ids, which the event loop never does
+    unsafe { DeviceId::dummy() }
+}
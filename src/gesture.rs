@@ -0,0 +1,800 @@
+use crate::view::View;
+use std::{cell::RefCell, rc::{Rc, Weak}, fmt, time::{Duration, Instant}};
+
+/// The function run when a gesture is recognized
+type Callback = Rc<RefCell<Box<dyn FnMut(&GestureEvent)>>>;
+
+/// What a recognizer looks for in the presses of the primary mouse button
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Recognizer {
+    /// A number of presses and releases in a row inside the view, 1 for a click, 2 for a double click and so on
+    Click(u32),
+    /// A press which is held for a while without moving
+    LongPress,
+    /// A press which moves further than the drag threshold
+    Drag,
+}
+
+/// A gesture which was recognized, positions are in pixels of the window
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GestureEvent {
+    /// The view was clicked a number of times in a row
+    Click {
+        /// The number of clicks
+        count: u32,
+        /// The position of the last release
+        position: (f32, f32),
+    },
+    /// The view was pressed for the long press duration
+    LongPress {
+        /// The position of the press
+        position: (f32, f32),
+    },
+    /// The pointer moved past the drag threshold
+    DragStart {
+        /// The position of the press
+        position: (f32, f32),
+    },
+    /// The pointer moved during a drag
+    DragMove {
+        /// The position of the pointer
+        position: (f32, f32),
+        /// The distance from the position of the press
+        delta: (f32, f32),
+    },
+    /// The drag ended because the button was released or the drag was cancelled
+    DragEnd {
+        /// The position of the pointer
+        position: (f32, f32),
+        /// The distance from the position of the press
+        delta: (f32, f32),
+    },
+}
+
+/// The timing and distances used to tell gestures apart
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GestureSettings {
+    /// The longest time between a release and the next press for them to count as a multi click
+    pub multi_click_interval: Duration,
+    /// The time a press must be held to be a long press
+    pub long_press_duration: Duration,
+    /// The distance in pixels the pointer may move during clicks and long presses
+    pub slop: f32,
+    /// The distance in pixels the pointer has to move before a press is a drag
+    pub drag_threshold: f32,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self { multi_click_interval: Duration::from_millis(500), long_press_duration: Duration::from_millis(500), slop: 4.0, drag_threshold: 4.0 }
+    }
+}
+
+/// A recognizer attached to a view with the function to run when it recognizes its gesture
+pub struct Gesture {
+    /// What to look for
+    recognizer: Recognizer,
+    /// The owner token of the view, the gesture is removed once the view is dropped
+    owner: Weak<()>,
+    /// The function to run
+    callback: Callback,
+}
+
+impl Gesture {
+    /// Creates a gesture for a view, it only sees presses which start inside the view
+    ///
+    /// # Parameters
+    ///
+    /// view: The view the gesture belongs to
+    ///
+    /// recognizer: What to look for
+    ///
+    /// callback: The function to run when the gesture is recognized
+    pub fn new<F: FnMut(&GestureEvent) + 'static>(view: &View, recognizer: Recognizer, callback: F) -> Self {
+        Self { recognizer, owner: view.get_owner(), callback: Rc::new(RefCell::new(Box::new(callback))) }
+    }
+
+    /// Checks if the view of the gesture has been dropped
+    fn is_orphaned(&self) -> bool {
+        self.owner.strong_count() == 0
+    }
+
+    /// Gets the depth of the view in a hit path, None if it is not in the path
+    ///
+    /// # Parameters
+    ///
+    /// path: The owner tokens of the views below the pointer from the root down
+    fn get_depth(&self, path: &[Weak<()>]) -> Option<usize> {
+        path.iter().position(|view| view.ptr_eq(&self.owner))
+    }
+}
+
+impl fmt::Debug for Gesture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Gesture")
+            .field("recognizer", &self.recognizer)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Identifies a gesture added to a gesture controller
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GestureId(usize);
+
+/// A sequence of presses which the gestures compete for
+#[derive(Debug)]
+struct Sequence {
+    /// The gestures which may still recognize the sequence, the gestures of deeper views come first
+    candidates: Vec<GestureId>,
+    /// The gesture which recognized the sequence and receives the rest of it, None while the gestures still compete
+    winner: Option<GestureId>,
+    /// The position of the first press
+    start: (f32, f32),
+    /// The time of the last press
+    pressed_at: Instant,
+    /// True while the button is held down
+    pressed: bool,
+    /// The number of releases so far
+    clicks: u32,
+    /// The time of the last release
+    released_at: Option<Instant>,
+    /// The position of the pointer
+    pointer: (f32, f32),
+}
+
+/// Holds all the gestures of an event loop and decides which of them recognizes the presses of the primary mouse button.
+///
+/// The gestures of the views below the press compete for it, the gestures of deeper views are preferred.
+/// Moving past the drag threshold gives the sequence to a drag, holding still for the long press duration to a long press and releasing to a click.
+/// A click of a lower count waits for the multi click interval while a click of a higher count could still be recognized
+#[derive(Debug, Default)]
+pub struct GestureController {
+    /// The gestures which are active
+    gestures: Vec<(GestureId, Gesture)>,
+    /// The timing and distances
+    settings: GestureSettings,
+    /// The current sequence, None if there is none
+    sequence: Option<Sequence>,
+    /// The id given to the next gesture
+    next_id: usize,
+}
+
+impl GestureController {
+    /// Adds a gesture, it takes part from the next press on
+    ///
+    /// # Parameters
+    ///
+    /// gesture: The gesture to add
+    pub fn add(&mut self, gesture: Gesture) -> GestureId {
+        let id = GestureId(self.next_id);
+        self.next_id += 1;
+        self.gestures.push((id, gesture));
+
+        id
+    }
+
+    /// Removes a gesture, returns false if it was already removed
+    ///
+    /// # Parameters
+    ///
+    /// id: The gesture to remove
+    pub fn remove(&mut self, id: GestureId) -> bool {
+        if let Some(sequence) = &mut self.sequence {
+            sequence.candidates.retain(|own| *own != id);
+            if sequence.winner == Some(id) {
+                self.sequence = None;
+            }
+        }
+
+        let len = self.gestures.len();
+        self.gestures.retain(|(own, _)| *own != id);
+
+        self.gestures.len() != len
+    }
+
+    /// Gets the timing and distances used to tell gestures apart
+    pub fn get_settings(&self) -> GestureSettings {
+        self.settings
+    }
+
+    /// Sets the timing and distances used to tell gestures apart, they are used from the next event on
+    ///
+    /// # Parameters
+    ///
+    /// settings: The new settings
+    pub fn set_settings(&mut self, settings: GestureSettings) {
+        self.settings = settings;
+    }
+
+    /// Checks if the gestures are following a sequence of presses
+    pub fn is_active(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// Gets the next time a gesture may be recognized without any input, None if there is none
+    pub fn get_next_deadline(&self) -> Option<Instant> {
+        let sequence = self.sequence.as_ref().filter(|sequence| sequence.winner.is_none())?;
+        match sequence.released_at {
+            // A long press is recognized while the button is held down
+            _ if sequence.pressed => self.has_candidate(sequence, |recognizer| recognizer == Recognizer::LongPress).then(|| sequence.pressed_at + self.settings.long_press_duration),
+
+            // A waiting click is recognized once no further click can follow
+            Some(released_at) => Some(released_at + self.settings.multi_click_interval),
+            None => None,
+        }
+    }
+
+    /// Stops following the current sequence, a running drag ends
+    pub fn cancel(&mut self) -> Vec<(Callback, GestureEvent)> {
+        let sequence = match self.sequence.take() {
+            Some(sequence) => sequence,
+            None => return Vec::new(),
+        };
+
+        match sequence.winner.and_then(|id| self.get_gesture(id)) {
+            Some(gesture) if gesture.recognizer == Recognizer::Drag => vec![(Rc::clone(&gesture.callback), drag_event(&sequence, true))],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Starts or continues a sequence with a press
+    ///
+    /// # Parameters
+    ///
+    /// root: The root view
+    ///
+    /// pointer: The position of the pointer in pixels of the window
+    ///
+    /// time: The current time
+    fn press(&mut self, root: &View, pointer: (f32, f32), time: Instant) -> Vec<(Callback, GestureEvent)> {
+        self.gestures.retain(|(_, gesture)| !gesture.is_orphaned());
+        let mut recognized = Vec::new();
+
+        // A press close to the last one soon enough continues a multi click, otherwise the waiting click is recognized first
+        if let Some(sequence) = &mut self.sequence {
+            let close = distance(sequence.start, pointer) <= self.settings.slop;
+            let soon = sequence.released_at.is_some_and(|released_at| time <= released_at + self.settings.multi_click_interval);
+            let clicks = sequence.clicks;
+            let more = |recognizer| matches!(recognizer, Recognizer::Click(count) if count > clicks);
+            let gestures = &self.gestures;
+            let continues = sequence.candidates.iter().any(|id| gestures.iter().any(|(own, gesture)| own == id && more(gesture.recognizer)));
+            if !sequence.pressed && sequence.winner.is_none() && close && soon && continues {
+                sequence.candidates.retain(|id| gestures.iter().any(|(own, gesture)| own == id && more(gesture.recognizer)));
+                sequence.pressed = true;
+                sequence.pressed_at = time;
+                sequence.pointer = pointer;
+                return recognized;
+            }
+
+            recognized.extend(self.finish_clicks());
+            recognized.extend(self.cancel());
+        }
+
+        // All the gestures of the views below the pointer compete, the deepest views first
+        let mut path = Vec::new();
+        hit(root, pointer, &mut path);
+        let mut candidates: Vec<(usize, GestureId)> = self.gestures.iter()
+            .filter(|(_, gesture)| !matches!(gesture.recognizer, Recognizer::Click(0)))
+            .filter_map(|(id, gesture)| gesture.get_depth(&path).map(|depth| (depth, *id)))
+            .collect();
+        candidates.sort_by_key(|(depth, _)| std::cmp::Reverse(*depth));
+        if !candidates.is_empty() {
+            let candidates = candidates.into_iter().map(|(_, id)| id).collect();
+            self.sequence = Some(Sequence { candidates, winner: None, start: pointer, pressed_at: time, pressed: true, clicks: 0, released_at: None, pointer });
+        }
+
+        recognized
+    }
+
+    /// Moves the pointer, a drag gets the sequence once the pointer moves past the threshold and clicks and long presses fail once it leaves the slop
+    ///
+    /// # Parameters
+    ///
+    /// pointer: The position of the pointer in pixels of the window
+    fn moved(&mut self, pointer: (f32, f32)) -> Vec<(Callback, GestureEvent)> {
+        let sequence = match &mut self.sequence {
+            Some(sequence) => sequence,
+            None => return Vec::new(),
+        };
+        sequence.pointer = pointer;
+
+        // A drag which won gets every movement
+        if let Some(winner) = sequence.winner {
+            return match self.gestures.iter().find(|(id, gesture)| *id == winner && gesture.recognizer == Recognizer::Drag) {
+                Some((_, gesture)) => vec![(Rc::clone(&gesture.callback), drag_event(sequence, false))],
+                None => Vec::new(),
+            };
+        }
+
+        let moved = distance(sequence.start, pointer);
+        if sequence.pressed && moved >= self.settings.drag_threshold {
+            let gestures = &self.gestures;
+            let drag = sequence.candidates.iter().copied().find(|id| gestures.iter().any(|(own, gesture)| own == id && gesture.recognizer == Recognizer::Drag));
+            if let Some(drag) = drag {
+                sequence.winner = Some(drag);
+                sequence.candidates.clear();
+                let callback = Rc::clone(&self.get_gesture(drag).unwrap().callback);
+                let sequence = self.sequence.as_ref().unwrap();
+                return vec![(Rc::clone(&callback), GestureEvent::DragStart { position: sequence.start }), (callback, drag_event(sequence, false))];
+            }
+        }
+
+        // Everything else fails once the pointer moves too far, a drag may still follow and a waiting click is still recognized
+        if moved > self.settings.slop {
+            if !sequence.pressed {
+                return self.finish_clicks();
+            }
+
+            let gestures = &self.gestures;
+            sequence.candidates.retain(|id| gestures.iter().any(|(own, gesture)| own == id && gesture.recognizer == Recognizer::Drag));
+            if sequence.candidates.is_empty() {
+                self.sequence = None;
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// Releases the button, this may complete a click or end a drag
+    ///
+    /// # Parameters
+    ///
+    /// root: The root view
+    ///
+    /// pointer: The position of the pointer in pixels of the window
+    ///
+    /// time: The current time
+    fn release(&mut self, root: &View, pointer: (f32, f32), time: Instant) -> Vec<(Callback, GestureEvent)> {
+        let sequence = match &mut self.sequence {
+            Some(sequence) if sequence.pressed => sequence,
+            _ => return Vec::new(),
+        };
+        sequence.pointer = pointer;
+
+        // A gesture which already won ends the sequence
+        if sequence.winner.is_some() {
+            let sequence = self.sequence.take().unwrap();
+            return match sequence.winner.and_then(|id| self.get_gesture(id)) {
+                Some(gesture) if gesture.recognizer == Recognizer::Drag => vec![(Rc::clone(&gesture.callback), drag_event(&sequence, false).into_end())],
+                _ => Vec::new(),
+            };
+        }
+
+        // Only clicks of views which are still below the pointer remain
+        let mut path = Vec::new();
+        hit(root, pointer, &mut path);
+        sequence.pressed = false;
+        sequence.clicks += 1;
+        sequence.released_at = Some(time);
+        let gestures = &self.gestures;
+        sequence.candidates.retain(|id| gestures.iter().any(|(own, gesture)| own == id && matches!(gesture.recognizer, Recognizer::Click(_)) && gesture.get_depth(&path).is_some()));
+
+        // A click is recognized right away unless a click of a higher count could still follow
+        let clicks = sequence.clicks;
+        if self.has_candidate(self.sequence.as_ref().unwrap(), |recognizer| matches!(recognizer, Recognizer::Click(count) if count > clicks)) {
+            return Vec::new();
+        }
+
+        self.finish_clicks()
+    }
+
+    /// Recognizes the gestures which are due, this is a long press which was held long enough or a click which no longer waits for more clicks
+    ///
+    /// # Parameters
+    ///
+    /// time: The current time
+    fn run_due(&mut self, time: Instant) -> Vec<(Callback, GestureEvent)> {
+        match self.get_next_deadline() {
+            Some(deadline) if deadline <= time => (),
+            _ => return Vec::new(),
+        }
+
+        // The deepest long press wins
+        let sequence = self.sequence.as_mut().unwrap();
+        if sequence.pressed {
+            let gestures = &self.gestures;
+            let long_press = sequence.candidates.iter().copied().find(|id| gestures.iter().any(|(own, gesture)| own == id && gesture.recognizer == Recognizer::LongPress));
+            sequence.winner = long_press;
+            sequence.candidates.clear();
+
+            let position = sequence.start;
+            return long_press.and_then(|id| self.get_gesture(id)).map(|gesture| vec![(Rc::clone(&gesture.callback), GestureEvent::LongPress { position })]).unwrap_or_default();
+        }
+
+        self.finish_clicks()
+    }
+
+    /// Ends a sequence which is between presses by recognizing the deepest click matching the number of clicks
+    fn finish_clicks(&mut self) -> Vec<(Callback, GestureEvent)> {
+        let sequence = match self.sequence.take() {
+            Some(sequence) if !sequence.pressed && sequence.winner.is_none() => sequence,
+            sequence => {
+                self.sequence = sequence;
+                return Vec::new();
+            }
+        };
+
+        let clicks = sequence.clicks;
+        let click = sequence.candidates.iter().find_map(|id| self.get_gesture(*id).filter(|gesture| gesture.recognizer == Recognizer::Click(clicks)));
+        match click {
+            Some(gesture) => vec![(Rc::clone(&gesture.callback), GestureEvent::Click { count: clicks, position: sequence.pointer })],
+            None => Vec::new(),
+        }
+    }
+
+    /// Gets a gesture by its id
+    ///
+    /// # Parameters
+    ///
+    /// id: The id of the gesture
+    fn get_gesture(&self, id: GestureId) -> Option<&Gesture> {
+        self.gestures.iter().find(|(own, _)| *own == id).map(|(_, gesture)| gesture)
+    }
+
+    /// Checks if any candidate of a sequence has a recognizer matching a condition
+    ///
+    /// # Parameters
+    ///
+    /// sequence: The sequence to check
+    ///
+    /// condition: The condition the recognizer must match
+    fn has_candidate<F: Fn(Recognizer) -> bool>(&self, sequence: &Sequence, condition: F) -> bool {
+        sequence.candidates.iter().any(|id| self.get_gesture(*id).is_some_and(|gesture| condition(gesture.recognizer)))
+    }
+}
+
+impl GestureEvent {
+    /// Turns a drag movement into the end of the drag
+    fn into_end(self) -> Self {
+        match self {
+            Self::DragMove { position, delta } => Self::DragEnd { position, delta },
+            event => event,
+        }
+    }
+}
+
+/// Starts or continues a sequence with a press and runs the callbacks of the recognized gestures
+///
+/// # Parameters
+///
+/// controller: The gesture controller, it must not be borrowed
+///
+/// root: The root view
+///
+/// pointer: The position of the pointer in pixels of the window
+///
+/// time: The current time
+pub(crate) fn press(controller: &RefCell<GestureController>, root: &View, pointer: (f32, f32), time: Instant) {
+    let recognized = controller.borrow_mut().press(root, pointer, time);
+    notify(recognized);
+}
+
+/// Moves the pointer and runs the callbacks of the recognized gestures
+///
+/// # Parameters
+///
+/// controller: The gesture controller, it must not be borrowed
+///
+/// pointer: The position of the pointer in pixels of the window
+pub(crate) fn moved(controller: &RefCell<GestureController>, pointer: (f32, f32)) {
+    let recognized = controller.borrow_mut().moved(pointer);
+    notify(recognized);
+}
+
+/// Releases the button and runs the callbacks of the recognized gestures
+///
+/// # Parameters
+///
+/// controller: The gesture controller, it must not be borrowed
+///
+/// root: The root view
+///
+/// pointer: The position of the pointer in pixels of the window
+///
+/// time: The current time
+pub(crate) fn release(controller: &RefCell<GestureController>, root: &View, pointer: (f32, f32), time: Instant) {
+    let recognized = controller.borrow_mut().release(root, pointer, time);
+    notify(recognized);
+}
+
+/// Stops following the current sequence and ends a running drag
+///
+/// # Parameters
+///
+/// controller: The gesture controller, it must not be borrowed
+pub(crate) fn cancel(controller: &RefCell<GestureController>) {
+    let recognized = controller.borrow_mut().cancel();
+    notify(recognized);
+}
+
+/// Recognizes the gestures which are due and runs their callbacks
+///
+/// # Parameters
+///
+/// controller: The gesture controller, it must not be borrowed
+///
+/// time: The current time
+pub(crate) fn run_due(controller: &RefCell<GestureController>, time: Instant) {
+    let recognized = controller.borrow_mut().run_due(time);
+    notify(recognized);
+}
+
+/// Runs the callbacks of recognized gestures
+///
+/// # Parameters
+///
+/// recognized: The callbacks with the events to run them with
+fn notify(recognized: Vec<(Callback, GestureEvent)>) {
+    for (callback, event) in recognized {
+        (callback.borrow_mut())(&event);
+    }
+}
+
+/// Gets the movement of a drag
+///
+/// # Parameters
+///
+/// sequence: The sequence of the drag
+///
+/// end: True if the drag ends
+fn drag_event(sequence: &Sequence, end: bool) -> GestureEvent {
+    let (position, delta) = (sequence.pointer, (sequence.pointer.0 - sequence.start.0, sequence.pointer.1 - sequence.start.1));
+    match end {
+        true => GestureEvent::DragEnd { position, delta },
+        false => GestureEvent::DragMove { position, delta },
+    }
+}
+
+/// Gets the distance between two points
+///
+/// # Parameters
+///
+/// a: The first point
+///
+/// b: The second point
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// Adds the owner tokens of a view and its descendants below a point from the view down, the youngest children are on top
+///
+/// # Parameters
+///
+/// view: The view to start at, it is always added
+///
+/// pointer: The point in pixels of the window
+///
+/// path: The list to add the owner tokens to
+fn hit(view: &View, pointer: (f32, f32), path: &mut Vec<Weak<()>>) {
+    path.push(view.get_owner());
+    if let Some(child) = view.get_children().iter().rev().find(|child| child.get_rect().is_some_and(|rect| rect.contains(pointer.0, pointer.1))) {
+        hit(child, pointer, path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{gen_locate, gen_set_update_info, gen_event_loop, gen_cursor_moved, gen_mouse_input};
+    use crate::{view::{children, extent}, render, clock::{Clock, ManualClock}};
+    use winit::event::ElementState;
+
+    /// Creates a root with a child covering the top half
+    fn gen_root() -> Box<View> {
+        let mut root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        let single = |size| gen_locate(extent::PositionType::Set(0.0), extent::SizeType::Set(size));
+        let child = View::new(extent::ExtentUpdate { x: single(1.0), y: single(0.5) }, Some(Rc::clone(&scheduler)));
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+        root.update((100.0, 100.0), Instant::now(), &mut render::Damage::new()).unwrap();
+
+        root
+    }
+
+    /// Adds a gesture which records its events
+    fn record(controller: &RefCell<GestureController>, view: &View, recognizer: Recognizer, events: &Rc<RefCell<Vec<(&'static str, GestureEvent)>>>, name: &'static str) -> GestureId {
+        let events = Rc::clone(events);
+        controller.borrow_mut().add(Gesture::new(view, recognizer, move |event| events.borrow_mut().push((name, *event))))
+    }
+
+    #[test]
+    fn click() {
+        let root = gen_root();
+        let controller = RefCell::new(GestureController::default());
+        let events = Rc::new(RefCell::new(Vec::new()));
+        record(&controller, &root.get_children()[0], Recognizer::Click(1), &events, "child");
+        let start = Instant::now();
+
+        // A press and release inside is a click
+        press(&controller, &root, (50.0, 20.0), start);
+        release(&controller, &root, (51.0, 21.0), start);
+        assert_eq!(vec![("child", GestureEvent::Click { count: 1, position: (51.0, 21.0) })], *events.borrow());
+        assert!(!controller.borrow().is_active());
+
+        // Releasing outside or moving too far is not
+        events.borrow_mut().clear();
+        press(&controller, &root, (50.0, 20.0), start);
+        release(&controller, &root, (50.0, 80.0), start);
+        press(&controller, &root, (50.0, 20.0), start);
+        moved(&controller, (50.0, 30.0));
+        moved(&controller, (50.0, 20.0));
+        release(&controller, &root, (50.0, 20.0), start);
+        assert!(events.borrow().is_empty());
+
+        // Presses outside the view are ignored
+        press(&controller, &root, (50.0, 80.0), start);
+        assert!(!controller.borrow().is_active());
+    }
+
+    #[test]
+    fn deleted() {
+        let mut root = gen_root();
+        let scheduler = root.get_children_scheduler();
+        let controller = RefCell::new(GestureController::default());
+        let events = Rc::new(RefCell::new(Vec::new()));
+        record(&controller, &root.get_children()[0], Recognizer::Click(1), &events, "child");
+        let style = root.get_children()[0].get_style_controller();
+        let start = Instant::now();
+
+        // The gesture is removed once its view is deleted even while its controllers are still held
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(0)).unwrap();
+        root.resolve_children();
+        press(&controller, &root, (50.0, 20.0), start);
+        release(&controller, &root, (50.0, 20.0), start);
+        assert!(events.borrow().is_empty());
+        assert!(controller.borrow().gestures.is_empty());
+        drop(style);
+    }
+
+    #[test]
+    fn multi_click() {
+        let root = gen_root();
+        let controller = RefCell::new(GestureController::default());
+        let events = Rc::new(RefCell::new(Vec::new()));
+        record(&controller, &root.get_children()[0], Recognizer::Click(1), &events, "single");
+        record(&controller, &root, Recognizer::Click(2), &events, "double");
+        record(&controller, &root, Recognizer::Click(3), &events, "triple");
+        let start = Instant::now();
+        let interval = controller.borrow().get_settings().multi_click_interval;
+
+        // A single click waits until no further click can follow
+        press(&controller, &root, (50.0, 20.0), start);
+        release(&controller, &root, (50.0, 20.0), start);
+        assert!(events.borrow().is_empty());
+        assert_eq!(Some(start + interval), controller.borrow().get_next_deadline());
+        run_due(&controller, start + interval - Duration::from_millis(1));
+        assert!(events.borrow().is_empty());
+        run_due(&controller, start + interval);
+        assert_eq!(vec![("single", GestureEvent::Click { count: 1, position: (50.0, 20.0) })], *events.borrow());
+
+        // Two clicks in time are a double click of the ancestor
+        events.borrow_mut().clear();
+        let time = start + interval * 2;
+        press(&controller, &root, (50.0, 20.0), time);
+        release(&controller, &root, (50.0, 20.0), time);
+        press(&controller, &root, (51.0, 20.0), time + interval);
+        release(&controller, &root, (51.0, 20.0), time + interval);
+        run_due(&controller, time + interval * 2);
+        assert_eq!(vec![("double", GestureEvent::Click { count: 2, position: (51.0, 20.0) })], *events.borrow());
+
+        // The triple click is recognized right away since nothing can follow it
+        events.borrow_mut().clear();
+        let time = start + interval * 4;
+        for _ in 0..3 {
+            press(&controller, &root, (50.0, 20.0), time);
+            release(&controller, &root, (50.0, 20.0), time);
+        }
+        assert_eq!(vec![("triple", GestureEvent::Click { count: 3, position: (50.0, 20.0) })], *events.borrow());
+
+        // A late second press starts over after recognizing the waiting click
+        events.borrow_mut().clear();
+        let time = start + interval * 6;
+        press(&controller, &root, (50.0, 20.0), time);
+        release(&controller, &root, (50.0, 20.0), time);
+        press(&controller, &root, (50.0, 20.0), time + interval * 2);
+        assert_eq!(vec![("single", GestureEvent::Click { count: 1, position: (50.0, 20.0) })], *events.borrow());
+    }
+
+    #[test]
+    fn long_press() {
+        let root = gen_root();
+        let controller = RefCell::new(GestureController::default());
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let child = &root.get_children()[0];
+        record(&controller, child, Recognizer::Click(1), &events, "click");
+        record(&controller, child, Recognizer::LongPress, &events, "long");
+        let start = Instant::now();
+        let duration = controller.borrow().get_settings().long_press_duration;
+
+        // Holding still claims the press so the release is not a click
+        press(&controller, &root, (50.0, 20.0), start);
+        assert_eq!(Some(start + duration), controller.borrow().get_next_deadline());
+        moved(&controller, (52.0, 20.0));
+        run_due(&controller, start + duration);
+        release(&controller, &root, (52.0, 20.0), start + duration);
+        assert_eq!(vec![("long", GestureEvent::LongPress { position: (50.0, 20.0) })], *events.borrow());
+
+        // A quick release is a click
+        events.borrow_mut().clear();
+        press(&controller, &root, (50.0, 20.0), start);
+        release(&controller, &root, (50.0, 20.0), start);
+        run_due(&controller, start + duration);
+        assert_eq!(vec![("click", GestureEvent::Click { count: 1, position: (50.0, 20.0) })], *events.borrow());
+    }
+
+    #[test]
+    fn drag() {
+        let root = gen_root();
+        let controller = RefCell::new(GestureController::default());
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let child = &root.get_children()[0];
+        record(&controller, &root, Recognizer::Drag, &events, "root");
+        record(&controller, child, Recognizer::Drag, &events, "child");
+        record(&controller, child, Recognizer::Click(1), &events, "click");
+        record(&controller, child, Recognizer::LongPress, &events, "long");
+        controller.borrow_mut().set_settings(GestureSettings { drag_threshold: 10.0, ..Default::default() });
+        let start = Instant::now();
+
+        // Moving past the slop only leaves the drags
+        press(&controller, &root, (50.0, 20.0), start);
+        moved(&controller, (50.0, 26.0));
+        assert_eq!(None, controller.borrow().get_next_deadline());
+        assert!(controller.borrow().is_active());
+
+        // The drag of the deepest view wins and follows the pointer to the release
+        moved(&controller, (50.0, 30.0));
+        moved(&controller, (60.0, 80.0));
+        release(&controller, &root, (60.0, 80.0), start);
+        assert_eq!(vec![
+            ("child", GestureEvent::DragStart { position: (50.0, 20.0) }),
+            ("child", GestureEvent::DragMove { position: (50.0, 30.0), delta: (0.0, 10.0) }),
+            ("child", GestureEvent::DragMove { position: (60.0, 80.0), delta: (10.0, 60.0) }),
+            ("child", GestureEvent::DragEnd { position: (60.0, 80.0), delta: (10.0, 60.0) }),
+        ], *events.borrow());
+
+        // Cancelling ends a running drag
+        events.borrow_mut().clear();
+        press(&controller, &root, (50.0, 70.0), start);
+        moved(&controller, (50.0, 90.0));
+        cancel(&controller);
+        assert_eq!(Some(&("root", GestureEvent::DragEnd { position: (50.0, 90.0), delta: (0.0, 20.0) })), events.borrow().last());
+        assert!(!controller.borrow().is_active());
+    }
+
+    #[test]
+    fn event_loop() {
+        let clock = ManualClock::new();
+        let mut event_loop = gen_event_loop(&[gen_set_update_info(0.0, 0.5)]);
+        event_loop.set_clock(Rc::new(clock.clone()));
+        event_loop.run_frame(&mut |_, _, _| ());
+        let events = Rc::new(RefCell::new(Vec::new()));
+        for recognizer in [Recognizer::LongPress, Recognizer::Click(2)] {
+            let events = Rc::clone(&events);
+            let gesture = Gesture::new(&event_loop.get_root().get_children()[0], recognizer, move |event| events.borrow_mut().push(*event));
+            event_loop.get_gesture_controller().borrow_mut().add(gesture);
+        }
+        let settings = event_loop.get_gesture_controller().borrow().get_settings();
+
+        // The loop wakes up when the press becomes a long press
+        event_loop.get_backend_mut().push_window_event(gen_cursor_moved(10.0, 10.0));
+        event_loop.get_backend_mut().push_window_event(gen_mouse_input(ElementState::Pressed));
+        let control_flow = event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!(winit::event_loop::ControlFlow::WaitUntil(clock.now() + settings.long_press_duration), control_flow);
+        clock.advance(settings.long_press_duration);
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!(vec![GestureEvent::LongPress { position: (10.0, 10.0) }], *events.borrow());
+        event_loop.get_backend_mut().push_window_event(gen_mouse_input(ElementState::Released));
+        event_loop.run_frame(&mut |_, _, _| ());
+
+        // Two quick clicks are a double click
+        events.borrow_mut().clear();
+        for _ in 0..2 {
+            event_loop.get_backend_mut().push_window_event(gen_mouse_input(ElementState::Pressed));
+            event_loop.get_backend_mut().push_window_event(gen_mouse_input(ElementState::Released));
+            event_loop.run_frame(&mut |_, _, _| ());
+            clock.advance(Duration::from_millis(100));
+        }
+        assert_eq!(vec![GestureEvent::Click { count: 2, position: (10.0, 10.0) }], *events.borrow());
+        assert!(!event_loop.get_gesture_controller().borrow().is_active());
+    }
+}
@@ -0,0 +1,737 @@
+use crate::view::{View, extent, children, style};
+use std::{cell::RefCell, rc::Rc, fs, path::PathBuf, time::SystemTime, hash::{Hash, Hasher}, collections::hash_map::DefaultHasher};
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+/// The contents of a layout file, this describes all the children of the root view
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Layout {
+    /// The children of the root view
+    #[serde(default)]
+    pub children: Vec<LayoutNode>,
+}
+
+/// Describes a single view in a layout file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LayoutNode {
+    /// The identity of the view, views with the same key keep their state across reloads.
+    /// Views without a key are matched with the view without a key at the same position among the unkeyed siblings
+    #[serde(default)]
+    pub key: Option<String>,
+    /// The extent update info of the view
+    pub extent: extent::ExtentUpdate,
+    /// The style of the view, it is replaced if the event loop uses a theme
+    #[serde(default)]
+    pub style: style::Style,
+    /// The kind of view used to select it in a theme
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// The classes used to select the view in a theme
+    #[serde(default)]
+    pub classes: Vec<String>,
+    /// The children of the view
+    #[serde(default)]
+    pub children: Vec<LayoutNode>,
+}
+
+impl Layout {
+    /// Parses a layout from a string and validates it
+    /// 
+    /// # Parameters
+    /// 
+    /// source: The contents of the layout file
+    pub fn parse(source: &str) -> Result<Self, LayoutError> {
+        let layout: Self = ron::from_str(source)?;
+        layout.validate()?;
+
+        Ok(layout)
+    }
+
+    /// Makes sure all views in the layout have valid references and values and that no siblings share a key, warnings are ignored
+    pub fn validate(&self) -> Result<(), LayoutError> {
+        LayoutNode::check_keys(&self.children, &mut Vec::new())?;
+
+        let report = self.diagnose();
+        if !report.has_errors() {
+            Ok(())
+        } else {
+            Err(LayoutError::InvalidViews(report))
+        }
+    }
+
+    /// Finds all problems in the layout including warnings
+    pub fn diagnose(&self) -> extent::DiagnosticReport {
+        let mut report = extent::DiagnosticReport::new();
+        LayoutNode::diagnose_list(&self.children, &mut Vec::new(), &mut report.diagnostics);
+
+        report
+    }
+}
+
+impl LayoutNode {
+    /// Makes sure no siblings share a key in a list of siblings and all of their children
+    /// 
+    /// # Parameters
+    /// 
+    /// nodes: The siblings to check
+    /// 
+    /// path: The child indices from the root to the parent of the siblings
+    fn check_keys(nodes: &[LayoutNode], path: &mut Vec<usize>) -> Result<(), LayoutError> {
+        for (pos, node) in nodes.iter().enumerate() {
+            path.push(pos);
+
+            if let Some(key) = &node.key {
+                if nodes[..pos].iter().any(|sibling| sibling.key.as_ref() == Some(key)) {
+                    return Err(LayoutError::DuplicateKey(path.clone(), key.clone()));
+                }
+            }
+
+            Self::check_keys(&node.children, path)?;
+
+            path.pop();
+        }
+
+        Ok(())
+    }
+
+    /// Finds the extent problems of a list of siblings and all of their children, each view is checked against stand-ins of its older siblings
+    /// 
+    /// # Parameters
+    /// 
+    /// nodes: The siblings to check
+    /// 
+    /// path: The child indices from the root to the parent of the siblings
+    /// 
+    /// diagnostics: The list to add the problems to
+    fn diagnose_list(nodes: &[LayoutNode], path: &mut Vec<usize>, diagnostics: &mut Vec<extent::Diagnostic>) {
+        // Create stand-in controllers for the siblings to check against
+        let controllers: Vec<Rc<RefCell<extent::ExtentController>>> = nodes
+            .iter()
+            .map(|node| Rc::new(RefCell::new(extent::ExtentController::new(node.extent))))
+            .collect();
+
+        for (pos, node) in nodes.iter().enumerate() {
+            path.push(pos);
+            diagnostics.extend(node.extent.diagnose(&controllers[..pos], path));
+            Self::diagnose_list(&node.children, path, diagnostics);
+            path.pop();
+        }
+    }
+
+    /// Sets the style and the theme selection information of a view
+    /// 
+    /// # Parameters
+    /// 
+    /// controller: The style controller of the view
+    fn apply_style(&self, controller: &mut style::StyleController) {
+        // The style is only marked as changed if it is different
+        controller.set_style(self.style);
+        controller.set_tag(self.tag.clone());
+        controller.set_classes(self.classes.clone());
+    }
+
+    /// Creates the view described by this node including all of its children
+    /// 
+    /// # Parameters
+    /// 
+    /// parent_scheduler: The children scheduler of the parent view
+    fn build(&self, parent_scheduler: &Rc<RefCell<children::ChildrenScheduler>>) -> Result<(Box<View>, LayoutHandle), LayoutError> {
+        let view = View::new(self.extent, Some(Rc::clone(parent_scheduler)));
+        self.apply_style(&mut view.get_style_controller().borrow_mut());
+        let children_scheduler = view.get_children_scheduler();
+
+        // Create all the children and schedule them to be added
+        let mut children = Vec::new();
+        for node in self.children.iter() {
+            let (child, handle) = node.build(&children_scheduler)?;
+            children_scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child))?;
+            children.push(handle);
+        }
+
+        let handle = LayoutHandle { key: self.key.clone(), extent_controller: view.get_extent_controller(), style_controller: view.get_style_controller(), children_scheduler, children };
+
+        Ok((view, handle))
+    }
+
+    /// Checks that the descendants of the node can be added without building any view, the references are checked against stand-ins
+    fn check(&self) -> Result<(), LayoutError> {
+        let mut stand_ins = Vec::new();
+        for node in self.children.iter() {
+            children::ChildrenScheduleOperation::Push(View::new(node.extent, None)).simulate(&mut stand_ins)?;
+            node.check()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The handles used to change a live view which was created from a layout file
+#[derive(Clone, Debug)]
+struct LayoutHandle {
+    /// The key of the view
+    key: Option<String>,
+    /// The extent controller of the view
+    extent_controller: Rc<RefCell<extent::ExtentController>>,
+    /// The style controller of the view
+    style_controller: Rc<RefCell<style::StyleController>>,
+    /// The children scheduler of the view
+    children_scheduler: Rc<RefCell<children::ChildrenScheduler>>,
+    /// The handles for all the children in the order they will have once the scheduler has been resolved
+    children: Vec<LayoutHandle>,
+}
+
+/// The changes which turn the live children of a view into the ones described by a list of nodes, it is created without changing any live view
+#[derive(Debug)]
+struct ListPlan<'a> {
+    /// True if views are added, removed or reordered
+    structural: bool,
+    /// The operations to push to the children scheduler, they are only pushed if the list is structural
+    operations: Vec<PlanOperation<'a>>,
+    /// The new children in their new order
+    entries: Vec<PlanEntry<'a>>,
+}
+
+/// An operation in a plan, the views of new nodes are only built once the plan is applied
+#[derive(Debug)]
+enum PlanOperation<'a> {
+    /// Delete the view at a position
+    Delete(usize),
+    /// Move a view from one position to another
+    Move(usize, usize),
+    /// Build the view for a node and insert it at a position
+    Insert(&'a LayoutNode, usize),
+}
+
+/// A child in a plan
+#[derive(Debug)]
+enum PlanEntry<'a> {
+    /// A view which is kept with its current position, its new description and the plan for its children
+    Kept(usize, &'a LayoutNode, ListPlan<'a>),
+    /// A view which is built and inserted when the plan is applied
+    New,
+}
+
+impl LayoutHandle {
+    /// Changes the current children into the ones described by the nodes.
+    /// Views which are matched by identity keep their state, the rest are deleted or created.
+    /// 
+    /// All changes are planned and checked first, nothing is changed if the plan fails.
+    /// The final references of the nodes must have been validated beforehand
+    /// 
+    /// # Parameters
+    /// 
+    /// handles: The handles for the current children
+    /// 
+    /// scheduler: The children scheduler of the parent
+    /// 
+    /// nodes: The new children
+    fn apply_list(handles: &mut Vec<LayoutHandle>, scheduler: &Rc<RefCell<children::ChildrenScheduler>>, nodes: &[LayoutNode]) -> Result<(), LayoutError> {
+        let plan = Self::plan_list(handles, nodes)?;
+        Self::commit_list(handles, scheduler, plan)
+    }
+
+    /// Finds the operations needed to turn the current children into the ones described by the nodes.
+    /// The operations are checked against stand-ins of the children and no view is built, so no live view is changed
+    /// 
+    /// # Parameters
+    /// 
+    /// handles: The handles for the current children
+    /// 
+    /// nodes: The new children
+    fn plan_list<'a>(handles: &[LayoutHandle], nodes: &'a [LayoutNode]) -> Result<ListPlan<'a>, LayoutError> {
+        // Find the old view for each of the new nodes
+        let matches = Self::match_list(handles, nodes);
+
+        // Only change the structure if views have been added, removed or reordered
+        let structural = handles.len() != nodes.len() || matches.iter().enumerate().any(|(pos, old)| *old != Some(pos));
+        let mut operations = Vec::new();
+
+        if structural {
+            // The views which are kept lose their references while they are rearranged so their stand-ins start without any
+            let mut stand_ins: Vec<Rc<RefCell<extent::ExtentController>>> = handles
+                .iter()
+                .enumerate()
+                .map(|(old, handle)| {
+                    let update_info = if matches.contains(&Some(old)) { placeholder_update_info() } else { handle.extent_controller.borrow().get_update_info() };
+                    Rc::new(RefCell::new(extent::ExtentController::new(update_info)))
+                })
+                .collect();
+            let mut push = |operation: PlanOperation<'a>, stand_in: children::ChildrenScheduleOperation| -> Result<(), LayoutError> {
+                stand_in.simulate(&mut stand_ins)?;
+                operations.push(operation);
+                Ok(())
+            };
+
+            // Delete views which are not kept, going from the back so positions stay valid
+            let mut current: Vec<Option<usize>> = (0..handles.len()).map(Some).collect();
+            for old in (0..current.len()).rev() {
+                if !matches.contains(&Some(old)) {
+                    push(PlanOperation::Delete(old), children::ChildrenScheduleOperation::Delete(old))?;
+                    current.remove(old);
+                }
+            }
+
+            // Move or insert the views front to back so all older siblings are final when a view is placed
+            for (pos, (node, old)) in nodes.iter().zip(matches.iter()).enumerate() {
+                match old {
+                    Some(old) => {
+                        let from = current.iter().position(|check| *check == Some(*old)).unwrap();
+                        if from != pos {
+                            push(PlanOperation::Move(from, pos), children::ChildrenScheduleOperation::Move(from, pos))?;
+                            let moved = current.remove(from);
+                            current.insert(pos, moved);
+                        }
+                    }
+
+                    // The new view and its descendants are checked through stand-ins, it is only built once the plan is applied
+                    None => {
+                        node.check()?;
+                        push(PlanOperation::Insert(node, pos), children::ChildrenScheduleOperation::Insert(View::new(node.extent, None), pos))?;
+                        current.insert(pos, None);
+                    }
+                }
+            }
+        }
+
+        // Plan the children of all the views which are kept
+        let mut entries = Vec::new();
+        for (node, old) in nodes.iter().zip(matches.iter()) {
+            let entry = match old {
+                Some(old) => PlanEntry::Kept(*old, node, Self::plan_list(&handles[*old].children, &node.children)?),
+                None => PlanEntry::New,
+            };
+            entries.push(entry);
+        }
+
+        Ok(ListPlan { structural, operations, entries })
+    }
+
+    /// Applies a plan to the live children
+    /// 
+    /// # Parameters
+    /// 
+    /// handles: The handles for the current children, they are replaced by the handles in the new order
+    /// 
+    /// scheduler: The children scheduler of the parent
+    /// 
+    /// plan: The plan created for the handles
+    fn commit_list(handles: &mut Vec<LayoutHandle>, scheduler: &Rc<RefCell<children::ChildrenScheduler>>, plan: ListPlan) -> Result<(), LayoutError> {
+        let mut created: Vec<Option<LayoutHandle>> = plan.entries.iter().map(|_| None).collect();
+        if plan.structural {
+            // Remove all references from views which are kept, they could otherwise be invalid during the intermediate steps
+            for entry in plan.entries.iter() {
+                if let PlanEntry::Kept(old, ..) = entry {
+                    handles[*old].extent_controller.borrow_mut().set_update_info(placeholder_update_info());
+                }
+            }
+
+            // The new views are built and inserted in the order they were planned
+            for operation in plan.operations {
+                let operation = match operation {
+                    PlanOperation::Delete(pos) => children::ChildrenScheduleOperation::Delete(pos),
+                    PlanOperation::Move(from, to) => children::ChildrenScheduleOperation::Move(from, to),
+                    PlanOperation::Insert(node, pos) => {
+                        let (view, handle) = node.build(scheduler)?;
+                        created[pos] = Some(handle);
+                        children::ChildrenScheduleOperation::Insert(view, pos)
+                    }
+                };
+                scheduler.borrow_mut().push_operation(operation)?;
+            }
+        }
+
+        // Update the extents and children of all the views which were kept
+        let mut current: Vec<Option<LayoutHandle>> = handles.drain(..).map(Some).collect();
+        for (entry, created) in plan.entries.into_iter().zip(created) {
+            let handle = match entry {
+                PlanEntry::Kept(old, node, children) => {
+                    let mut handle = current[old].take().unwrap();
+                    handle.apply(node, children)?;
+                    handle
+                }
+                PlanEntry::New => created.unwrap(),
+            };
+            handles.push(handle);
+        }
+
+        Ok(())
+    }
+
+    /// Updates the extent, style and children of a view which was kept
+    /// 
+    /// # Parameters
+    /// 
+    /// node: The new description of the view
+    /// 
+    /// children: The plan for the children of the view
+    fn apply(&mut self, node: &LayoutNode, children: ListPlan) -> Result<(), LayoutError> {
+        // Only replace the extent if it has changed
+        if self.extent_controller.borrow().get_update_info() != node.extent {
+            self.extent_controller.borrow_mut().set_update_info(node.extent);
+        }
+
+        node.apply_style(&mut self.style_controller.borrow_mut());
+
+        self.key = node.key.clone();
+        Self::commit_list(&mut self.children, &self.children_scheduler, children)
+    }
+
+    /// Finds the position of the old view for each of the new nodes, None if it is a new view
+    /// 
+    /// # Parameters
+    /// 
+    /// handles: The handles for the current children
+    /// 
+    /// nodes: The new children
+    fn match_list(handles: &[LayoutHandle], nodes: &[LayoutNode]) -> Vec<Option<usize>> {
+        let unkeyed: Vec<usize> = handles.iter().enumerate().filter(|(_, handle)| handle.key.is_none()).map(|(pos, _)| pos).collect();
+        let mut unkeyed_count = 0;
+
+        nodes
+            .iter()
+            .map(|node| match &node.key {
+                // Match by key
+                Some(key) => handles.iter().position(|handle| handle.key.as_ref() == Some(key)),
+
+                // Match with the next unkeyed view
+                None => {
+                    let old = unkeyed.get(unkeyed_count).copied();
+                    unkeyed_count += 1;
+                    old
+                }
+            })
+            .collect()
+    }
+}
+
+/// Creates the update info used for views while they are being rearranged, it has no references to other views
+fn placeholder_update_info() -> extent::ExtentUpdate {
+    let locate = extent::ExtentLocate { pos: extent::PositionType::Set(0.0), size: extent::SizeType::Set(1.0) };
+    let update_single = extent::ExtentUpdateSingle { extent_type: extent::ExtentUpdateType::Locate(locate), scale_rel: 1.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0 };
+
+    extent::ExtentUpdate { x: update_single, y: update_single }
+}
+
+/// Hashes the contents of a layout file to tell if they have changed
+///
+/// # Parameters
+///
+/// source: The contents of the file
+fn hash_source(source: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+/// Watches a layout file and applies any changes to the views created from it
+#[derive(Clone, Debug)]
+pub struct LayoutWatcher {
+    /// The path of the layout file
+    path: PathBuf,
+    /// The modification time of the file when it was last read
+    modified: Option<SystemTime>,
+    /// The hash of the contents of the file when it was last read, it is compared when the modification time does not show that nothing changed
+    hash: Option<u64>,
+    /// The children scheduler of the root view
+    root_scheduler: Rc<RefCell<children::ChildrenScheduler>>,
+    /// The handles for all the children of the root view
+    children: Vec<LayoutHandle>,
+}
+
+impl LayoutWatcher {
+    /// Loads a layout file and creates a root view with all the views described in it
+    /// 
+    /// # Parameters
+    /// 
+    /// path: The path of the layout file
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use views::{layout::LayoutWatcher, event_loop::EventLoop};
+    /// 
+    /// let (root, mut watcher) = LayoutWatcher::new("layout.ron").unwrap();
+    /// let event_loop = EventLoop::new(*root).unwrap();
+    /// 
+    /// event_loop.run(move |_event, _window_target, _control_flow| {
+    ///     if let Err(err) = watcher.poll() {
+    ///         eprintln!("{}", err);
+    ///     }
+    /// });
+    /// ```
+    pub fn new(path: impl Into<PathBuf>) -> Result<(Box<View>, Self), LayoutError> {
+        let path = path.into();
+        let modified = fs::metadata(&path)?.modified().ok();
+        let source = fs::read_to_string(&path)?;
+        let layout = Layout::parse(&source)?;
+
+        let (root, mut watcher) = Self::from_layout(&layout)?;
+        watcher.path = path;
+        watcher.modified = modified;
+        watcher.hash = Some(hash_source(&source));
+
+        Ok((root, watcher))
+    }
+
+    /// Creates a root view with all the views described by the layout, the watcher will not be attached to any file
+    /// 
+    /// # Parameters
+    /// 
+    /// layout: The layout to create the views from
+    pub fn from_layout(layout: &Layout) -> Result<(Box<View>, Self), LayoutError> {
+        layout.validate()?;
+
+        let root = View::new_root();
+        let root_scheduler = root.get_children_scheduler();
+        let mut watcher = Self { path: PathBuf::new(), modified: None, hash: None, root_scheduler, children: Vec::new() };
+        LayoutHandle::apply_list(&mut watcher.children, &watcher.root_scheduler, &layout.children)?;
+
+        Ok((root, watcher))
+    }
+
+    /// Checks if the layout file has been modified and applies the changes if it has.
+    /// Returns true if changes were applied.
+    /// 
+    /// If the file is invalid an error is returned and the current views are left untouched,
+    /// the same version of the file is not reported again.
+    pub fn poll(&mut self) -> Result<bool, LayoutError> {
+        let modified = fs::metadata(&self.path)?.modified().ok();
+
+        // Nothing has changed since last time
+        if modified.is_some() && modified == self.modified {
+            return Ok(false);
+        }
+
+        // The contents are compared if the file system has no modification times or the file was only touched
+        let source = fs::read_to_string(&self.path)?;
+        let hash = hash_source(&source);
+        self.modified = modified;
+        if Some(hash) == self.hash {
+            return Ok(false);
+        }
+
+        self.hash = Some(hash);
+        self.apply(&Layout::parse(&source)?)?;
+
+        Ok(true)
+    }
+
+    /// Reads the layout file and applies it, whether or not it has been modified
+    pub fn reload(&mut self) -> Result<(), LayoutError> {
+        let layout = Layout::parse(&fs::read_to_string(&self.path)?)?;
+        self.apply(&layout)
+    }
+
+    /// Applies a layout to the views, views with the same identity keep their state.
+    /// The changes are scheduled and take effect at the next update of the root view.
+    /// 
+    /// If the layout is invalid an error is returned and nothing is changed
+    /// 
+    /// # Parameters
+    /// 
+    /// layout: The new layout
+    pub fn apply(&mut self, layout: &Layout) -> Result<(), LayoutError> {
+        layout.validate()?;
+        LayoutHandle::apply_list(&mut self.children, &self.root_scheduler, &layout.children)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum LayoutError {
+    #[error("Unable to read the layout file: {:?}", .0)]
+    Io(std::io::Error),
+    #[error("Unable to parse the layout file: {}", .0)]
+    Parse(ron::error::SpannedError),
+    #[error("The layout has invalid views:\n{}", .0)]
+    InvalidViews(extent::DiagnosticReport),
+    #[error("The view at {:?} uses the key {:?} which is already used by a sibling", .0, .1)]
+    DuplicateKey(Vec<usize>, String),
+    #[error("Unable to schedule the layout changes: {}", .0)]
+    Schedule(children::ValidateError),
+}
+
+impl From<std::io::Error> for LayoutError {
+    fn from(err: std::io::Error) -> LayoutError {
+        LayoutError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for LayoutError {
+    fn from(err: ron::error::SpannedError) -> LayoutError {
+        LayoutError::Parse(err)
+    }
+}
+
+impl From<children::ValidateError> for LayoutError {
+    fn from(err: children::ValidateError) -> LayoutError {
+        LayoutError::Schedule(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Color;
+
+    fn gen_node(key: Option<&str>, pos: f32, children: Vec<LayoutNode>) -> LayoutNode {
+        let mut extent = placeholder_update_info();
+        extent.x.extent_type = extent::ExtentUpdateType::Locate(extent::ExtentLocate { pos: extent::PositionType::Set(pos), size: extent::SizeType::Set(0.1) });
+        LayoutNode { key: key.map(String::from), extent, style: style::Style::default(), tag: None, classes: Vec::new(), children }
+    }
+
+    fn gen_prev_node(key: Option<&str>) -> LayoutNode {
+        let mut extent = placeholder_update_info();
+        extent.x.extent_type = extent::ExtentUpdateType::Locate(extent::ExtentLocate { pos: extent::PositionType::Anchor(extent::AnchorPoint { ref_view: extent::RefView::Prev, ref_point: 1.0 }), size: extent::SizeType::Set(0.1) });
+        LayoutNode { key: key.map(String::from), extent, style: style::Style::default(), tag: None, classes: Vec::new(), children: Vec::new() }
+    }
+
+    fn root_keys(root: &mut View) -> Vec<f32> {
+        root.resolve_children();
+        root.get_children().iter().map(|child| match child.get_extent_controller().borrow().get_update_info().x.extent_type {
+            extent::ExtentUpdateType::Locate(extent::ExtentLocate { pos: extent::PositionType::Set(pos), .. }) => pos,
+            _ => -1.0,
+        }).collect()
+    }
+
+    #[test]
+    fn parse() {
+        let source = "(children: [(key: Some(\"a\"), extent: (x: (extent_type: Locate((pos: Set(0.0), size: Set(0.5)))), y: (extent_type: Locate((pos: Set(0.0), size: Set(1.0))))), style: (background: Some((r: 255, g: 0, b: 0, a: 255)), border_width: 2.0)), (extent: (x: (extent_type: Locate((pos: Anchor((ref_view: Prev, ref_point: 1.0)), size: Set(0.5))), offset_abs: 0.1), y: (extent_type: Locate((pos: Set(0.0), size: Set(1.0))))))])";
+        let layout = Layout::parse(source).unwrap();
+        assert_eq!(2, layout.children.len());
+        assert_eq!(Some("a".to_string()), layout.children[0].key);
+        assert_eq!(1.0, layout.children[1].extent.x.scale_rel);
+        assert_eq!(0.1, layout.children[1].extent.x.offset_abs);
+        assert_eq!(Some(Color::RED), layout.children[0].style.background);
+        assert_eq!(2.0, layout.children[0].style.border_width);
+        assert_eq!(1.0, layout.children[0].style.opacity);
+        assert_eq!(style::Style::default(), layout.children[1].style);
+
+        assert!(matches!(Layout::parse("(children: [(extent: ()"), Err(LayoutError::Parse(_))));
+    }
+
+    #[test]
+    fn validate() {
+        let layout_valid = Layout { children: vec![gen_node(None, 0.0, vec![gen_node(None, 0.0, Vec::new()), gen_prev_node(None)]), gen_prev_node(None)] };
+        assert!(layout_valid.validate().is_ok());
+
+        let layout_no_prev = Layout { children: vec![gen_node(None, 0.0, vec![gen_prev_node(None)])] };
+        assert!(matches!(layout_no_prev.validate(), Err(LayoutError::InvalidViews(report)) if report.diagnostics.len() == 1 && report.diagnostics[0].path == vec![0, 0]));
+
+        let layout_many = Layout { children: vec![gen_prev_node(None), gen_node(None, 0.0, vec![gen_prev_node(None)])] };
+        let report = layout_many.diagnose();
+        assert_eq!(2, report.diagnostics.len());
+        assert_eq!(vec![0], report.diagnostics[0].path);
+        assert_eq!(Some(extent::Dim::X), report.diagnostics[0].dim);
+        assert_eq!(vec![1, 0], report.diagnostics[1].path);
+
+        let layout_duplicate = Layout { children: vec![gen_node(Some("a"), 0.0, Vec::new()), gen_node(Some("a"), 0.0, Vec::new())] };
+        assert!(matches!(layout_duplicate.validate(), Err(LayoutError::DuplicateKey(path, key)) if path == vec![1] && key == "a"));
+    }
+
+    #[test]
+    fn apply() {
+        let layout = Layout { children: vec![gen_node(Some("a"), 0.0, Vec::new()), gen_node(Some("b"), 1.0, Vec::new()), gen_node(Some("c"), 2.0, Vec::new())] };
+        let (mut root, mut watcher) = LayoutWatcher::from_layout(&layout).unwrap();
+        assert_eq!(vec![0.0, 1.0, 2.0], root_keys(&mut root));
+        let controller_a = watcher.children[0].extent_controller.clone();
+        let controller_c = watcher.children[2].extent_controller.clone();
+
+        // Reorder, delete and insert
+        let layout_new = Layout { children: vec![gen_node(Some("c"), 2.0, Vec::new()), gen_node(Some("d"), 3.0, Vec::new()), gen_node(Some("a"), 4.0, Vec::new()), gen_prev_node(None)] };
+        watcher.apply(&layout_new).unwrap();
+        assert_eq!(vec![2.0, 3.0, 4.0, -1.0], root_keys(&mut root));
+
+        // Views with the same key keep their state
+        assert!(Rc::ptr_eq(&controller_c, &root.get_children()[0].get_extent_controller()));
+        assert!(Rc::ptr_eq(&controller_a, &root.get_children()[2].get_extent_controller()));
+        assert_eq!(layout_new.children[3].extent, root.get_children()[3].get_extent_controller().borrow().get_update_info());
+
+        // Style changes reach the kept views
+        let mut layout_styled = layout_new.clone();
+        layout_styled.children[0].style.background = Some(Color::BLUE);
+        layout_styled.children[0].classes = vec!["selected".to_string()];
+        watcher.apply(&layout_styled).unwrap();
+        assert_eq!(Some(Color::BLUE), root.get_children()[0].get_style().background);
+        assert!(root.get_children()[0].get_style_controller().borrow().has_class("selected"));
+    }
+
+    #[test]
+    fn apply_children() {
+        let layout = Layout { children: vec![gen_node(Some("a"), 0.0, vec![gen_node(None, 0.0, Vec::new())])] };
+        let (mut root, mut watcher) = LayoutWatcher::from_layout(&layout).unwrap();
+        root.resolve_children();
+        assert_eq!(1, root.get_children()[0].get_children().len());
+
+        let layout_new = Layout { children: vec![gen_node(Some("a"), 0.0, vec![gen_node(None, 0.0, Vec::new()), gen_prev_node(None)])] };
+        watcher.apply(&layout_new).unwrap();
+        root.resolve_children();
+        assert_eq!(2, root.get_children()[0].get_children().len());
+    }
+
+    #[test]
+    fn apply_invalid() {
+        let layout = Layout { children: vec![gen_node(Some("a"), 0.0, Vec::new())] };
+        let (mut root, mut watcher) = LayoutWatcher::from_layout(&layout).unwrap();
+
+        let layout_invalid = Layout { children: vec![gen_prev_node(None), gen_node(Some("a"), 1.0, Vec::new())] };
+        assert!(watcher.apply(&layout_invalid).is_err());
+        assert_eq!(vec![0.0], root_keys(&mut root));
+    }
+
+    #[test]
+    fn apply_failed_plan() {
+        let second = gen_prev_node(Some("b"));
+        let layout = Layout { children: vec![gen_node(Some("a"), 0.0, Vec::new()), second.clone()] };
+        let (mut root, mut watcher) = LayoutWatcher::from_layout(&layout).unwrap();
+        root.resolve_children();
+
+        // Inserting a view which needs a previous sibling at the front fails after the first view was planned to be deleted
+        let nodes = vec![gen_prev_node(Some("c")), second];
+        assert!(LayoutHandle::apply_list(&mut watcher.children, &watcher.root_scheduler, &nodes).is_err());
+
+        // The live views and the handles are untouched
+        assert!(!root.get_children_scheduler().borrow().has_operations());
+        assert_eq!(2, watcher.children.len());
+        assert_eq!(layout.children[1].extent, watcher.children[1].extent_controller.borrow().get_update_info());
+        assert_eq!(vec![0.0, -1.0], root_keys(&mut root));
+
+        // Planning a new view with children does not schedule anything on the live views when a later kept view fails
+        let nodes = vec![gen_node(Some("c"), 2.0, vec![gen_node(None, 0.0, Vec::new())]), gen_node(Some("a"), 0.0, vec![gen_prev_node(None)])];
+        assert!(LayoutHandle::apply_list(&mut watcher.children, &watcher.root_scheduler, &nodes).is_err());
+        assert!(!root.get_children_scheduler().borrow().has_operations());
+
+        // A valid layout still applies afterwards
+        watcher.apply(&Layout { children: vec![gen_node(Some("c"), 2.0, Vec::new())] }).unwrap();
+        assert_eq!(vec![2.0], root_keys(&mut root));
+    }
+
+    #[test]
+    fn poll() {
+        let path = std::env::temp_dir().join(format!("views_layout_poll_{}.ron", std::process::id()));
+        let source = "(children: [(extent: (x: (extent_type: Locate((pos: Set(0.0), size: Set(0.5)))), y: (extent_type: Locate((pos: Set(0.0), size: Set(1.0))))))])";
+        fs::write(&path, source).unwrap();
+
+        let (mut root, mut watcher) = LayoutWatcher::new(&path).unwrap();
+        assert!(!watcher.poll().unwrap());
+        assert_eq!(vec![0.0], root_keys(&mut root));
+
+        // Without a matching modification time the contents are compared
+        watcher.modified = None;
+        assert!(!watcher.poll().unwrap());
+        assert!(!root.get_children_scheduler().borrow().has_operations());
+
+        // An invalid file is reported once and the views are kept
+        fs::write(&path, "(children: [").unwrap();
+        watcher.modified = None;
+        assert!(watcher.poll().is_err());
+        assert_eq!(vec![0.0], root_keys(&mut root));
+
+        fs::write(&path, source.replace("Set(0.0), size: Set(0.5)", "Set(0.25), size: Set(0.5)")).unwrap();
+        watcher.modified = None;
+        assert!(watcher.poll().unwrap());
+        assert_eq!(vec![0.25], root_keys(&mut root));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,2 +1,15 @@
 pub mod view;
-pub mod event_loop;
\ No newline at end of file
+pub mod event_loop;
+pub mod layout;
+pub mod theme;
+pub mod render;
+pub mod animation;
+pub mod timer;
+pub mod clock;
+pub mod shortcut;
+pub mod drag;
+pub mod gesture;
+pub mod testing;
+
+#[cfg(test)]
+mod fixtures;
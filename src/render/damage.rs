@@ -0,0 +1,137 @@
+use super::Rect;
+
+/// The most rectangles kept before the closest ones are merged, repainting a little more is cheaper than repainting many small areas
+const MAX_RECTS: usize = 8;
+
+/// The areas of the window in pixels whose content changed since they were last repainted.
+/// Overlapping and touching rectangles are merged so there are never more than a few of them
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Damage {
+    /// The damaged rectangles, they never overlap or touch
+    rects: Vec<Rect>,
+}
+
+impl Damage {
+    /// Creates new damage without any damaged areas
+    pub fn new() -> Self {
+        Self { rects: Vec::new() }
+    }
+
+    /// Gets the damaged rectangles, they never overlap
+    pub fn get_rects(&self) -> &[Rect] {
+        &self.rects
+    }
+
+    /// Checks if nothing is damaged
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Removes all damaged areas
+    pub fn clear(&mut self) {
+        self.rects.clear();
+    }
+
+    /// Gets the smallest rectangle containing all the damaged areas, None if nothing is damaged
+    pub fn get_bounds(&self) -> Option<Rect> {
+        self.rects.iter().copied().reduce(|bounds, rect| bounds.union(&rect))
+    }
+
+    /// Marks an area as damaged, empty and non-finite rectangles are ignored
+    ///
+    /// # Parameters
+    ///
+    /// rect: The damaged area in pixels
+    pub fn add(&mut self, rect: Rect) {
+        if !rect.is_finite() || rect.is_empty() {
+            return;
+        }
+
+        // Grow the rectangle until it does not touch any of the others
+        let mut rect = rect;
+        while let Some(pos) = self.rects.iter().position(|other| touches(&rect, other)) {
+            rect = rect.union(&self.rects.swap_remove(pos));
+        }
+        self.rects.push(rect);
+
+        // Merge the pair which adds the least area until there are few enough
+        while self.rects.len() > MAX_RECTS {
+            let mut best = (0, 1, f32::INFINITY);
+            for first in 0..self.rects.len() {
+                for second in first + 1..self.rects.len() {
+                    let (a, b) = (&self.rects[first], &self.rects[second]);
+                    let added = a.union(b).area() - a.area() - b.area();
+                    if added < best.2 {
+                        best = (first, second, added);
+                    }
+                }
+            }
+
+            let merged = self.rects.swap_remove(best.1).union(&self.rects.swap_remove(best.0));
+            self.add(merged);
+        }
+    }
+
+    /// Marks all the areas of other damage as damaged
+    ///
+    /// # Parameters
+    ///
+    /// other: The damage to include
+    pub fn extend(&mut self, other: &Damage) {
+        for rect in other.rects.iter() {
+            self.add(*rect);
+        }
+    }
+}
+
+/// Checks if two rectangles overlap or share an edge
+///
+/// # Parameters
+///
+/// a: The first rectangle
+///
+/// b: The second rectangle
+fn touches(a: &Rect, b: &Rect) -> bool {
+    a.x <= b.x + b.w && b.x <= a.x + a.w && a.y <= b.y + b.h && b.y <= a.y + a.h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add() {
+        let mut damage = Damage::new();
+        damage.add(Rect::new(0.0, 0.0, 2.0, 2.0));
+        damage.add(Rect::new(10.0, 0.0, 2.0, 2.0));
+        damage.add(Rect::new(0.0, 0.0, f32::NAN, 2.0));
+        damage.add(Rect::new(5.0, 5.0, 0.0, 2.0));
+        assert_eq!(2, damage.get_rects().len());
+
+        // A rectangle bridging both merges them into one
+        damage.add(Rect::new(2.0, 1.0, 8.0, 4.0));
+        assert_eq!(&[Rect::new(0.0, 0.0, 12.0, 5.0)], damage.get_rects());
+        assert_eq!(Some(Rect::new(0.0, 0.0, 12.0, 5.0)), damage.get_bounds());
+
+        damage.clear();
+        assert!(damage.is_empty());
+        assert_eq!(None, damage.get_bounds());
+    }
+
+    #[test]
+    fn limit() {
+        // A row of separate squares ends up with the closest ones merged
+        let mut damage = Damage::new();
+        for pos in 0..MAX_RECTS + 1 {
+            damage.add(Rect::new(pos as f32 * 10.0, 0.0, 1.0, 1.0));
+        }
+        damage.add(Rect::new(95.0, 50.0, 1.0, 1.0));
+        assert!(damage.get_rects().len() <= MAX_RECTS);
+        assert_eq!(Some(Rect::new(0.0, 0.0, 96.0, 51.0)), damage.get_bounds());
+
+        // Every square is still covered
+        for pos in 0..MAX_RECTS + 1 {
+            assert!(damage.get_rects().iter().any(|rect| rect.x <= pos as f32 * 10.0 && rect.x + rect.w >= pos as f32 * 10.0 + 1.0));
+        }
+    }
+}
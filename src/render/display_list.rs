@@ -0,0 +1,446 @@
+use crate::view::View;
+use super::{Color, Rect, Bitmap, TextRun, Painter};
+use serde::{Serialize, Deserialize};
+
+/// A single drawing command, all coordinates are absolute pixels with the origin in the top left corner of the window.
+/// Colors are stored as they were given, the layers they are inside of are faded as a whole when they are popped
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DrawCommand {
+    /// Blends a color on top of a rectangle
+    Rect {
+        /// The rectangle to fill
+        rect: Rect,
+        /// The color to fill with
+        color: Color,
+    },
+    /// Blends a color on top of a rounded rectangle
+    RoundedRect {
+        /// The rectangle to fill
+        rect: Rect,
+        /// The radius of the corners
+        radius: f32,
+        /// The color to fill with
+        color: Color,
+    },
+    /// Blends a color on top of the outline of a rounded rectangle, the outline is inside the rectangle
+    RoundedRectOutline {
+        /// The outer edge of the outline
+        rect: Rect,
+        /// The radius of the outer corners
+        radius: f32,
+        /// The width of the outline
+        width: f32,
+        /// The color to draw with
+        color: Color,
+    },
+    /// Blends a color on top of the pixels covered by a coverage mask
+    Mask {
+        /// The column of the left edge of the mask
+        x: i32,
+        /// The row of the top edge of the mask
+        y: i32,
+        /// The number of columns in the mask
+        width: usize,
+        /// The coverage of each pixel row by row
+        mask: Vec<u8>,
+        /// The color to draw with
+        color: Color,
+    },
+    /// Draws a line of text
+    Text(TextRun),
+    /// Blends a part of a bitmap scaled to a rectangle on top
+    Image {
+        /// The rectangle to draw the bitmap into
+        rect: Rect,
+        /// The bitmap to draw
+        bitmap: Bitmap,
+        /// The part of the bitmap to draw in pixels of the bitmap
+        source: Rect,
+    },
+    /// Narrows the clip rectangle until the matching pop
+    PushClip(Rect),
+    /// Restores the clip rectangle from before the matching push
+    PopClip,
+    /// Draws everything into a separate layer with an opacity until the matching pop
+    PushLayer(f32),
+    /// Fades the layer from the matching push as a whole and blends it on top of everything drawn before it
+    PopLayer,
+}
+
+impl DrawCommand {
+    /// Gets a rectangle containing every pixel the command may draw, None for the commands which only change the clip rectangle or the layers
+    pub fn get_bounds(&self) -> Option<Rect> {
+        match self {
+            Self::Rect { rect, .. } | Self::RoundedRect { rect, .. } | Self::RoundedRectOutline { rect, .. } | Self::Image { rect, .. } => Some(*rect),
+            Self::Mask { x, y, width, mask, .. } => Some(Rect::new(*x as f32, *y as f32, *width as f32, (mask.len() / (*width).max(1)) as f32)),
+            Self::Text(run) => Some(run.get_bounds()),
+            Self::PushClip(_) | Self::PopClip | Self::PushLayer(_) | Self::PopLayer => None,
+        }
+    }
+
+    /// Draws the command with a painter
+    ///
+    /// # Parameters
+    ///
+    /// painter: The painter to draw with
+    fn replay(&self, painter: &mut dyn Painter) {
+        match self {
+            Self::Rect { rect, color } => painter.fill_rect(*rect, *color),
+            Self::RoundedRect { rect, radius, color } => painter.fill_rounded_rect(*rect, *radius, *color),
+            Self::RoundedRectOutline { rect, radius, width, color } => painter.stroke_rounded_rect(*rect, *radius, *width, *color),
+            Self::Mask { x, y, width, mask, color } => painter.fill_mask(*x, *y, *width, mask, *color),
+            Self::Text(run) => painter.draw_text(run),
+            Self::Image { rect, bitmap, source } => painter.draw_bitmap(*rect, bitmap, *source),
+            Self::PushClip(rect) => painter.push_clip(*rect),
+            Self::PopClip => painter.pop_clip(),
+            Self::PushLayer(opacity) => painter.push_layer(*opacity),
+            Self::PopLayer => painter.pop_layer(),
+        }
+    }
+}
+
+/// A flat list of drawing commands describing a frame, every push has a matching pop.
+/// It does not depend on the views or any renderer so it can be stored, compared, serialized or sent elsewhere to be drawn
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DisplayList {
+    /// The commands in the order they are drawn
+    commands: Vec<DrawCommand>,
+}
+
+impl DisplayList {
+    /// Records the drawing commands for a laid out and styled view tree, the root covers the entire window.
+    /// The tree must have been updated first, only resolved children are recorded
+    ///
+    /// # Parameters
+    ///
+    /// root: The root of the view tree
+    ///
+    /// window_size: The size of the window in pixels as (w, h)
+    pub fn from_view(root: &View, window_size: (f32, f32)) -> Self {
+        let window = Rect::new(0.0, 0.0, window_size.0, window_size.1);
+        let mut painter = DisplayListPainter::new(window);
+        paint_view(root, window, &mut painter);
+
+        painter.finish()
+    }
+
+    /// Gets the commands in the order they are drawn
+    pub fn get_commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    /// Draws all the commands with a painter
+    ///
+    /// # Parameters
+    ///
+    /// painter: The painter to draw with
+    pub fn replay(&self, painter: &mut dyn Painter) {
+        for command in self.commands.iter() {
+            command.replay(painter);
+        }
+    }
+
+    /// Draws the commands inside an area with a painter, nothing outside of the area is drawn.
+    /// The commands which cannot draw inside the area are skipped
+    ///
+    /// # Parameters
+    ///
+    /// painter: The painter to draw with
+    ///
+    /// area: The rectangle to draw
+    pub fn replay_area(&self, painter: &mut dyn Painter, area: Rect) {
+        painter.push_clip(area);
+        for command in self.commands.iter() {
+            if command.get_bounds().is_some_and(|bounds| bounds.intersect(&area).is_empty()) {
+                continue;
+            }
+            command.replay(painter);
+        }
+        painter.pop_clip();
+    }
+}
+
+/// A painter recording everything drawn with it into a display list instead of drawing it
+#[derive(Clone, Debug)]
+pub struct DisplayListPainter {
+    /// The commands recorded so far
+    commands: Vec<DrawCommand>,
+    /// The stack of clip rectangles, the last one is the current one and the first one covers the entire window
+    clips: Vec<Rect>,
+    /// The stack of combined layer opacities, the last one is the current one and the first one is fully opaque
+    opacities: Vec<f32>,
+}
+
+impl DisplayListPainter {
+    /// Creates a new painter which may draw on the entire window
+    ///
+    /// # Parameters
+    ///
+    /// window: The rectangle of the window in pixels
+    pub fn new(window: Rect) -> Self {
+        Self { commands: Vec::new(), clips: vec![window], opacities: vec![1.0] }
+    }
+
+    /// Finishes recording, all clips and layers which are still pushed are popped
+    pub fn finish(mut self) -> DisplayList {
+        while self.clips.len() > 1 {
+            self.pop_clip();
+        }
+        while self.opacities.len() > 1 {
+            self.pop_layer();
+        }
+
+        DisplayList { commands: self.commands }
+    }
+}
+
+impl Painter for DisplayListPainter {
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        self.commands.push(DrawCommand::Rect { rect, color });
+    }
+
+    fn fill_rounded_rect(&mut self, rect: Rect, radius: f32, color: Color) {
+        self.commands.push(DrawCommand::RoundedRect { rect, radius, color });
+    }
+
+    fn stroke_rounded_rect(&mut self, rect: Rect, radius: f32, width: f32, color: Color) {
+        self.commands.push(DrawCommand::RoundedRectOutline { rect, radius, width, color });
+    }
+
+    fn fill_mask(&mut self, x: i32, y: i32, width: usize, mask: &[u8], color: Color) {
+        self.commands.push(DrawCommand::Mask { x, y, width, mask: mask.to_vec(), color });
+    }
+
+    fn draw_text(&mut self, run: &TextRun) {
+        self.commands.push(DrawCommand::Text(run.clone()));
+    }
+
+    fn draw_bitmap(&mut self, rect: Rect, bitmap: &Bitmap, source: Rect) {
+        self.commands.push(DrawCommand::Image { rect, bitmap: bitmap.clone(), source });
+    }
+
+    fn get_clip(&self) -> Rect {
+        // The stack is never empty
+        self.clips[self.clips.len() - 1]
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        let clip = self.get_clip().intersect(&rect);
+        self.clips.push(clip);
+        self.commands.push(DrawCommand::PushClip(rect));
+    }
+
+    fn pop_clip(&mut self) {
+        // Only pops which match a push are recorded
+        if self.clips.len() > 1 {
+            self.clips.pop();
+            self.commands.push(DrawCommand::PopClip);
+        }
+    }
+
+    fn get_opacity(&self) -> f32 {
+        // The stack is never empty
+        self.opacities[self.opacities.len() - 1]
+    }
+
+    fn push_layer(&mut self, opacity: f32) {
+        let opacity = if opacity.is_finite() { opacity.clamp(0.0, 1.0) } else { 1.0 };
+        self.opacities.push(self.get_opacity() * opacity);
+        self.commands.push(DrawCommand::PushLayer(opacity));
+    }
+
+    fn pop_layer(&mut self) {
+        // Only pops which match a push are recorded
+        if self.opacities.len() > 1 {
+            self.opacities.pop();
+            self.commands.push(DrawCommand::PopLayer);
+        }
+    }
+}
+
+/// Draws a view and all of its children in painter's order, a parent is drawn before its children and older siblings before younger ones
+///
+/// # Parameters
+///
+/// view: The view to draw
+///
+/// parent_rect: The rectangle of the parent in pixels
+///
+/// painter: The painter to draw with
+fn paint_view(view: &View, parent_rect: Rect, painter: &mut dyn Painter) {
+    let rect = parent_rect.sub_rect(view.get_extent());
+    let style = view.get_style();
+    let content_rect = rect.sub_rect(style.padding.content_extent());
+
+    // Fade the view and all of its children as a whole, an opaque view is drawn without a layer
+    let faded = style.opacity < 1.0;
+    if faded {
+        painter.push_layer(style.opacity);
+    }
+
+    if let Some(background) = style.background {
+        painter.fill_rounded_rect(rect, style.corner_radius, background);
+    }
+    if let Some(drawable) = view.get_drawable() {
+        drawable.draw(content_rect, &style, painter);
+    }
+    if style.border_width > 0.0 && style.border_color.a > 0 {
+        painter.stroke_rounded_rect(rect, style.corner_radius, style.border_width, style.border_color);
+    }
+
+    // Narrow the clip rectangle for the children if required
+    if view.get_clip() {
+        painter.push_clip(rect);
+    }
+
+    // Nothing can be drawn once the clip rectangle is empty or everything is invisible
+    if !painter.get_clip().is_empty() && painter.get_opacity() > 0.0 {
+        for child in view.get_children() {
+            paint_view(child, content_rect, painter);
+        }
+    }
+
+    if view.get_clip() {
+        painter.pop_clip();
+    }
+    if faded {
+        painter.pop_layer();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::gen_set_update_info;
+    use crate::{event_loop::{EventLoop, Headless}, render::{Font, Text, Framebuffer, FramebufferPainter}, view::{children, style}};
+    use std::rc::Rc;
+
+    /// A clipped root with a faded child showing text and a second child showing an image
+    fn gen_event_loop() -> EventLoop<(), Headless<()>> {
+        let mut root = View::new_root();
+        root.set_clip(true);
+        let scheduler = root.get_children_scheduler();
+        let text = View::new(gen_set_update_info(0.0, 0.5), Some(Rc::clone(&scheduler)));
+        let font = Font::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/fonts/DejaVuSansMono.ttf")).unwrap();
+        text.get_drawable_controller().borrow_mut().set_drawable(Some(Rc::new(Text::new("Hi", font, 10.0))));
+        text.get_style_controller().borrow_mut().set_style(style::Style { opacity: 0.5, text_color: Color::RED, ..style::Style::default() });
+        let image = View::new(gen_set_update_info(0.5, 0.5), Some(Rc::clone(&scheduler)));
+        image.get_drawable_controller().borrow_mut().set_drawable(Some(Rc::new(crate::render::Image::new(Bitmap::from_pixels(1, 1, vec![Color::BLUE]).unwrap(), crate::render::ScaleMode::Stretch))));
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(text)).unwrap();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(image)).unwrap();
+
+        let mut event_loop = EventLoop::from_backend(*root, Headless::<()>::new(40, 40)).unwrap();
+        event_loop.run_frame(&mut |_, _, _| ());
+
+        event_loop
+    }
+
+    #[test]
+    fn from_view() {
+        let event_loop = gen_event_loop();
+        let list = DisplayList::from_view(event_loop.get_root(), (40.0, 40.0));
+        let commands = list.get_commands();
+
+        // The opaque root only has its clip, a border without a width is not drawn
+        assert_eq!(DrawCommand::PushClip(Rect::new(0.0, 0.0, 40.0, 40.0)), commands[0]);
+        assert!(!commands.iter().any(|command| matches!(command, DrawCommand::RoundedRectOutline { .. })));
+
+        // The text is a single run in absolute coordinates inside the faded layer
+        assert_eq!(DrawCommand::PushLayer(0.5), commands[1]);
+        assert_eq!(DrawCommand::PushClip(Rect::new(0.0, 0.0, 20.0, 20.0)), commands[2]);
+        match &commands[3] {
+            DrawCommand::Text(run) => {
+                assert_eq!(("Hi", 0.0, Color::RED), (run.text.as_str(), run.x, run.color));
+                assert!(run.baseline > 0.0 && run.baseline < 20.0);
+            }
+            command => panic!("Expected text, got {:?}", command),
+        }
+        assert_eq!(DrawCommand::PopClip, commands[4]);
+        assert_eq!(DrawCommand::PopLayer, commands[5]);
+        assert_eq!(1, commands.iter().filter(|command| matches!(command, DrawCommand::PushLayer(_))).count());
+
+        // The image is scaled to the second child
+        assert!(commands.contains(&DrawCommand::Image { rect: Rect::new(20.0, 20.0, 20.0, 20.0), bitmap: Bitmap::from_pixels(1, 1, vec![Color::BLUE]).unwrap(), source: Rect::new(0.0, 0.0, 1.0, 1.0) }));
+
+        // Every push is popped
+        let pushes = commands.iter().filter(|command| matches!(command, DrawCommand::PushClip(_) | DrawCommand::PushLayer(_))).count();
+        let pops = commands.iter().filter(|command| matches!(command, DrawCommand::PopClip | DrawCommand::PopLayer)).count();
+        assert_eq!(pushes, pops);
+        assert_eq!(&DrawCommand::PopClip, commands.last().unwrap());
+    }
+
+    #[test]
+    fn serialize() {
+        let event_loop = gen_event_loop();
+        let list = DisplayList::from_view(event_loop.get_root(), (40.0, 40.0));
+
+        // Everything except the fonts survives a round trip
+        let serialized = ron::to_string(&list).unwrap();
+        let deserialized: DisplayList = ron::from_str(&serialized).unwrap();
+        assert_eq!(list.get_commands().len(), deserialized.get_commands().len());
+        for (command, other) in list.get_commands().iter().zip(deserialized.get_commands().iter()) {
+            match (command, other) {
+                (DrawCommand::Text(run), DrawCommand::Text(other)) => {
+                    assert_eq!(TextRun { font: None, ..run.clone() }, *other);
+                    assert_eq!(Some("DejaVu Sans Mono"), other.font_name.as_deref());
+                }
+                _ => assert_eq!(command, other),
+            }
+        }
+
+        // A bitmap with the wrong number of pixels is rejected
+        assert!(ron::from_str::<Bitmap>("(width: 2, height: 2, pixels: [])").is_err());
+    }
+
+    #[test]
+    fn replay() {
+        let event_loop = gen_event_loop();
+        let list = DisplayList::from_view(event_loop.get_root(), (40.0, 40.0));
+        let mut framebuffer = Framebuffer::new(40, 40);
+        list.replay(&mut FramebufferPainter::new(&mut framebuffer));
+
+        // The faded text and the image are drawn
+        let pixels: Vec<Color> = (0..20).flat_map(|y| (0..20).map(move |x| (x, y))).map(|(x, y)| framebuffer.get_pixel(x, y).unwrap()).collect();
+        assert!(pixels.iter().any(|pixel| pixel.r == 255 && pixel.a > 0));
+        assert!(pixels.iter().all(|pixel| pixel.a <= 128));
+        assert_eq!(Some(Color::BLUE), framebuffer.get_pixel(30, 30));
+    }
+
+    #[test]
+    fn replay_area() {
+        let event_loop = gen_event_loop();
+        let list = DisplayList::from_view(event_loop.get_root(), (40.0, 40.0));
+        let mut painter = DisplayListPainter::new(Rect::new(0.0, 0.0, 40.0, 40.0));
+        list.replay_area(&mut painter, Rect::new(30.0, 30.0, 5.0, 5.0));
+        let commands = painter.finish();
+        let commands = commands.get_commands();
+
+        // Only the image reaches the area, the text is skipped but its layer and clip are kept
+        assert_eq!(DrawCommand::PushClip(Rect::new(30.0, 30.0, 5.0, 5.0)), commands[0]);
+        assert!(!commands.iter().any(|command| matches!(command, DrawCommand::Text(_))));
+        assert!(commands.iter().any(|command| matches!(command, DrawCommand::Image { .. })));
+        assert!(commands.contains(&DrawCommand::PushLayer(0.5)));
+        assert_eq!(&DrawCommand::PopClip, commands.last().unwrap());
+    }
+
+    #[test]
+    fn faded_overlap() {
+        // A faded root with two opaque children covering each other
+        let root = View::new_root();
+        root.get_style_controller().borrow_mut().set_style(style::Style { opacity: 0.5, ..style::Style::default() });
+        let scheduler = root.get_children_scheduler();
+        for color in [Color::RED, Color::BLUE] {
+            let child = View::new(gen_set_update_info(0.0, 1.0), Some(Rc::clone(&scheduler)));
+            child.get_style_controller().borrow_mut().set_style(style::Style { background: Some(color), ..style::Style::default() });
+            scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+        }
+        let mut event_loop = EventLoop::from_backend(*root, Headless::<()>::new(2, 2)).unwrap();
+        event_loop.run_frame(&mut |_, _, _| ());
+
+        // The covered child does not show through the faded root
+        let list = DisplayList::from_view(event_loop.get_root(), (2.0, 2.0));
+        let mut framebuffer = Framebuffer::new(2, 2);
+        list.replay(&mut FramebufferPainter::new(&mut framebuffer));
+        assert_eq!(Some(Color::new(0, 0, 255, 128)), framebuffer.get_pixel(1, 1));
+    }
+}
@@ -0,0 +1,129 @@
+use super::{Color, Rect, Painter};
+use crate::view::{style::Style, extent::Ratio};
+use std::{fmt::Debug, rc::Rc};
+
+/// Content which can be attached to a view to decide what it looks like.
+/// The layout only decides where the content goes, the content decides how it is drawn
+pub trait Drawable: Debug {
+    /// Draws the content
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The rectangle inside the padding of the view in pixels as resolved by the last update
+    /// 
+    /// style: The style of the view
+    /// 
+    /// painter: The painter to draw with
+    fn draw(&self, rect: Rect, style: &Style, painter: &mut dyn Painter);
+
+    /// Measures the natural size of the content in pixels as (w, h), this is used by intrinsic sizes.
+    /// None if the content has no natural size, which is the default
+    /// 
+    /// # Parameters
+    /// 
+    /// max_width: The width available to the content if it is known, content which wraps should fit inside it
+    fn measure(&self, _max_width: Option<f32>) -> Option<(f32, f32)> {
+        None
+    }
+
+    /// Gets the natural ratio of w to h of the content, this is used by extents with a ratio coming from the content.
+    /// The default is the ratio of the natural size without a width limit, None if there is no natural size
+    fn aspect_ratio(&self) -> Option<Ratio> {
+        self.measure(None).and_then(|(w, h)| Ratio::new(w, h))
+    }
+}
+
+/// A plain color covers the entire view
+impl Drawable for Color {
+    fn draw(&self, rect: Rect, _style: &Style, painter: &mut dyn Painter) {
+        painter.fill_rect(rect, *self);
+    }
+}
+
+/// A container for the content of a view, changing the content marks the view as dirty
+#[derive(Clone, Debug, Default)]
+pub struct DrawableController {
+    /// The content, None to draw nothing
+    drawable: Option<Rc<dyn Drawable>>,
+    /// True if the content changed since the view was last updated
+    dirty: bool,
+}
+
+impl DrawableController {
+    /// Gets the content, None if the view draws nothing
+    pub fn get_drawable(&self) -> Option<Rc<dyn Drawable>> {
+        self.drawable.clone()
+    }
+
+    /// Replaces the content and marks the view as dirty so it is laid out and drawn again
+    /// 
+    /// # Parameters
+    /// 
+    /// drawable: The new content, None to draw nothing
+    pub fn set_drawable(&mut self, drawable: Option<Rc<dyn Drawable>>) {
+        self.drawable = drawable;
+        self.dirty = true;
+    }
+
+    /// Marks the view as dirty, this must be done when a drawable which is shared changes what it draws or measures
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Checks if the content changed since the view was last updated
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Marks the content as up to date, this is done when the view is updated
+    pub(crate) fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{Framebuffer, FramebufferPainter};
+
+    /// Draws only the left half of the view, clipped to the view itself
+    #[derive(Debug)]
+    struct LeftHalf(Color);
+
+    impl Drawable for LeftHalf {
+        fn draw(&self, rect: Rect, _style: &Style, painter: &mut dyn Painter) {
+            painter.push_clip(rect);
+            painter.fill_rect(Rect::new(rect.x - rect.w, rect.y, rect.w * 1.5, rect.h), self.0);
+            painter.pop_clip();
+        }
+    }
+
+    #[test]
+    fn draw() {
+        let mut framebuffer = Framebuffer::new(4, 2);
+        let mut painter = FramebufferPainter::new(&mut framebuffer);
+        let drawables: Vec<Box<dyn Drawable>> = vec![Box::new(Color::BLUE), Box::new(LeftHalf(Color::RED))];
+        for drawable in drawables.iter() {
+            drawable.draw(Rect::new(1.0, 0.0, 2.0, 2.0), &Style::default(), &mut painter);
+        }
+
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(0, 1));
+        assert_eq!(Some(Color::RED), framebuffer.get_pixel(1, 1));
+        assert_eq!(Some(Color::BLUE), framebuffer.get_pixel(2, 1));
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(3, 1));
+    }
+
+    #[test]
+    fn controller() {
+        let mut controller = DrawableController::default();
+        assert!(controller.get_drawable().is_none());
+        assert!(!controller.is_dirty());
+
+        controller.set_drawable(Some(Rc::new(Color::RED)));
+        assert!(controller.is_dirty());
+        assert!(controller.get_drawable().is_some());
+        controller.clear_dirty();
+        controller.mark_dirty();
+        assert!(controller.is_dirty());
+    }
+}
@@ -0,0 +1,444 @@
+use super::{Color, Rect, Bitmap};
+
+/// An in memory image with 8 bit RGBA pixels stored row by row from the top left corner
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Framebuffer {
+    /// The width in pixels
+    width: u32,
+    /// The height in pixels
+    height: u32,
+    /// The pixel data, 4 bytes per pixel
+    pixels: Vec<u8>,
+}
+
+impl Framebuffer {
+    /// Creates a new fully transparent framebuffer
+    /// 
+    /// # Parameters
+    /// 
+    /// width: The width in pixels
+    /// 
+    /// height: The height in pixels
+    pub fn new(width: u32, height: u32) -> Self {
+        let pixels = vec![0; width as usize * height as usize * 4];
+
+        Self { width, height, pixels }
+    }
+
+    /// Gets the width in pixels
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    /// Gets the height in pixels
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Gets the rectangle covering the entire framebuffer
+    pub fn get_rect(&self) -> Rect {
+        Rect::new(0.0, 0.0, self.width as f32, self.height as f32)
+    }
+
+    /// Gets the raw RGBA pixel data
+    pub fn get_pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Gets the color of a single pixel, None if it is outside the framebuffer
+    /// 
+    /// # Parameters
+    /// 
+    /// x: The column of the pixel
+    /// 
+    /// y: The row of the pixel
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<Color> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let index = self.index(x, y);
+        Some(Color::new(self.pixels[index], self.pixels[index + 1], self.pixels[index + 2], self.pixels[index + 3]))
+    }
+
+    /// Sets the color of a single pixel without blending, pixels outside the framebuffer are ignored
+    /// 
+    /// # Parameters
+    /// 
+    /// x: The column of the pixel
+    /// 
+    /// y: The row of the pixel
+    /// 
+    /// color: The new color
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: Color) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let index = self.index(x, y);
+        self.pixels[index..index + 4].copy_from_slice(&[color.r, color.g, color.b, color.a]);
+    }
+
+    /// Sets every pixel to a single color
+    /// 
+    /// # Parameters
+    /// 
+    /// color: The color to fill with
+    pub fn clear(&mut self, color: Color) {
+        for pixel in self.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+    }
+
+    /// Replaces all pixels whose centers are inside the rectangle with a color without blending
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The rectangle to clear
+    /// 
+    /// color: The color to clear with
+    pub fn clear_rect(&mut self, rect: Rect, color: Color) {
+        if !rect.is_finite() {
+            return;
+        }
+
+        let (columns, rows) = self.pixel_range(rect);
+        for y in rows {
+            for x in columns.clone() {
+                let index = self.index(x, y);
+                self.pixels[index..index + 4].copy_from_slice(&[color.r, color.g, color.b, color.a]);
+            }
+        }
+    }
+
+    /// Blends a color on top of all pixels whose centers are inside both the rectangle and the clip rectangle
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The rectangle to fill
+    /// 
+    /// clip: The rectangle outside of which nothing is drawn
+    /// 
+    /// color: The color to fill with
+    pub fn fill_rect(&mut self, rect: Rect, clip: Rect, color: Color) {
+        self.fill_where(rect, clip, color, |_, _| true);
+    }
+
+    /// Blends a color on top of all pixels whose centers are inside both the rounded rectangle and the clip rectangle
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The rectangle to fill
+    /// 
+    /// radius: The radius of the corners, it is limited to half the shortest side
+    /// 
+    /// clip: The rectangle outside of which nothing is drawn
+    /// 
+    /// color: The color to fill with
+    pub fn fill_rounded_rect(&mut self, rect: Rect, radius: f32, clip: Rect, color: Color) {
+        self.fill_where(rect, clip, color, |x, y| inside_rounded_rect(&rect, radius, x, y));
+    }
+
+    /// Blends a color on top of all pixels whose centers are inside the outline of the rounded rectangle and the clip rectangle,
+    /// the outline is drawn on the inside of the rectangle
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The outer edge of the outline
+    /// 
+    /// radius: The radius of the outer corners, it is limited to half the shortest side
+    /// 
+    /// width: The width of the outline
+    /// 
+    /// clip: The rectangle outside of which nothing is drawn
+    /// 
+    /// color: The color to draw with
+    pub fn stroke_rounded_rect(&mut self, rect: Rect, radius: f32, width: f32, clip: Rect, color: Color) {
+        if width <= 0.0 || !width.is_finite() {
+            return;
+        }
+
+        let inner = rect.shrink(width);
+        let inner_radius = (radius - width).max(0.0);
+        let inside_inner = |x, y| !inner.is_empty() && inside_rect(&inner, x, y) && inside_rounded_rect(&inner, inner_radius, x, y);
+        self.fill_where(rect, clip, color, |x, y| inside_rounded_rect(&rect, radius, x, y) && !inside_inner(x, y));
+    }
+
+    /// Blends a color on top of all pixels inside the clip rectangle using a coverage mask
+    /// 
+    /// # Parameters
+    /// 
+    /// x: The column of the left edge of the mask
+    /// 
+    /// y: The row of the top edge of the mask
+    /// 
+    /// width: The number of columns in the mask
+    /// 
+    /// mask: The coverage of each pixel row by row, 0 is not covered and 255 is fully covered
+    /// 
+    /// clip: The rectangle outside of which nothing is drawn
+    /// 
+    /// color: The color to draw with
+    pub fn fill_mask(&mut self, x: i32, y: i32, width: usize, mask: &[u8], clip: Rect, color: Color) {
+        if width == 0 || color.a == 0 {
+            return;
+        }
+
+        let (columns, rows) = self.pixel_range(clip);
+        for (pos, coverage) in mask.iter().enumerate() {
+            let (column, row) = (x as i64 + (pos % width) as i64, y as i64 + (pos / width) as i64);
+            if *coverage == 0 || column < columns.start as i64 || column >= columns.end as i64 || row < rows.start as i64 || row >= rows.end as i64 {
+                continue;
+            }
+
+            let index = self.index(column as u32, row as u32);
+            let below = Color::new(self.pixels[index], self.pixels[index + 1], self.pixels[index + 2], self.pixels[index + 3]);
+            let blended = color.with_opacity(*coverage as f32 / 255.0).blend_over(below);
+            self.pixels[index..index + 4].copy_from_slice(&[blended.r, blended.g, blended.b, blended.a]);
+        }
+    }
+
+    /// Blends a part of a bitmap scaled to a rectangle on top of all pixels whose centers are inside both the rectangle and the clip rectangle,
+    /// each pixel takes the color of the closest pixel of the bitmap
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The rectangle to draw the bitmap into
+    /// 
+    /// bitmap: The bitmap to draw
+    /// 
+    /// source: The part of the bitmap to draw in pixels of the bitmap
+    /// 
+    /// clip: The rectangle outside of which nothing is drawn
+    /// 
+    /// opacity: The opacity to draw the bitmap with
+    pub fn draw_bitmap(&mut self, rect: Rect, bitmap: &Bitmap, source: Rect, clip: Rect, opacity: f32) {
+        // Nothing can be sampled from an empty source
+        let source = source.intersect(&Rect::new(0.0, 0.0, bitmap.get_width() as f32, bitmap.get_height() as f32));
+        if !rect.is_finite() || !source.is_finite() || rect.is_empty() || source.is_empty() {
+            return;
+        }
+
+        // Map each pixel center back into the source and clamp to its edge pixels
+        let sample = |pos: f32, start: f32, len: f32, source_start: f32, source_len: f32| {
+            let mapped = source_start + (pos - start) / len * source_len;
+            mapped.floor().clamp(source_start.floor(), (source_start + source_len).ceil() - 1.0) as u32
+        };
+
+        let (columns, rows) = self.pixel_range(rect.intersect(&clip));
+        for y in rows {
+            let source_y = sample(y as f32 + 0.5, rect.y, rect.h, source.y, source.h);
+            for x in columns.clone() {
+                let source_x = sample(x as f32 + 0.5, rect.x, rect.w, source.x, source.w);
+                let color = match bitmap.get_pixel(source_x, source_y) {
+                    Some(color) => color.with_opacity(opacity),
+                    None => continue,
+                };
+
+                let index = self.index(x, y);
+                let below = Color::new(self.pixels[index], self.pixels[index + 1], self.pixels[index + 2], self.pixels[index + 3]);
+                let blended = color.blend_over(below);
+                self.pixels[index..index + 4].copy_from_slice(&[blended.r, blended.g, blended.b, blended.a]);
+            }
+        }
+    }
+
+    /// Blends another framebuffer faded with an opacity on top of all pixels inside the clip rectangle, this composites a layer as a whole
+    ///
+    /// # Parameters
+    ///
+    /// layer: The framebuffer to draw, pixels outside of this framebuffer are ignored
+    ///
+    /// origin: The pixel of this framebuffer the top left pixel of the layer is drawn at, it may be outside of this framebuffer
+    ///
+    /// clip: The rectangle outside of which nothing is drawn
+    ///
+    /// opacity: The opacity to draw the layer with
+    pub fn draw_layer(&mut self, layer: &Framebuffer, origin: (i32, i32), clip: Rect, opacity: f32) {
+        let (columns, rows) = self.pixel_range(clip);
+        for y in rows {
+            for x in columns.clone() {
+                let pixel = u32::try_from(x as i32 - origin.0).ok().zip(u32::try_from(y as i32 - origin.1).ok()).and_then(|(x, y)| layer.get_pixel(x, y));
+                let color = match pixel {
+                    Some(color) if color.a > 0 => color.with_opacity(opacity),
+                    _ => continue,
+                };
+
+                let index = self.index(x, y);
+                let below = Color::new(self.pixels[index], self.pixels[index + 1], self.pixels[index + 2], self.pixels[index + 3]);
+                let blended = color.blend_over(below);
+                self.pixels[index..index + 4].copy_from_slice(&[blended.r, blended.g, blended.b, blended.a]);
+            }
+        }
+    }
+
+    /// Blends a color on top of all pixels whose centers are inside both the rectangle and the clip rectangle and pass the test
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The rectangle bounding the pixels to fill
+    /// 
+    /// clip: The rectangle outside of which nothing is drawn
+    /// 
+    /// color: The color to fill with
+    /// 
+    /// inside: Tests whether the pixel with the given center should be filled
+    fn fill_where<F: Fn(f32, f32) -> bool>(&mut self, rect: Rect, clip: Rect, color: Color, inside: F) {
+        // Non-finite rectangles cover nothing, the intersection would hide them
+        if !rect.is_finite() || color.a == 0 {
+            return;
+        }
+
+        let (columns, rows) = self.pixel_range(rect.intersect(&clip));
+        for y in rows {
+            for x in columns.clone() {
+                if !inside(x as f32 + 0.5, y as f32 + 0.5) {
+                    continue;
+                }
+
+                let index = self.index(x, y);
+                let below = Color::new(self.pixels[index], self.pixels[index + 1], self.pixels[index + 2], self.pixels[index + 3]);
+                let blended = color.blend_over(below);
+                self.pixels[index..index + 4].copy_from_slice(&[blended.r, blended.g, blended.b, blended.a]);
+            }
+        }
+    }
+
+    /// Finds the columns and rows of the pixels whose centers are inside the rectangle
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The rectangle to find the pixels for
+    pub(crate) fn pixel_range(&self, rect: Rect) -> (std::ops::Range<u32>, std::ops::Range<u32>) {
+        // A pixel is covered if its center is inside the half open rectangle
+        let bound = |start: f32, len: f32, max: u32| {
+            if len <= 0.0 {
+                return 0..0;
+            }
+            let first = (start - 0.5).ceil().clamp(0.0, max as f32) as u32;
+            let last = (start + len - 0.5).ceil().clamp(0.0, max as f32) as u32;
+            first..last
+        };
+
+        (bound(rect.x, rect.w, self.width), bound(rect.y, rect.h, self.height))
+    }
+
+    /// Finds the index of the first byte of a pixel
+    /// 
+    /// # Parameters
+    /// 
+    /// x: The column of the pixel
+    /// 
+    /// y: The row of the pixel
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+}
+
+/// Checks if a point is inside a rectangle, the right and bottom edges are not included
+/// 
+/// # Parameters
+/// 
+/// rect: The rectangle to check against
+/// 
+/// x: The x position of the point
+/// 
+/// y: The y position of the point
+fn inside_rect(rect: &Rect, x: f32, y: f32) -> bool {
+    x >= rect.x && x < rect.x + rect.w && y >= rect.y && y < rect.y + rect.h
+}
+
+/// Checks if a point inside the bounding rectangle is also inside the rounded corners
+/// 
+/// # Parameters
+/// 
+/// rect: The rectangle to check against
+/// 
+/// radius: The radius of the corners, it is limited to half the shortest side
+/// 
+/// x: The x position of the point
+/// 
+/// y: The y position of the point
+fn inside_rounded_rect(rect: &Rect, radius: f32, x: f32, y: f32) -> bool {
+    let radius = radius.min(rect.w / 2.0).min(rect.h / 2.0);
+    if radius.is_nan() || radius <= 0.0 {
+        return true;
+    }
+
+    // Find the distance to the closest point on the rectangle shrunk by the radius, rounding may make the shrunk rectangle slightly inverted
+    let closest_x = x.clamp(rect.x + radius, (rect.x + rect.w - radius).max(rect.x + radius));
+    let closest_y = y.clamp(rect.y + radius, (rect.y + rect.h - radius).max(rect.y + radius));
+    (x - closest_x).powi(2) + (y - closest_y).powi(2) <= radius.powi(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_rect() {
+        let mut framebuffer = Framebuffer::new(4, 4);
+        framebuffer.fill_rect(Rect::new(1.0, 1.0, 2.0, 2.0), framebuffer.get_rect(), Color::RED);
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(0, 0));
+        assert_eq!(Some(Color::RED), framebuffer.get_pixel(1, 1));
+        assert_eq!(Some(Color::RED), framebuffer.get_pixel(2, 2));
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(3, 3));
+        assert_eq!(None, framebuffer.get_pixel(4, 0));
+
+        // Only pixels with their centers inside are covered
+        framebuffer.clear(Color::BLACK);
+        framebuffer.fill_rect(Rect::new(0.6, 0.0, 1.0, 1.0), framebuffer.get_rect(), Color::BLUE);
+        assert_eq!(Some(Color::BLACK), framebuffer.get_pixel(0, 0));
+        assert_eq!(Some(Color::BLUE), framebuffer.get_pixel(1, 0));
+        assert_eq!(Some(Color::BLACK), framebuffer.get_pixel(2, 0));
+
+        // The clip rectangle and the framebuffer bounds limit the fill
+        framebuffer.clear(Color::BLACK);
+        framebuffer.fill_rect(Rect::new(-5.0, -5.0, 20.0, 20.0), Rect::new(0.0, 0.0, 2.0, 4.0), Color::GREEN);
+        assert_eq!(Some(Color::GREEN), framebuffer.get_pixel(1, 3));
+        assert_eq!(Some(Color::BLACK), framebuffer.get_pixel(2, 3));
+        framebuffer.fill_rect(Rect::new(0.0, 0.0, f32::NAN, 4.0), framebuffer.get_rect(), Color::WHITE);
+        assert_eq!(Some(Color::GREEN), framebuffer.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn rounded_rect() {
+        let mut framebuffer = Framebuffer::new(8, 8);
+        framebuffer.fill_rounded_rect(framebuffer.get_rect(), 4.0, framebuffer.get_rect(), Color::RED);
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(0, 0));
+        assert_eq!(Some(Color::RED), framebuffer.get_pixel(1, 1));
+        assert_eq!(Some(Color::RED), framebuffer.get_pixel(0, 4));
+
+        // A radius of exactly half the side does not panic when rounding inverts the shrunk rectangle
+        framebuffer.fill_rounded_rect(Rect::new(0.0, 0.0, 11.4, 5.0), 5.7, framebuffer.get_rect(), Color::BLUE);
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(7, 7));
+
+        // Only the outline is drawn
+        framebuffer.clear(Color::TRANSPARENT);
+        framebuffer.stroke_rounded_rect(framebuffer.get_rect(), 0.0, 2.0, framebuffer.get_rect(), Color::BLUE);
+        assert_eq!(Some(Color::BLUE), framebuffer.get_pixel(0, 0));
+        assert_eq!(Some(Color::BLUE), framebuffer.get_pixel(1, 4));
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(2, 2));
+        assert_eq!(Some(Color::BLUE), framebuffer.get_pixel(7, 6));
+
+        // A border wider than the rectangle fills it
+        framebuffer.clear(Color::TRANSPARENT);
+        framebuffer.stroke_rounded_rect(Rect::new(0.0, 0.0, 2.0, 2.0), 0.0, 3.0, framebuffer.get_rect(), Color::BLUE);
+        assert_eq!(Some(Color::BLUE), framebuffer.get_pixel(1, 1));
+    }
+
+    #[test]
+    fn fill_mask() {
+        let mut framebuffer = Framebuffer::new(3, 3);
+        let mask = [255, 0, 128, 255];
+        framebuffer.fill_mask(-1, 1, 2, &mask, Rect::new(0.0, 0.0, 3.0, 2.0), Color::RED);
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(0, 0));
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(0, 1));
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(0, 2));
+
+        framebuffer.fill_mask(0, 0, 2, &mask, framebuffer.get_rect(), Color::RED);
+        assert_eq!(Some(Color::RED), framebuffer.get_pixel(0, 0));
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(1, 0));
+        assert_eq!(Some(Color::new(255, 0, 0, 128)), framebuffer.get_pixel(0, 1));
+    }
+}
@@ -0,0 +1,390 @@
+use super::{Color, Rect, Painter, Drawable};
+use crate::view::style::Style;
+use std::{fs, path::Path, rc::Rc};
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+/// A decoded image with straight alpha pixels stored row by row from the top left corner, it is cheap to clone since the pixels are shared
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "BitmapData", try_from = "BitmapData")]
+pub struct Bitmap {
+    /// The width in pixels
+    width: u32,
+    /// The height in pixels
+    height: u32,
+    /// The pixels
+    pixels: Rc<[Color]>,
+}
+
+impl Bitmap {
+    /// Decodes a PNG or JPEG image from a local file
+    ///
+    /// # Parameters
+    ///
+    /// path: The path to the image file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ImageError> {
+        let data = fs::read(path)?;
+
+        Self::from_bytes(&data)
+    }
+
+    /// Decodes a PNG or JPEG image from memory, the format is detected from the data
+    ///
+    /// # Parameters
+    ///
+    /// data: The contents of the image file
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ImageError> {
+        let decoded = image::load_from_memory(data)?.into_rgba8();
+        let (width, height) = decoded.dimensions();
+        let pixels = decoded.pixels().map(|pixel| Color::new(pixel[0], pixel[1], pixel[2], pixel[3])).collect();
+
+        Ok(Self { width, height, pixels })
+    }
+
+    /// Creates a bitmap from pixels, returns None if the number of pixels does not match the size
+    ///
+    /// # Parameters
+    ///
+    /// width: The width in pixels
+    ///
+    /// height: The height in pixels
+    ///
+    /// pixels: The pixels row by row from the top left corner
+    pub fn from_pixels(width: u32, height: u32, pixels: Vec<Color>) -> Option<Self> {
+        if pixels.len() != width as usize * height as usize {
+            return None;
+        }
+
+        Some(Self { width, height, pixels: pixels.into() })
+    }
+
+    /// Gets the width in pixels
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    /// Gets the height in pixels
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Gets all the pixels row by row from the top left corner
+    pub fn get_pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// Gets the color of a single pixel, None if it is outside the bitmap
+    ///
+    /// # Parameters
+    ///
+    /// x: The column of the pixel
+    ///
+    /// y: The row of the pixel
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<Color> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(self.pixels[y as usize * self.width as usize + x as usize])
+    }
+}
+
+/// The serialized form of a bitmap, the number of pixels is checked when it is deserialized
+#[derive(Serialize, Deserialize)]
+struct BitmapData {
+    /// The width in pixels
+    width: u32,
+    /// The height in pixels
+    height: u32,
+    /// The pixels
+    pixels: Vec<Color>,
+}
+
+impl From<Bitmap> for BitmapData {
+    fn from(bitmap: Bitmap) -> Self {
+        Self { width: bitmap.width, height: bitmap.height, pixels: bitmap.pixels.to_vec() }
+    }
+}
+
+impl TryFrom<BitmapData> for Bitmap {
+    type Error = String;
+
+    fn try_from(data: BitmapData) -> Result<Self, Self::Error> {
+        let (width, height) = (data.width, data.height);
+        Self::from_pixels(width, height, data.pixels).ok_or_else(|| format!("The number of pixels does not match the size {}x{}", width, height))
+    }
+}
+
+/// How an image is scaled to fit the view
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ScaleMode {
+    /// The image covers the entire view without keeping its aspect ratio
+    #[default]
+    Stretch,
+    /// The image is as large as possible while staying inside the view and keeping its aspect ratio, it is centered
+    Contain,
+    /// The image is as small as possible while covering the entire view and keeping its aspect ratio, it is centered and cut off at the edges
+    Cover,
+    /// The image keeps its natural size in pixels, it is centered and cut off at the edges if it is larger than the view
+    None,
+    /// The image is split into nine parts by the distances in pixels of the image from each edge.
+    /// The corners keep their natural size, the edges are stretched along the edge and the center is stretched to fill the rest.
+    /// The corners are shrunk if the view is too small to fit them
+    NineSlice {
+        /// The distance from the left edge
+        left: u32,
+        /// The distance from the top edge
+        top: u32,
+        /// The distance from the right edge
+        right: u32,
+        /// The distance from the bottom edge
+        bottom: u32,
+    },
+}
+
+/// Image content for a view
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    /// The image to draw
+    pub bitmap: Bitmap,
+    /// How the image is scaled to fit the view
+    pub scale: ScaleMode,
+}
+
+impl Image {
+    /// Creates new image content
+    ///
+    /// # Parameters
+    ///
+    /// bitmap: The image to draw
+    ///
+    /// scale: How the image is scaled to fit the view
+    pub fn new(bitmap: Bitmap, scale: ScaleMode) -> Self {
+        Self { bitmap, scale }
+    }
+
+    /// Finds the parts of the image to draw as pairs of the rectangle to draw into and the part of the image in pixels of the image
+    ///
+    /// # Parameters
+    ///
+    /// rect: The rectangle inside the padding of the view
+    fn layout(&self, rect: Rect) -> Vec<(Rect, Rect)> {
+        let (width, height) = (self.bitmap.get_width() as f32, self.bitmap.get_height() as f32);
+        let source = Rect::new(0.0, 0.0, width, height);
+        let centered = |scale: f32| {
+            let (w, h) = (width * scale, height * scale);
+            Rect::new(rect.x + (rect.w - w) / 2.0, rect.y + (rect.h - h) / 2.0, w, h)
+        };
+
+        match self.scale {
+            // Cover the entire rectangle
+            ScaleMode::Stretch => vec![(rect, source)],
+
+            // Scale uniformly and center
+            ScaleMode::Contain => vec![(centered((rect.w / width).min(rect.h / height)), source)],
+            ScaleMode::Cover => vec![(centered((rect.w / width).max(rect.h / height)), source)],
+            ScaleMode::None => vec![(centered(1.0), source)],
+
+            // Split both the image and the rectangle into three columns and three rows
+            ScaleMode::NineSlice { left, top, right, bottom } => {
+                let (left, right) = fit_slices(left as f32, right as f32, width);
+                let (top, bottom) = fit_slices(top as f32, bottom as f32, height);
+
+                // The borders keep their size in the rectangle unless they do not fit
+                let (dest_left, dest_right) = fit_slices(left, right, rect.w);
+                let (dest_top, dest_bottom) = fit_slices(top, bottom, rect.h);
+
+                let columns = [(left, dest_left), (width - left - right, rect.w - dest_left - dest_right), (right, dest_right)];
+                let rows = [(top, dest_top), (height - top - bottom, rect.h - dest_top - dest_bottom), (bottom, dest_bottom)];
+
+                let mut parts = Vec::new();
+                let (mut source_y, mut dest_y) = (0.0, rect.y);
+                for (source_h, dest_h) in rows {
+                    let (mut source_x, mut dest_x) = (0.0, rect.x);
+                    for (source_w, dest_w) in columns {
+                        parts.push((Rect::new(dest_x, dest_y, dest_w, dest_h), Rect::new(source_x, source_y, source_w, source_h)));
+                        source_x += source_w;
+                        dest_x += dest_w;
+                    }
+                    source_y += source_h;
+                    dest_y += dest_h;
+                }
+
+                parts
+            }
+        }
+    }
+}
+
+impl Drawable for Image {
+    fn draw(&self, rect: Rect, _style: &Style, painter: &mut dyn Painter) {
+        // Cover and none may reach outside the view
+        painter.push_clip(rect);
+
+        for (dest, source) in self.layout(rect) {
+            painter.draw_bitmap(dest, &self.bitmap, source);
+        }
+
+        painter.pop_clip();
+    }
+
+    fn measure(&self, _max_width: Option<f32>) -> Option<(f32, f32)> {
+        Some((self.bitmap.get_width() as f32, self.bitmap.get_height() as f32))
+    }
+}
+
+/// Shrinks two slices along the same side proportionally so they fit inside the length together
+///
+/// # Parameters
+///
+/// start: The length of the first slice
+///
+/// end: The length of the last slice
+///
+/// len: The total length available
+fn fit_slices(start: f32, end: f32, len: f32) -> (f32, f32) {
+    if start + end <= len {
+        return (start, end);
+    }
+
+    let scale = len.max(0.0) / (start + end);
+    (start * scale, end * scale)
+}
+
+#[derive(Error, Debug)]
+pub enum ImageError {
+    #[error("The image file could not be read: {:?}", .0)]
+    Io(std::io::Error),
+    #[error("The image could not be decoded: {:?}", .0)]
+    Decode(image::ImageError),
+}
+
+impl From<std::io::Error> for ImageError {
+    fn from(err: std::io::Error) -> Self {
+        ImageError::Io(err)
+    }
+}
+
+impl From<image::ImageError> for ImageError {
+    fn from(err: image::ImageError) -> Self {
+        ImageError::Decode(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{gen_locate, gen_ratio};
+    use crate::{render::{Framebuffer, FramebufferPainter, Damage}, view::{View, extent, children}};
+    use image::ImageEncoder;
+
+    /// A 2x1 bitmap with a red and a blue pixel
+    fn gen_bitmap() -> Bitmap {
+        Bitmap::from_pixels(2, 1, vec![Color::RED, Color::BLUE]).unwrap()
+    }
+
+    /// Draws an image into a new framebuffer
+    fn draw(image: &Image, width: u32, height: u32, rect: Rect) -> Framebuffer {
+        let mut framebuffer = Framebuffer::new(width, height);
+        let mut painter = FramebufferPainter::new(&mut framebuffer);
+        image.draw(rect, &Style::default(), &mut painter);
+
+        framebuffer
+    }
+
+    #[test]
+    fn decode() {
+        // PNG keeps the exact pixels
+        let mut png = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png).write_image(&[255, 0, 0, 255, 0, 0, 255, 128], 2, 1, image::ColorType::Rgba8).unwrap();
+        let bitmap = Bitmap::from_bytes(&png).unwrap();
+        assert_eq!(vec![Color::RED, Color::new(0, 0, 255, 128)], bitmap.get_pixels());
+
+        // JPEG is lossy and has no alpha
+        let mut jpeg = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new(&mut jpeg).write_image(&[200; 8 * 8 * 3], 8, 8, image::ColorType::Rgb8).unwrap();
+        let bitmap = Bitmap::from_bytes(&jpeg).unwrap();
+        assert_eq!((8, 8), (bitmap.get_width(), bitmap.get_height()));
+        assert!(bitmap.get_pixels().iter().all(|pixel| pixel.a == 255 && pixel.r.abs_diff(200) <= 2));
+
+        assert!(matches!(Bitmap::from_bytes(&[0, 1, 2, 3]), Err(ImageError::Decode(_))));
+        assert!(matches!(Bitmap::from_file("missing.png"), Err(ImageError::Io(_))));
+        assert_eq!(None, Bitmap::from_pixels(2, 2, vec![Color::RED]));
+        assert_eq!(None, gen_bitmap().get_pixel(2, 0));
+    }
+
+    #[test]
+    fn scale() {
+        let rect = Rect::new(0.0, 0.0, 4.0, 4.0);
+
+        // Stretch covers everything, the left half is red
+        let stretch = draw(&Image::new(gen_bitmap(), ScaleMode::Stretch), 4, 4, rect);
+        assert_eq!(Some(Color::RED), stretch.get_pixel(1, 3));
+        assert_eq!(Some(Color::BLUE), stretch.get_pixel(2, 0));
+
+        // Contain leaves a gap above and below
+        let contain = draw(&Image::new(gen_bitmap(), ScaleMode::Contain), 4, 4, rect);
+        assert_eq!(Some(Color::TRANSPARENT), contain.get_pixel(0, 0));
+        assert_eq!(Some(Color::RED), contain.get_pixel(0, 1));
+        assert_eq!(Some(Color::BLUE), contain.get_pixel(3, 2));
+        assert_eq!(Some(Color::TRANSPARENT), contain.get_pixel(3, 3));
+
+        // Cover cuts off the sides, each pixel of the image is 4x4
+        let cover = draw(&Image::new(gen_bitmap(), ScaleMode::Cover), 6, 4, Rect::new(1.0, 0.0, 4.0, 4.0));
+        assert_eq!(Some(Color::TRANSPARENT), cover.get_pixel(0, 0));
+        assert_eq!(Some(Color::RED), cover.get_pixel(1, 0));
+        assert_eq!(Some(Color::RED), cover.get_pixel(2, 3));
+        assert_eq!(Some(Color::BLUE), cover.get_pixel(3, 3));
+        assert_eq!(Some(Color::TRANSPARENT), cover.get_pixel(5, 0));
+
+        // None keeps the size and centers
+        let none = draw(&Image::new(gen_bitmap(), ScaleMode::None), 4, 4, rect);
+        assert_eq!(Some(Color::TRANSPARENT), none.get_pixel(0, 1));
+        assert_eq!(Some(Color::RED), none.get_pixel(1, 1));
+        assert_eq!(Some(Color::BLUE), none.get_pixel(2, 1));
+        assert_eq!(Some(Color::TRANSPARENT), none.get_pixel(1, 2));
+    }
+
+    #[test]
+    fn nine_slice() {
+        // A 3x3 bitmap with a red border and a blue center
+        let mut pixels = vec![Color::RED; 9];
+        pixels[4] = Color::BLUE;
+        let bitmap = Bitmap::from_pixels(3, 3, pixels).unwrap();
+        let image = Image::new(bitmap, ScaleMode::NineSlice { left: 1, top: 1, right: 1, bottom: 1 });
+
+        // The border stays one pixel wide
+        let framebuffer = draw(&image, 6, 4, Rect::new(0.0, 0.0, 6.0, 4.0));
+        for (x, y) in [(0, 0), (5, 0), (3, 0), (0, 2), (5, 3)] {
+            assert_eq!(Some(Color::RED), framebuffer.get_pixel(x, y));
+        }
+        for (x, y) in [(1, 1), (4, 1), (4, 2)] {
+            assert_eq!(Some(Color::BLUE), framebuffer.get_pixel(x, y));
+        }
+
+        // The corners shrink if they do not fit
+        assert_eq!((1.0, 1.0), fit_slices(2.0, 2.0, 2.0));
+        assert_eq!(Rect::new(0.0, 0.0, 0.5, 0.5), image.layout(Rect::new(0.0, 0.0, 1.0, 1.0))[0].0);
+    }
+
+    #[test]
+    fn aspect_ratio() {
+        // The image is twice as wide as it is tall, the padding takes half the height
+        let mut root = View::new_root();
+        let ratio = gen_ratio(extent::PositionType::Set(0.0), extent::RatioSource::Content);
+        let locate = gen_locate(extent::PositionType::Set(0.0), extent::SizeType::Set(0.5));
+        let scheduler = root.get_children_scheduler();
+        let child = View::new(extent::ExtentUpdate { x: ratio, y: locate }, Some(Rc::clone(&scheduler)));
+        child.get_drawable_controller().borrow_mut().set_drawable(Some(Rc::new(Image::new(gen_bitmap(), ScaleMode::Stretch))));
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+
+        // In a 200x100 parent the child is 100x50 pixels
+        root.update((200.0, 100.0), std::time::Instant::now(), &mut Damage::new()).unwrap();
+        assert_eq!((0.0, 0.0, 0.5, 0.5), root.get_children()[0].get_extent());
+
+        // With padding the area inside it keeps the ratio instead
+        root.get_children()[0].get_style_controller().borrow_mut().set_style(Style { padding: crate::view::style::Insets::new(0.0, 0.25, 0.0, 0.25), ..Style::default() });
+        root.update((200.0, 100.0), std::time::Instant::now(), &mut Damage::new()).unwrap();
+        assert_eq!((0.0, 0.0, 0.25, 0.5), root.get_children()[0].get_extent());
+    }
+}
@@ -0,0 +1,228 @@
+mod framebuffer;
+mod painter;
+mod drawable;
+mod software;
+mod text;
+mod image;
+mod damage;
+mod display_list;
+
+pub use framebuffer::Framebuffer;
+pub use painter::{Painter, FramebufferPainter};
+pub use drawable::{Drawable, DrawableController};
+pub use text::{Font, FontError, Text, TextAlign, TextRun};
+pub use image::{Bitmap, Image, ImageError, ScaleMode};
+pub use damage::Damage;
+pub use display_list::{DisplayList, DrawCommand, DisplayListPainter};
+
+use serde::{Serialize, Deserialize};
+pub use software::SoftwareRenderer;
+
+/// A color with straight (not premultiplied) alpha
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Color {
+    /// The red component
+    pub r: u8,
+    /// The green component
+    pub g: u8,
+    /// The blue component
+    pub b: u8,
+    /// The alpha component, 0 is fully transparent and 255 fully opaque
+    pub a: u8,
+}
+
+impl Color {
+    pub const TRANSPARENT: Self = Self::new(0, 0, 0, 0);
+    pub const BLACK: Self = Self::new(0, 0, 0, 255);
+    pub const WHITE: Self = Self::new(255, 255, 255, 255);
+    pub const RED: Self = Self::new(255, 0, 0, 255);
+    pub const GREEN: Self = Self::new(0, 255, 0, 255);
+    pub const BLUE: Self = Self::new(0, 0, 255, 255);
+
+    /// Creates a new color
+    /// 
+    /// # Parameters
+    /// 
+    /// r: The red component
+    /// 
+    /// g: The green component
+    /// 
+    /// b: The blue component
+    /// 
+    /// a: The alpha component
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Gets the color with its alpha multiplied by an opacity
+    /// 
+    /// # Parameters
+    /// 
+    /// opacity: The opacity to multiply with, it is clamped to 0 to 1 and a non-finite opacity is treated as 1
+    pub fn with_opacity(&self, opacity: f32) -> Self {
+        let opacity = if opacity.is_finite() { opacity.clamp(0.0, 1.0) } else { 1.0 };
+
+        Self { a: (self.a as f32 * opacity).round() as u8, ..*self }
+    }
+
+    /// Blends this color on top of another color using source over compositing
+    /// 
+    /// # Parameters
+    /// 
+    /// below: The color this color is drawn on top of
+    pub fn blend_over(&self, below: Self) -> Self {
+        // Skip the math for the common cases
+        match self.a {
+            255 => return *self,
+            0 => return below,
+            _ => (),
+        }
+
+        let src_a = self.a as f32 / 255.0;
+        let dst_a = below.a as f32 / 255.0 * (1.0 - src_a);
+        let out_a = src_a + dst_a;
+        let channel = |src: u8, dst: u8| ((src as f32 * src_a + dst as f32 * dst_a) / out_a).round() as u8;
+
+        Self::new(channel(self.r, below.r), channel(self.g, below.g), channel(self.b, below.b), (out_a * 255.0).round() as u8)
+    }
+}
+
+/// A rectangle in pixels, the origin is the top left corner of the window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    /// The x position of the left edge
+    pub x: f32,
+    /// The y position of the top edge
+    pub y: f32,
+    /// The width
+    pub w: f32,
+    /// The height
+    pub h: f32,
+}
+
+impl Rect {
+    /// Creates a new rectangle
+    /// 
+    /// # Parameters
+    /// 
+    /// x: The x position of the left edge
+    /// 
+    /// y: The y position of the top edge
+    /// 
+    /// w: The width
+    /// 
+    /// h: The height
+    pub const fn new(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// Finds the rectangle covered by an extent relative to this rectangle, (0, 0, 1, 1) gives this rectangle
+    /// 
+    /// # Parameters
+    /// 
+    /// extent: The extent as (x, y, w, h) relative to this rectangle
+    pub fn sub_rect(&self, extent: (f32, f32, f32, f32)) -> Self {
+        Self::new(self.x + extent.0 * self.w, self.y + extent.1 * self.h, extent.2 * self.w, extent.3 * self.h)
+    }
+
+    /// Moves the rectangle without changing its size
+    /// 
+    /// # Parameters
+    /// 
+    /// dx: The distance to move to the right
+    /// 
+    /// dy: The distance to move down
+    pub fn translate(&self, dx: f32, dy: f32) -> Self {
+        Self::new(self.x + dx, self.y + dy, self.w, self.h)
+    }
+
+    /// Finds the overlap of two rectangles, a rectangle with no area is returned if they do not overlap
+    /// 
+    /// # Parameters
+    /// 
+    /// other: The rectangle to overlap with
+    pub fn intersect(&self, other: &Self) -> Self {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let w = ((self.x + self.w).min(other.x + other.w) - x).max(0.0);
+        let h = ((self.y + self.h).min(other.y + other.h) - y).max(0.0);
+
+        Self::new(x, y, w, h)
+    }
+
+    /// Finds the smallest rectangle containing both rectangles
+    /// 
+    /// # Parameters
+    /// 
+    /// other: The rectangle to include
+    pub fn union(&self, other: &Self) -> Self {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let w = (self.x + self.w).max(other.x + other.w) - x;
+        let h = (self.y + self.h).max(other.y + other.h) - y;
+
+        Self::new(x, y, w, h)
+    }
+
+    /// Gets the area of the rectangle, 0 if it is empty
+    pub fn area(&self) -> f32 {
+        if self.is_empty() { 0.0 } else { self.w * self.h }
+    }
+
+    /// Shrinks the rectangle by the same amount from every edge, the size never gets negative
+    /// 
+    /// # Parameters
+    /// 
+    /// amount: The distance to move each edge inwards
+    pub fn shrink(&self, amount: f32) -> Self {
+        Self::new(self.x + amount, self.y + amount, (self.w - 2.0 * amount).max(0.0), (self.h - 2.0 * amount).max(0.0))
+    }
+
+    /// Checks if a point is inside the rectangle, the right and bottom edges are outside
+    /// 
+    /// # Parameters
+    /// 
+    /// x: The x position of the point
+    /// 
+    /// y: The y position of the point
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.w && y < self.y + self.h
+    }
+
+    /// Checks if all the values of the rectangle are finite
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.w.is_finite() && self.h.is_finite()
+    }
+
+    /// Checks if the rectangle has no area
+    pub fn is_empty(&self) -> bool {
+        self.w <= 0.0 || self.h <= 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend_over() {
+        assert_eq!(Color::RED, Color::RED.blend_over(Color::BLUE));
+        assert_eq!(Color::BLUE, Color::TRANSPARENT.blend_over(Color::BLUE));
+        assert_eq!(Color::new(128, 0, 127, 255), Color::new(255, 0, 0, 128).blend_over(Color::BLUE));
+        assert_eq!(Color::new(255, 0, 0, 128), Color::new(255, 0, 0, 128).blend_over(Color::TRANSPARENT));
+        assert_eq!(Color::new(255, 0, 0, 64), Color::new(255, 0, 0, 128).with_opacity(0.5));
+        assert_eq!(Color::RED, Color::RED.with_opacity(f32::NAN));
+    }
+
+    #[test]
+    fn intersect() {
+        let rect = Rect::new(0.0, 0.0, 10.0, 10.0);
+        assert_eq!(Rect::new(5.0, 2.0, 5.0, 3.0), rect.intersect(&Rect::new(5.0, 2.0, 10.0, 3.0)));
+        assert!(rect.intersect(&Rect::new(20.0, 0.0, 10.0, 10.0)).is_empty());
+        assert_eq!(Rect::new(2.5, 5.0, 5.0, 2.5), rect.sub_rect((0.25, 0.5, 0.5, 0.25)));
+        assert_eq!(Rect::new(4.0, 4.0, 2.0, 2.0), rect.shrink(4.0));
+        assert!(rect.shrink(6.0).is_empty());
+        assert_eq!(Rect::new(0.0, -5.0, 15.0, 15.0), rect.union(&Rect::new(5.0, -5.0, 10.0, 10.0)));
+        assert_eq!(100.0, rect.area());
+    }
+}
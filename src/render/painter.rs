@@ -0,0 +1,333 @@
+use super::{Color, Rect, Framebuffer, Bitmap, TextRun};
+
+/// A backend neutral interface for drawing, all coordinates are in pixels with the origin in the top left corner of the window
+pub trait Painter {
+    /// Blends a color on top of everything inside the rectangle and the current clip rectangle
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The rectangle to fill
+    /// 
+    /// color: The color to fill with
+    fn fill_rect(&mut self, rect: Rect, color: Color);
+
+    /// Blends a color on top of everything inside the rounded rectangle and the current clip rectangle
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The rectangle to fill
+    /// 
+    /// radius: The radius of the corners, it is limited to half the shortest side
+    /// 
+    /// color: The color to fill with
+    fn fill_rounded_rect(&mut self, rect: Rect, radius: f32, color: Color);
+
+    /// Blends a color on top of everything inside the outline of the rounded rectangle and the current clip rectangle,
+    /// the outline is drawn on the inside of the rectangle
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The outer edge of the outline
+    /// 
+    /// radius: The radius of the outer corners, it is limited to half the shortest side
+    /// 
+    /// width: The width of the outline
+    /// 
+    /// color: The color to draw with
+    fn stroke_rounded_rect(&mut self, rect: Rect, radius: f32, width: f32, color: Color);
+
+    /// Blends a color on top of everything inside the current clip rectangle using a coverage mask, this is used for glyphs
+    /// 
+    /// # Parameters
+    /// 
+    /// x: The column of the left edge of the mask
+    /// 
+    /// y: The row of the top edge of the mask
+    /// 
+    /// width: The number of columns in the mask
+    /// 
+    /// mask: The coverage of each pixel row by row, 0 is not covered and 255 is fully covered
+    /// 
+    /// color: The color to draw with
+    fn fill_mask(&mut self, x: i32, y: i32, width: usize, mask: &[u8], color: Color);
+
+    /// Blends a line of text on top of everything inside the current clip rectangle.
+    /// By default every glyph is rasterized and drawn with fill_mask, text runs without a font are not drawn
+    /// 
+    /// # Parameters
+    /// 
+    /// run: The line of text to draw
+    fn draw_text(&mut self, run: &TextRun) {
+        run.for_each_glyph(|x, y, width, mask| self.fill_mask(x, y, width, mask, run.color));
+    }
+
+    /// Blends a part of a bitmap scaled to a rectangle on top of everything inside the current clip rectangle
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The rectangle to draw the bitmap into
+    /// 
+    /// bitmap: The bitmap to draw
+    /// 
+    /// source: The part of the bitmap to draw in pixels of the bitmap
+    fn draw_bitmap(&mut self, rect: Rect, bitmap: &Bitmap, source: Rect);
+
+    /// Gets the current clip rectangle, nothing is drawn outside of it
+    fn get_clip(&self) -> Rect;
+
+    /// Narrows the clip rectangle to the overlap of the current clip rectangle and the given rectangle until it is popped
+    /// 
+    /// # Parameters
+    /// 
+    /// rect: The rectangle to clip to
+    fn push_clip(&mut self, rect: Rect);
+
+    /// Restores the clip rectangle from before the last push, the clip rectangle of the entire target is never popped
+    fn pop_clip(&mut self);
+
+    /// Gets the combined opacity of all pushed layers
+    fn get_opacity(&self) -> f32;
+
+    /// Draws everything into a separate layer until it is popped, the layer is faded as a whole when it is popped.
+    /// Overlapping drawings inside the layer cover each other before the layer is faded, so they do not show through each other
+    /// 
+    /// # Parameters
+    /// 
+    /// opacity: The opacity of the layer, 0 is invisible and 1 is fully opaque
+    fn push_layer(&mut self, opacity: f32);
+
+    /// Fades the last pushed layer and blends it on top of everything drawn before it was pushed
+    fn pop_layer(&mut self);
+}
+
+/// A painter drawing directly into a framebuffer
+#[derive(Debug)]
+pub struct FramebufferPainter<'a> {
+    /// The framebuffer to draw into
+    framebuffer: &'a mut Framebuffer,
+    /// The stack of clip rectangles, the last one is the current one and the first one covers the entire framebuffer
+    clips: Vec<Rect>,
+    /// The stack of pushed layers, the last one is the current one
+    layers: Vec<Layer>,
+}
+
+impl<'a> FramebufferPainter<'a> {
+    /// Creates a new painter which may draw on the entire framebuffer
+    /// 
+    /// # Parameters
+    /// 
+    /// framebuffer: The framebuffer to draw into
+    pub fn new(framebuffer: &'a mut Framebuffer) -> Self {
+        let clips = vec![framebuffer.get_rect()];
+        let layers = Vec::new();
+
+        Self { framebuffer, clips, layers }
+    }
+
+    /// Gets the framebuffer everything is drawn into with the pixel its top left corner covers, this is the one of the innermost layer which has its own.
+    /// None if one of the layers is invisible so nothing has to be drawn
+    fn get_target(&mut self) -> Option<(&mut Framebuffer, (u32, u32))> {
+        if self.layers.iter().any(|layer| layer.opacity <= 0.0) {
+            return None;
+        }
+
+        let layer = self.layers.iter_mut().rev().find_map(|layer| {
+            let origin = layer.origin;
+            layer.framebuffer.as_mut().map(|framebuffer| (framebuffer, origin))
+        });
+        match layer {
+            Some(layer) => Some(layer),
+            None => Some((&mut *self.framebuffer, (0, 0))),
+        }
+    }
+}
+
+/// Moves a rectangle from window coordinates into the coordinates of a framebuffer
+///
+/// # Parameters
+///
+/// rect: The rectangle to move
+///
+/// origin: The pixel the top left corner of the framebuffer covers
+fn to_target(rect: Rect, origin: (u32, u32)) -> Rect {
+    rect.translate(-(origin.0 as f32), -(origin.1 as f32))
+}
+
+impl<'a> Painter for FramebufferPainter<'a> {
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        let clip = self.get_clip();
+        if let Some((target, origin)) = self.get_target() {
+            target.fill_rect(to_target(rect, origin), to_target(clip, origin), color);
+        }
+    }
+
+    fn fill_rounded_rect(&mut self, rect: Rect, radius: f32, color: Color) {
+        let clip = self.get_clip();
+        if let Some((target, origin)) = self.get_target() {
+            target.fill_rounded_rect(to_target(rect, origin), radius, to_target(clip, origin), color);
+        }
+    }
+
+    fn stroke_rounded_rect(&mut self, rect: Rect, radius: f32, width: f32, color: Color) {
+        let clip = self.get_clip();
+        if let Some((target, origin)) = self.get_target() {
+            target.stroke_rounded_rect(to_target(rect, origin), radius, width, to_target(clip, origin), color);
+        }
+    }
+
+    fn fill_mask(&mut self, x: i32, y: i32, width: usize, mask: &[u8], color: Color) {
+        let clip = self.get_clip();
+        if let Some((target, origin)) = self.get_target() {
+            target.fill_mask(x - origin.0 as i32, y - origin.1 as i32, width, mask, to_target(clip, origin), color);
+        }
+    }
+
+    fn draw_bitmap(&mut self, rect: Rect, bitmap: &Bitmap, source: Rect) {
+        let clip = self.get_clip();
+        if let Some((target, origin)) = self.get_target() {
+            target.draw_bitmap(to_target(rect, origin), bitmap, source, to_target(clip, origin), 1.0);
+        }
+    }
+
+    fn get_clip(&self) -> Rect {
+        // The stack is never empty
+        self.clips[self.clips.len() - 1]
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        let clip = self.get_clip().intersect(&rect);
+        self.clips.push(clip);
+    }
+
+    fn pop_clip(&mut self) {
+        if self.clips.len() > 1 {
+            self.clips.pop();
+        }
+    }
+
+    fn get_opacity(&self) -> f32 {
+        self.layers.iter().map(|layer| layer.opacity).product()
+    }
+
+    fn push_layer(&mut self, opacity: f32) {
+        let opacity = if opacity.is_finite() { opacity.clamp(0.0, 1.0) } else { 1.0 };
+
+        // Only a partly faded layer needs its own framebuffer, the others are drawn directly or not at all.
+        // Nothing outside of the clip rectangle can be drawn, so the framebuffer only covers its pixels
+        let clip = self.get_clip();
+        let (columns, rows) = self.framebuffer.pixel_range(clip);
+        let origin = (columns.start, rows.start);
+        let framebuffer = (opacity > 0.0 && opacity < 1.0).then(|| Framebuffer::new(columns.len() as u32, rows.len() as u32));
+        self.layers.push(Layer { framebuffer, origin, opacity, clip });
+    }
+
+    fn pop_layer(&mut self) {
+        let layer = match self.layers.pop() {
+            Some(layer) => layer,
+            None => return,
+        };
+
+        // Fade the layer as a whole inside the clip rectangle it was pushed with
+        if let Some(framebuffer) = layer.framebuffer {
+            if let Some((target, origin)) = self.get_target() {
+                let layer_origin = (layer.origin.0 as i32 - origin.0 as i32, layer.origin.1 as i32 - origin.1 as i32);
+                target.draw_layer(&framebuffer, layer_origin, to_target(layer.clip, origin), layer.opacity);
+            }
+        }
+    }
+}
+
+/// A layer pushed onto a framebuffer painter
+#[derive(Debug)]
+struct Layer {
+    /// The framebuffer everything inside the layer is drawn into, None if it is drawn directly into the one below
+    framebuffer: Option<Framebuffer>,
+    /// The pixel of the painted framebuffer the top left corner of the framebuffer of the layer covers
+    origin: (u32, u32),
+    /// The opacity of the layer
+    opacity: f32,
+    /// The clip rectangle when the layer was pushed
+    clip: Rect,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clip() {
+        let mut framebuffer = Framebuffer::new(4, 4);
+        let mut painter = FramebufferPainter::new(&mut framebuffer);
+        painter.push_clip(Rect::new(0.0, 0.0, 2.0, 2.0));
+        painter.push_clip(Rect::new(1.0, 1.0, 2.0, 2.0));
+        assert_eq!(Rect::new(1.0, 1.0, 1.0, 1.0), painter.get_clip());
+        painter.fill_rect(Rect::new(0.0, 0.0, 4.0, 4.0), Color::RED);
+
+        // The framebuffer clip stays after popping too often
+        painter.pop_clip();
+        painter.pop_clip();
+        painter.pop_clip();
+        assert_eq!(Rect::new(0.0, 0.0, 4.0, 4.0), painter.get_clip());
+        painter.fill_rect(Rect::new(3.0, 3.0, 1.0, 1.0), Color::BLUE);
+
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(0, 0));
+        assert_eq!(Some(Color::RED), framebuffer.get_pixel(1, 1));
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(2, 2));
+        assert_eq!(Some(Color::BLUE), framebuffer.get_pixel(3, 3));
+    }
+
+    #[test]
+    fn layer() {
+        let mut framebuffer = Framebuffer::new(3, 1);
+        let mut painter = FramebufferPainter::new(&mut framebuffer);
+        painter.push_layer(0.5);
+        painter.push_layer(0.5);
+        assert_eq!(0.25, painter.get_opacity());
+        painter.pop_layer();
+
+        // Overlapping drawings inside the layer cover each other before the layer is faded
+        painter.fill_rect(Rect::new(0.0, 0.0, 2.0, 1.0), Color::RED);
+        painter.fill_rect(Rect::new(1.0, 0.0, 1.0, 1.0), Color::BLUE);
+
+        // Nothing inside an invisible layer is drawn
+        painter.push_layer(0.0);
+        painter.fill_rect(Rect::new(2.0, 0.0, 1.0, 1.0), Color::RED);
+        painter.pop_layer();
+        painter.pop_layer();
+        painter.pop_layer();
+        assert_eq!(1.0, painter.get_opacity());
+
+        assert_eq!(Some(Color::new(255, 0, 0, 128)), framebuffer.get_pixel(0, 0));
+        assert_eq!(Some(Color::new(0, 0, 255, 128)), framebuffer.get_pixel(1, 0));
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(2, 0));
+    }
+
+    #[test]
+    fn clipped_layer() {
+        let mut framebuffer = Framebuffer::new(4, 4);
+        let mut painter = FramebufferPainter::new(&mut framebuffer);
+        painter.push_clip(Rect::new(1.0, 1.0, 2.0, 2.0));
+        painter.push_layer(0.5);
+
+        // The layer only covers the pixels inside the clip rectangle
+        let layer = painter.layers[0].framebuffer.as_ref().unwrap();
+        assert_eq!((2, 2), (layer.get_width(), layer.get_height()));
+        assert_eq!((1, 1), painter.layers[0].origin);
+
+        // Drawings inside a nested clip and layer keep their position in the window
+        painter.push_clip(Rect::new(2.0, 2.0, 2.0, 2.0));
+        painter.push_layer(0.5);
+        painter.fill_rect(Rect::new(0.0, 0.0, 4.0, 4.0), Color::RED);
+        painter.pop_layer();
+        painter.pop_clip();
+        painter.fill_rect(Rect::new(1.0, 1.0, 1.0, 1.0), Color::BLUE);
+        painter.pop_layer();
+        painter.pop_clip();
+
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(0, 0));
+        assert_eq!(Some(Color::new(0, 0, 255, 128)), framebuffer.get_pixel(1, 1));
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(2, 1));
+        assert_eq!(Some(Color::new(255, 0, 0, 64)), framebuffer.get_pixel(2, 2));
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(3, 3));
+    }
+}
@@ -0,0 +1,242 @@
+use crate::view::View;
+use super::{Color, Rect, Framebuffer, FramebufferPainter, Damage, DisplayList};
+
+/// A renderer which rasterizes a laid out view tree on the cpu into a framebuffer by replaying its display list.
+/// Views are drawn in painter's order, a parent is drawn before its children and older siblings before younger ones
+#[derive(Clone, Debug)]
+pub struct SoftwareRenderer {
+    /// The framebuffer to draw into
+    framebuffer: Framebuffer,
+    /// The color the framebuffer is cleared with before drawing
+    clear_color: Color,
+    /// True if the framebuffer does not hold a complete frame, the next frame is drawn entirely even if only some areas are damaged
+    full_redraw: bool,
+}
+
+impl SoftwareRenderer {
+    /// Creates a new renderer with a transparent background
+    /// 
+    /// # Parameters
+    /// 
+    /// width: The width of the framebuffer in pixels
+    /// 
+    /// height: The height of the framebuffer in pixels
+    pub fn new(width: u32, height: u32) -> Self {
+        let framebuffer = Framebuffer::new(width, height);
+        let clear_color = Color::TRANSPARENT;
+        let full_redraw = true;
+
+        Self { framebuffer, clear_color, full_redraw }
+    }
+
+    /// Gets the color the framebuffer is cleared with
+    pub fn get_clear_color(&self) -> Color {
+        self.clear_color
+    }
+
+    /// Sets the color the framebuffer is cleared with before drawing
+    /// 
+    /// # Parameters
+    /// 
+    /// clear_color: The new color
+    pub fn set_clear_color(&mut self, clear_color: Color) {
+        if clear_color != self.clear_color {
+            self.clear_color = clear_color;
+            self.full_redraw = true;
+        }
+    }
+
+    /// Gets the framebuffer with the last rendered frame
+    pub fn get_framebuffer(&self) -> &Framebuffer {
+        &self.framebuffer
+    }
+
+    /// Changes the size of the framebuffer, the content is lost
+    /// 
+    /// # Parameters
+    /// 
+    /// width: The new width in pixels
+    /// 
+    /// height: The new height in pixels
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width != self.framebuffer.get_width() || height != self.framebuffer.get_height() {
+            self.framebuffer = Framebuffer::new(width, height);
+            self.full_redraw = true;
+        }
+    }
+
+    /// Draws the view tree into the framebuffer, the root covers the entire framebuffer.
+    /// The tree must have been updated first, only resolved children are drawn
+    /// 
+    /// # Parameters
+    /// 
+    /// root: The root of the view tree
+    pub fn render(&mut self, root: &View) -> &Framebuffer {
+        self.framebuffer.clear(self.clear_color);
+        self.full_redraw = false;
+
+        let list = self.record(root);
+        list.replay(&mut FramebufferPainter::new(&mut self.framebuffer));
+
+        &self.framebuffer
+    }
+
+    /// Redraws only the damaged areas of the view tree into the framebuffer, everything else is kept from the last frame.
+    /// The entire tree is drawn if the framebuffer has not been drawn since it was created, resized or the clear color changed
+    /// 
+    /// # Parameters
+    /// 
+    /// root: The root of the view tree
+    /// 
+    /// damage: The areas which changed since the last frame
+    pub fn render_damage(&mut self, root: &View, damage: &Damage) -> &Framebuffer {
+        if self.full_redraw {
+            return self.render(root);
+        }
+
+        let window = self.framebuffer.get_rect();
+        let list = self.record(root);
+        for rect in damage.get_rects() {
+            // Whole pixels are redrawn so partly covered pixels at the edges are not blended twice
+            let left = rect.x.floor();
+            let top = rect.y.floor();
+            let area = Rect::new(left, top, (rect.x + rect.w).ceil() - left, (rect.y + rect.h).ceil() - top).intersect(&window);
+            if area.is_empty() {
+                continue;
+            }
+
+            self.framebuffer.clear_rect(area, self.clear_color);
+            list.replay_area(&mut FramebufferPainter::new(&mut self.framebuffer), area);
+        }
+
+        &self.framebuffer
+    }
+
+    /// Records the drawing commands for the view tree covering the entire framebuffer
+    /// 
+    /// # Parameters
+    /// 
+    /// root: The root of the view tree
+    fn record(&self, root: &View) -> DisplayList {
+        DisplayList::from_view(root, (self.framebuffer.get_width() as f32, self.framebuffer.get_height() as f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::gen_set_update_info;
+    use crate::{event_loop::{EventLoop, Headless}, view::{children, style}};
+    use std::rc::Rc;
+
+    #[test]
+    fn render() {
+        // A red child covering the top left quarter with a clipped blue grandchild sticking out, then a half transparent green sibling on top
+        let root = View::new_root();
+        root.get_drawable_controller().borrow_mut().set_drawable(Some(Rc::new(Color::WHITE)));
+        let scheduler = root.get_children_scheduler();
+        let mut first = View::new(gen_set_update_info(0.0, 0.5), Some(Rc::clone(&scheduler)));
+        first.get_drawable_controller().borrow_mut().set_drawable(Some(Rc::new(Color::RED)));
+        first.set_clip(true);
+        let grandchild = View::new(gen_set_update_info(0.5, 1.0), Some(first.get_children_scheduler()));
+        grandchild.get_drawable_controller().borrow_mut().set_drawable(Some(Rc::new(Color::BLUE)));
+        first.get_children_scheduler().borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(grandchild)).unwrap();
+        let second = View::new(gen_set_update_info(0.25, 0.5), Some(Rc::clone(&scheduler)));
+        second.get_drawable_controller().borrow_mut().set_drawable(Some(Rc::new(Color::new(0, 255, 0, 128))));
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(first)).unwrap();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(second)).unwrap();
+
+        let mut event_loop = EventLoop::from_backend(*root, Headless::<()>::new(8, 8)).unwrap();
+        event_loop.run_frame(&mut |_, _, _| ());
+
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        let framebuffer = renderer.render(event_loop.get_root());
+        assert_eq!(Some(Color::RED), framebuffer.get_pixel(0, 0));
+        assert_eq!(Some(Color::RED), framebuffer.get_pixel(1, 1));
+        assert_eq!(Some(Color::new(0, 128, 127, 255)), framebuffer.get_pixel(3, 3));
+        assert_eq!(Some(Color::WHITE), framebuffer.get_pixel(7, 7));
+
+        // The grandchild is clipped to the red child
+        assert_eq!(Some(Color::new(127, 255, 127, 255)), framebuffer.get_pixel(5, 2));
+        assert_eq!(Some(Color::WHITE), framebuffer.get_pixel(6, 2));
+    }
+
+    #[test]
+    fn render_damage() {
+        // A white root with a red child in the top left quarter
+        let root = View::new_root();
+        root.get_drawable_controller().borrow_mut().set_drawable(Some(Rc::new(Color::WHITE)));
+        let scheduler = root.get_children_scheduler();
+        let child = View::new(gen_set_update_info(0.0, 0.5), Some(Rc::clone(&scheduler)));
+        let child_style = child.get_style_controller();
+        let child_extent = child.get_extent_controller();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+        child_style.borrow_mut().set_style(style::Style { background: Some(Color::RED), ..style::Style::default() });
+
+        let mut event_loop = EventLoop::from_backend(*root, Headless::<()>::new(8, 8)).unwrap();
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!(&[Rect::new(0.0, 0.0, 8.0, 8.0)], event_loop.take_damage().get_rects());
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        renderer.render_damage(event_loop.get_root(), &Damage::new());
+
+        // Nothing changed so nothing is damaged
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(event_loop.take_damage().is_empty());
+
+        // A style change damages only the child
+        child_style.borrow_mut().set_style(style::Style { background: Some(Color::BLUE), ..style::Style::default() });
+        event_loop.run_frame(&mut |_, _, _| ());
+        let damage = event_loop.take_damage();
+        assert_eq!(&[Rect::new(0.0, 0.0, 4.0, 4.0)], damage.get_rects());
+
+        // Pixels outside the damage are kept even if they are out of date
+        let framebuffer = renderer.render_damage(event_loop.get_root(), &Damage::new());
+        assert_eq!(Some(Color::RED), framebuffer.get_pixel(1, 1));
+        let framebuffer = renderer.render_damage(event_loop.get_root(), &damage);
+        assert_eq!(Some(Color::BLUE), framebuffer.get_pixel(1, 1));
+        assert_eq!(Some(Color::WHITE), framebuffer.get_pixel(5, 5));
+
+        // Moving damages where the child was and where it is now
+        child_extent.borrow_mut().set_update_info(gen_set_update_info(0.5, 0.25));
+        event_loop.run_frame(&mut |_, _, _| ());
+        let damage = event_loop.take_damage();
+        assert_eq!(&[Rect::new(0.0, 0.0, 6.0, 6.0)], damage.get_rects());
+        let framebuffer = renderer.render_damage(event_loop.get_root(), &damage);
+        assert_eq!(Some(Color::WHITE), framebuffer.get_pixel(1, 1));
+        assert_eq!(Some(Color::BLUE), framebuffer.get_pixel(5, 5));
+
+        // Removing the child damages the root
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(0)).unwrap();
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!(&[Rect::new(0.0, 0.0, 8.0, 8.0)], event_loop.take_damage().get_rects());
+    }
+
+    #[test]
+    fn render_style() {
+        // A padded root with a rounded border and a faded child with a background
+        let root = View::new_root();
+        let root_style = style::Style { background: Some(Color::WHITE), border_width: 1.0, border_color: Color::BLACK, corner_radius: 2.0, padding: style::Insets::uniform(0.25), ..style::Style::default() };
+        root.get_style_controller().borrow_mut().set_style(root_style);
+        let scheduler = root.get_children_scheduler();
+        let child = View::new(gen_set_update_info(0.0, 1.0), Some(Rc::clone(&scheduler)));
+        child.get_style_controller().borrow_mut().set_style(style::Style { background: Some(Color::RED), opacity: 0.5, ..style::Style::default() });
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+
+        let mut event_loop = EventLoop::from_backend(*root, Headless::<()>::new(8, 8)).unwrap();
+        event_loop.run_frame(&mut |_, _, _| ());
+
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        let framebuffer = renderer.render(event_loop.get_root());
+
+        // The corners are cut off and the border is inside the extent
+        assert_eq!(Some(Color::TRANSPARENT), framebuffer.get_pixel(0, 0));
+        assert_eq!(Some(Color::BLACK), framebuffer.get_pixel(0, 4));
+        assert_eq!(Some(Color::WHITE), framebuffer.get_pixel(1, 4));
+
+        // The child only covers the area inside the padding
+        assert_eq!(Some(Color::WHITE), framebuffer.get_pixel(1, 1));
+        assert_eq!(Some(Color::new(255, 127, 127, 255)), framebuffer.get_pixel(2, 2));
+        assert_eq!(Some(Color::new(255, 127, 127, 255)), framebuffer.get_pixel(5, 5));
+        assert_eq!(Some(Color::WHITE), framebuffer.get_pixel(6, 6));
+    }
+}
@@ -0,0 +1,494 @@
+use super::{Color, Rect, Painter, Drawable};
+use crate::view::style::Style;
+use std::{fmt, fs, path::Path, rc::Rc};
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+/// The string put at the end of text which does not fit if the font has no ellipsis glyph
+const ELLIPSIS_FALLBACK: &str = "...";
+
+/// A font loaded from a font file, it is cheap to clone since the font data is shared
+#[derive(Clone)]
+pub struct Font {
+    /// The parsed font
+    inner: Rc<fontdue::Font>,
+}
+
+impl Font {
+    /// Loads a TrueType or OpenType font from a local file
+    ///
+    /// # Parameters
+    ///
+    /// path: The path to the font file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, FontError> {
+        let data = fs::read(path)?;
+
+        Self::from_bytes(&data)
+    }
+
+    /// Loads a TrueType or OpenType font from memory
+    ///
+    /// # Parameters
+    ///
+    /// data: The contents of the font file
+    pub fn from_bytes(data: &[u8]) -> Result<Self, FontError> {
+        let inner = fontdue::Font::from_bytes(data, fontdue::FontSettings::default()).map_err(FontError::Parse)?;
+
+        Ok(Self { inner: Rc::new(inner) })
+    }
+
+    /// Gets the name of the font stored in the font file, None if it has no name
+    pub fn get_name(&self) -> Option<&str> {
+        self.inner.name()
+    }
+
+    /// Gets the distance from the top of a line to the baseline and the distance between two baselines in pixels
+    ///
+    /// # Parameters
+    ///
+    /// size: The font size in pixels
+    fn line_metrics(&self, size: f32) -> (f32, f32) {
+        match self.inner.horizontal_line_metrics(size) {
+            Some(metrics) => (metrics.ascent, metrics.new_line_size),
+            None => (size, size),
+        }
+    }
+
+    /// Measures the width of a single line of text in pixels, this includes kerning
+    ///
+    /// # Parameters
+    ///
+    /// text: The text to measure
+    ///
+    /// size: The font size in pixels
+    fn measure_line(&self, text: &str, size: f32) -> f32 {
+        let mut width = 0.0;
+        let mut prev = None;
+        for character in text.chars() {
+            if let Some(prev) = prev {
+                width += self.inner.horizontal_kern(prev, character, size).unwrap_or(0.0);
+            }
+            width += self.inner.metrics(character, size).advance_width;
+            prev = Some(character);
+        }
+
+        width
+    }
+
+    /// Gets the string to end text with when it does not fit
+    fn ellipsis(&self) -> &'static str {
+        if self.inner.has_glyph('…') {
+            "…"
+        } else {
+            ELLIPSIS_FALLBACK
+        }
+    }
+}
+
+/// Fonts are only equal if they share the same loaded font data
+impl PartialEq for Font {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Font").field("glyph_count", &self.inner.glyph_count()).finish()
+    }
+}
+
+/// How the lines of a text are placed horizontally inside the view
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TextAlign {
+    /// Lines start at the left edge
+    #[default]
+    Left,
+    /// Lines are centered
+    Center,
+    /// Lines end at the right edge
+    Right,
+}
+
+/// Text content for a view. Each character is drawn with its own glyph with kerning applied between them,
+/// scripts which need complex shaping such as ligatures are not supported
+#[derive(Clone, Debug)]
+pub struct Text {
+    /// The text to draw, new lines always start a new line
+    pub text: String,
+    /// The font to draw with
+    pub font: Font,
+    /// The font size in pixels
+    pub size: f32,
+    /// The color of the text, None to use the text color of the style
+    pub color: Option<Color>,
+    /// How the lines are placed horizontally
+    pub align: TextAlign,
+    /// True if lines longer than the width of the view are wrapped at spaces, words longer than a line are broken up
+    pub wrap: bool,
+    /// True if text which does not fit inside the view is cut off with an ellipsis
+    pub ellipsis: bool,
+}
+
+/// A single line of laid out text
+#[derive(Clone, Debug, PartialEq)]
+struct Line {
+    /// The text of the line
+    text: String,
+    /// The width of the line in pixels
+    width: f32,
+}
+
+impl Text {
+    /// Creates new left aligned text in the color of the style which wraps and ends with an ellipsis if it does not fit
+    ///
+    /// # Parameters
+    ///
+    /// text: The text to draw
+    ///
+    /// font: The font to draw with
+    ///
+    /// size: The font size in pixels
+    pub fn new(text: &str, font: Font, size: f32) -> Self {
+        Self { text: text.to_string(), font, size, color: None, align: TextAlign::Left, wrap: true, ellipsis: true }
+    }
+
+    /// Splits the text into lines
+    ///
+    /// # Parameters
+    ///
+    /// max_width: The width to wrap at and to cut lines off at with an ellipsis, None for no limit
+    ///
+    /// max_height: The height to cut off lines at with an ellipsis, None for no limit
+    fn layout(&self, max_width: Option<f32>, max_height: Option<f32>) -> Vec<Line> {
+        let mut lines = Vec::new();
+        for paragraph in self.text.split('\n') {
+            match max_width {
+                Some(max_width) if self.wrap => self.wrap_paragraph(paragraph, max_width, &mut lines),
+                _ => lines.push(self.line(paragraph)),
+            }
+        }
+
+        if !self.ellipsis {
+            return lines;
+        }
+
+        // Remove the lines which do not fit, at least one line is always kept
+        let mut cut = false;
+        if let Some(max_height) = max_height {
+            let (_, line_height) = self.font.line_metrics(self.size);
+            let max_lines = ((max_height + 0.01) / line_height).floor().max(1.0) as usize;
+            if lines.len() > max_lines {
+                lines.truncate(max_lines);
+                cut = true;
+            }
+        }
+
+        // End lines which were cut off or are too wide with an ellipsis
+        let last = lines.len() - 1;
+        for (pos, line) in lines.iter_mut().enumerate() {
+            let too_wide = max_width.is_some_and(|max_width| line.width > max_width);
+            if too_wide || (cut && pos == last) {
+                *line = self.line_with_ellipsis(&line.text, max_width.unwrap_or(f32::INFINITY));
+            }
+        }
+
+        lines
+    }
+
+    /// Wraps a paragraph without new lines into lines which fit inside the width
+    ///
+    /// # Parameters
+    ///
+    /// paragraph: The text to wrap
+    ///
+    /// max_width: The width to wrap at
+    ///
+    /// lines: The list to add the lines to
+    fn wrap_paragraph(&self, paragraph: &str, max_width: f32, lines: &mut Vec<Line>) {
+        let mut current = String::new();
+        for word in paragraph.split_inclusive(' ') {
+            // Start a new line if the word does not fit, trailing spaces may hang outside
+            let candidate = format!("{}{}", current, word.trim_end());
+            if !current.is_empty() && self.font.measure_line(&candidate, self.size) > max_width {
+                lines.push(self.line(current.trim_end()));
+                current.clear();
+            }
+
+            // Break up words which are too long for an entire line
+            if current.is_empty() && self.font.measure_line(word.trim_end(), self.size) > max_width {
+                for character in word.chars() {
+                    let candidate = format!("{}{}", current, character);
+                    if !current.is_empty() && character != ' ' && self.font.measure_line(&candidate, self.size) > max_width {
+                        lines.push(self.line(&current));
+                        current.clear();
+                    }
+                    current.push(character);
+                }
+                continue;
+            }
+
+            current.push_str(word);
+        }
+
+        lines.push(self.line(current.trim_end()));
+    }
+
+    /// Creates a line from text
+    ///
+    /// # Parameters
+    ///
+    /// text: The text of the line
+    fn line(&self, text: &str) -> Line {
+        Line { text: text.to_string(), width: self.font.measure_line(text, self.size) }
+    }
+
+    /// Creates a line ending with an ellipsis which fits inside the width, as much of the text as possible is kept
+    ///
+    /// # Parameters
+    ///
+    /// text: The text to shorten
+    ///
+    /// max_width: The width the line must fit inside
+    fn line_with_ellipsis(&self, text: &str, max_width: f32) -> Line {
+        let ellipsis = self.font.ellipsis();
+        let mut kept: Vec<char> = text.trim_end().chars().collect();
+        loop {
+            let candidate: String = kept.iter().collect::<String>() + ellipsis;
+            let line = self.line(&candidate);
+            if line.width <= max_width || kept.is_empty() {
+                return line;
+            }
+            kept.pop();
+        }
+    }
+}
+
+impl Drawable for Text {
+    fn draw(&self, rect: Rect, style: &Style, painter: &mut dyn Painter) {
+        let color = self.color.unwrap_or(style.text_color);
+        let (ascent, line_height) = self.font.line_metrics(self.size);
+
+        // Nothing is drawn outside the view
+        painter.push_clip(rect);
+
+        for (pos, line) in self.layout(Some(rect.w), Some(rect.h)).into_iter().enumerate() {
+            let x = rect.x + match self.align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => (rect.w - line.width) / 2.0,
+                TextAlign::Right => rect.w - line.width,
+            };
+            let baseline = rect.y + pos as f32 * line_height + ascent;
+
+            painter.draw_text(&TextRun::new(x, baseline, line.text, &self.font, self.size, color));
+        }
+
+        painter.pop_clip();
+    }
+
+    fn measure(&self, max_width: Option<f32>) -> Option<(f32, f32)> {
+        let (_, line_height) = self.font.line_metrics(self.size);
+        let lines = self.layout(max_width, None);
+        let width = lines.iter().map(|line| line.width).fold(0.0, f32::max);
+
+        Some((width, lines.len() as f32 * line_height))
+    }
+}
+
+/// A single line of text placed in the window, this is what text is drawn as
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TextRun {
+    /// The x position of the start of the line in pixels
+    pub x: f32,
+    /// The y position of the baseline in pixels
+    pub baseline: f32,
+    /// The text of the line
+    pub text: String,
+    /// The font size in pixels
+    pub size: f32,
+    /// The color of the text
+    pub color: Color,
+    /// The name of the font, None if the font has no name
+    pub font_name: Option<String>,
+    /// The font to draw with, it is not serialized so text runs which have been deserialized are not drawn unless a font is given
+    #[serde(skip)]
+    pub font: Option<Font>,
+}
+
+impl TextRun {
+    /// Creates a new text run
+    ///
+    /// # Parameters
+    ///
+    /// x: The x position of the start of the line in pixels
+    ///
+    /// baseline: The y position of the baseline in pixels
+    ///
+    /// text: The text of the line
+    ///
+    /// font: The font to draw with
+    ///
+    /// size: The font size in pixels
+    ///
+    /// color: The color of the text
+    pub fn new(x: f32, baseline: f32, text: String, font: &Font, size: f32, color: Color) -> Self {
+        let font_name = font.get_name().map(|name| name.to_string());
+
+        Self { x, baseline, text, size, color, font_name, font: Some(font.clone()) }
+    }
+
+    /// Gets a rectangle containing every pixel the glyphs of the line may cover, it is empty without a font
+    pub(crate) fn get_bounds(&self) -> Rect {
+        let font = match &self.font {
+            Some(font) => font,
+            None => return Rect::default(),
+        };
+
+        // Glyphs may reach a little past their advance widths and the line metrics so the rectangle is widened by the font size
+        let (ascent, line_height) = font.line_metrics(self.size);
+        let width = font.measure_line(&self.text, self.size);
+        Rect::new(self.x - self.size, self.baseline - ascent - self.size, width + 2.0 * self.size, line_height + 2.0 * self.size)
+    }
+
+    /// Rasterizes every glyph of the line, nothing is rasterized without a font
+    ///
+    /// # Parameters
+    ///
+    /// glyph: Receives the column and row of the top left corner, the width and the coverage mask of each glyph
+    pub(crate) fn for_each_glyph<F: FnMut(i32, i32, usize, &[u8])>(&self, mut glyph: F) {
+        let font = match &self.font {
+            Some(font) => &font.inner,
+            None => return,
+        };
+
+        let mut pen = self.x;
+        let mut prev = None;
+        for character in self.text.chars() {
+            if let Some(prev) = prev {
+                pen += font.horizontal_kern(prev, character, self.size).unwrap_or(0.0);
+            }
+
+            // The glyph bitmap starts at the top left, ymin is the distance from the baseline to the bottom
+            let (metrics, bitmap) = font.rasterize(character, self.size);
+            let x = (pen + metrics.xmin as f32).round() as i32;
+            let y = (self.baseline - metrics.height as f32 - metrics.ymin as f32).round() as i32;
+            glyph(x, y, metrics.width, &bitmap);
+
+            pen += metrics.advance_width;
+            prev = Some(character);
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum FontError {
+    #[error("The font file could not be read: {:?}", .0)]
+    Io(std::io::Error),
+    #[error("The font could not be parsed: {:?}", .0)]
+    Parse(&'static str),
+}
+
+impl From<std::io::Error> for FontError {
+    fn from(err: std::io::Error) -> Self {
+        FontError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::gen_locate;
+    use crate::{render::{Framebuffer, FramebufferPainter, Damage}, view::{View, extent, children}};
+
+    fn load_font() -> Font {
+        Font::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/fonts/DejaVuSansMono.ttf")).unwrap()
+    }
+
+    fn line_texts(lines: &[Line]) -> Vec<&str> {
+        lines.iter().map(|line| line.text.as_str()).collect()
+    }
+
+    #[test]
+    fn load() {
+        assert!(matches!(Font::from_bytes(&[0, 1, 2, 3]), Err(FontError::Parse(_))));
+        assert!(matches!(Font::from_file("missing.ttf"), Err(FontError::Io(_))));
+    }
+
+    #[test]
+    fn layout() {
+        // Every character of the monospace font has the same advance
+        let text = Text::new("one two three\nfour", load_font(), 10.0);
+        let advance = text.font.measure_line("a", 10.0);
+        assert_eq!(advance * 3.0, text.font.measure_line("abc", 10.0));
+
+        // Without a width limit only new lines split the text
+        assert_eq!(vec!["one two three", "four"], line_texts(&text.layout(None, None)));
+
+        // Wrap at spaces, then break up words which do not fit
+        assert_eq!(vec!["one two", "three", "four"], line_texts(&text.layout(Some(advance * 7.5), None)));
+        assert_eq!(vec!["one", "two", "thr", "ee", "fou", "r"], line_texts(&text.layout(Some(advance * 3.5), None)));
+
+        // Cut off lines which do not fit vertically and end the last one with an ellipsis
+        let (_, line_height) = text.font.line_metrics(10.0);
+        assert_eq!(vec!["one two", "three…"], line_texts(&text.layout(Some(advance * 7.5), Some(line_height * 2.5))));
+
+        // Without wrapping lines which are too wide are cut off
+        let text_no_wrap = Text { wrap: false, ..text.clone() };
+        assert_eq!(vec!["one t…", "four"], line_texts(&text_no_wrap.layout(Some(advance * 6.5), None)));
+        let text_no_ellipsis = Text { wrap: false, ellipsis: false, ..text };
+        assert_eq!(vec!["one two three", "four"], line_texts(&text_no_ellipsis.layout(Some(advance * 6.5), Some(line_height))));
+    }
+
+    #[test]
+    fn measure() {
+        let text = Text::new("one two three", load_font(), 10.0);
+        let advance = text.font.measure_line("a", 10.0);
+        let (_, line_height) = text.font.line_metrics(10.0);
+        assert_eq!(Some((advance * 13.0, line_height)), text.measure(None));
+        assert_eq!(Some((advance * 7.0, line_height * 2.0)), text.measure(Some(advance * 7.5)));
+    }
+
+    #[test]
+    fn draw() {
+        let mut framebuffer = Framebuffer::new(40, 20);
+        let mut painter = FramebufferPainter::new(&mut framebuffer);
+        let text = Text::new("H", load_font(), 16.0);
+        let style = Style { text_color: Color::RED, ..Style::default() };
+        text.draw(Rect::new(0.0, 0.0, 20.0, 20.0), &style, &mut painter);
+
+        // Some of the glyph is drawn in the text color but nothing outside of the view
+        let pixels: Vec<Color> = (0..20).flat_map(|y| (0..40).map(move |x| (x, y))).map(|(x, y)| framebuffer.get_pixel(x, y).unwrap()).collect();
+        assert!(pixels.contains(&Color::RED));
+        assert!((0..20).all(|y| (20..40).all(|x| framebuffer.get_pixel(x, y) == Some(Color::TRANSPARENT))));
+
+        // Right aligned text starts further to the right
+        let mut framebuffer_right = Framebuffer::new(40, 20);
+        let mut painter = FramebufferPainter::new(&mut framebuffer_right);
+        Text { align: TextAlign::Right, ..text }.draw(Rect::new(0.0, 0.0, 20.0, 20.0), &style, &mut painter);
+        let first_column = |framebuffer: &Framebuffer| (0..40).find(|x| (0..20).any(|y| framebuffer.get_pixel(*x, y) != Some(Color::TRANSPARENT)));
+        assert!(first_column(&framebuffer_right) > first_column(&framebuffer));
+    }
+
+    #[test]
+    fn intrinsic() {
+        // A label whose width is half of the parent and whose height follows the wrapped text
+        let font = load_font();
+        let advance = font.measure_line("a", 10.0);
+        let (_, line_height) = font.line_metrics(10.0);
+        let mut root = View::new_root();
+        let update_x = gen_locate(extent::PositionType::Set(0.0), extent::SizeType::Set(0.5));
+        let update_y = gen_locate(extent::PositionType::Set(0.0), extent::SizeType::Intrinsic);
+        let scheduler = root.get_children_scheduler();
+        let label = View::new(extent::ExtentUpdate { x: update_x, y: update_y }, Some(Rc::clone(&scheduler)));
+        label.get_drawable_controller().borrow_mut().set_drawable(Some(Rc::new(Text::new("one two three", font, 10.0))));
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(label)).unwrap();
+
+        // The parent is wide enough for two words per line
+        root.update((advance * 16.0, 100.0), std::time::Instant::now(), &mut Damage::new()).unwrap();
+        assert_eq!(line_height * 2.0 / 100.0, root.get_children()[0].get_extent().3);
+
+        // A narrower parent needs three lines
+        root.update((advance * 12.0, 100.0), std::time::Instant::now(), &mut Damage::new()).unwrap();
+        assert_eq!(line_height * 3.0 / 100.0, root.get_children()[0].get_extent().3);
+    }
+}
@@ -0,0 +1,570 @@
+use crate::view::View;
+use std::{cell::RefCell, rc::{Rc, Weak}, fmt, str::FromStr};
+use thiserror::Error;
+use winit::event::{VirtualKeyCode, ModifiersState};
+
+/// The function run when a shortcut is pressed
+type Action = Rc<RefCell<Box<dyn FnMut()>>>;
+
+/// The names of the keys used when parsing and printing shortcuts, the first name of a key is used for printing
+const KEYS: &[(&str, VirtualKeyCode)] = &[
+    ("A", VirtualKeyCode::A), ("B", VirtualKeyCode::B), ("C", VirtualKeyCode::C), ("D", VirtualKeyCode::D), ("E", VirtualKeyCode::E),
+    ("F", VirtualKeyCode::F), ("G", VirtualKeyCode::G), ("H", VirtualKeyCode::H), ("I", VirtualKeyCode::I), ("J", VirtualKeyCode::J),
+    ("K", VirtualKeyCode::K), ("L", VirtualKeyCode::L), ("M", VirtualKeyCode::M), ("N", VirtualKeyCode::N), ("O", VirtualKeyCode::O),
+    ("P", VirtualKeyCode::P), ("Q", VirtualKeyCode::Q), ("R", VirtualKeyCode::R), ("S", VirtualKeyCode::S), ("T", VirtualKeyCode::T),
+    ("U", VirtualKeyCode::U), ("V", VirtualKeyCode::V), ("W", VirtualKeyCode::W), ("X", VirtualKeyCode::X), ("Y", VirtualKeyCode::Y),
+    ("Z", VirtualKeyCode::Z),
+    ("0", VirtualKeyCode::Key0), ("1", VirtualKeyCode::Key1), ("2", VirtualKeyCode::Key2), ("3", VirtualKeyCode::Key3), ("4", VirtualKeyCode::Key4),
+    ("5", VirtualKeyCode::Key5), ("6", VirtualKeyCode::Key6), ("7", VirtualKeyCode::Key7), ("8", VirtualKeyCode::Key8), ("9", VirtualKeyCode::Key9),
+    ("F1", VirtualKeyCode::F1), ("F2", VirtualKeyCode::F2), ("F3", VirtualKeyCode::F3), ("F4", VirtualKeyCode::F4), ("F5", VirtualKeyCode::F5),
+    ("F6", VirtualKeyCode::F6), ("F7", VirtualKeyCode::F7), ("F8", VirtualKeyCode::F8), ("F9", VirtualKeyCode::F9), ("F10", VirtualKeyCode::F10),
+    ("F11", VirtualKeyCode::F11), ("F12", VirtualKeyCode::F12),
+    ("Escape", VirtualKeyCode::Escape), ("Esc", VirtualKeyCode::Escape), ("Tab", VirtualKeyCode::Tab), ("Enter", VirtualKeyCode::Return),
+    ("Return", VirtualKeyCode::Return), ("Space", VirtualKeyCode::Space), ("Backspace", VirtualKeyCode::Back), ("Delete", VirtualKeyCode::Delete),
+    ("Insert", VirtualKeyCode::Insert), ("Home", VirtualKeyCode::Home), ("End", VirtualKeyCode::End), ("PageUp", VirtualKeyCode::PageUp),
+    ("PageDown", VirtualKeyCode::PageDown), ("Up", VirtualKeyCode::Up), ("Down", VirtualKeyCode::Down), ("Left", VirtualKeyCode::Left),
+    ("Right", VirtualKeyCode::Right), ("Plus", VirtualKeyCode::Plus), ("Minus", VirtualKeyCode::Minus), ("Equals", VirtualKeyCode::Equals),
+    ("Comma", VirtualKeyCode::Comma), ("Period", VirtualKeyCode::Period), ("Slash", VirtualKeyCode::Slash), ("Backslash", VirtualKeyCode::Backslash),
+    ("Semicolon", VirtualKeyCode::Semicolon), ("Apostrophe", VirtualKeyCode::Apostrophe), ("Grave", VirtualKeyCode::Grave),
+    ("LBracket", VirtualKeyCode::LBracket), ("RBracket", VirtualKeyCode::RBracket),
+];
+
+/// The names of the modifiers used when parsing and printing shortcuts, the first name of a modifier is used for printing
+const MODIFIERS: &[(&str, ModifiersState)] = &[
+    ("Ctrl", ModifiersState::CTRL), ("Control", ModifiersState::CTRL), ("Alt", ModifiersState::ALT), ("Option", ModifiersState::ALT),
+    ("Shift", ModifiersState::SHIFT), ("Logo", ModifiersState::LOGO), ("Super", ModifiersState::LOGO), ("Cmd", ModifiersState::LOGO),
+];
+
+/// A single key pressed together with the modifiers held down
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Chord {
+    /// The modifiers which must be held down, no other modifiers may be held down
+    pub modifiers: ModifiersState,
+    /// The key to press
+    pub key: VirtualKeyCode,
+}
+
+impl Chord {
+    /// Creates a new chord
+    ///
+    /// # Parameters
+    ///
+    /// modifiers: The modifiers which must be held down
+    ///
+    /// key: The key to press
+    pub fn new(modifiers: ModifiersState, key: VirtualKeyCode) -> Self {
+        Self { modifiers, key }
+    }
+}
+
+impl FromStr for Chord {
+    type Err = ShortcutError;
+
+    /// Parses a chord such as "Ctrl+Shift+K", the names are not case sensitive and the key comes last
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = source.split('+').map(str::trim).collect();
+        let key = parts.pop().unwrap_or_default();
+        let key = KEYS.iter().find(|(name, _)| name.eq_ignore_ascii_case(key)).ok_or_else(|| ShortcutError::UnknownKey(key.to_string()))?.1;
+
+        // The modifiers may come in any order
+        let mut modifiers = ModifiersState::empty();
+        for part in parts {
+            let modifier = MODIFIERS.iter().find(|(name, _)| name.eq_ignore_ascii_case(part)).ok_or_else(|| ShortcutError::UnknownModifier(part.to_string()))?.1;
+            modifiers |= modifier;
+        }
+
+        Ok(Self { modifiers, key })
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Print the modifiers in a fixed order using their first name
+        for modifier in [ModifiersState::CTRL, ModifiersState::ALT, ModifiersState::SHIFT, ModifiersState::LOGO] {
+            if self.modifiers.contains(modifier) {
+                if let Some((name, _)) = MODIFIERS.iter().find(|(_, own)| *own == modifier) {
+                    write!(f, "{}+", name)?;
+                }
+            }
+        }
+
+        match KEYS.iter().find(|(_, key)| *key == self.key) {
+            Some((name, _)) => write!(f, "{}", name),
+            None => write!(f, "{:?}", self.key),
+        }
+    }
+}
+
+/// A sequence of chords pressed one after the other, such as "Ctrl+K Ctrl+C"
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    /// The chords in the order they are pressed, there is always at least one
+    chords: Vec<Chord>,
+}
+
+impl Shortcut {
+    /// Creates a shortcut from a sequence of chords
+    ///
+    /// # Parameters
+    ///
+    /// chords: The chords in the order they are pressed
+    ///
+    /// # Errors
+    ///
+    /// ShortcutError::Empty: If there are no chords
+    pub fn new(chords: Vec<Chord>) -> Result<Self, ShortcutError> {
+        if chords.is_empty() {
+            return Err(ShortcutError::Empty);
+        }
+
+        Ok(Self { chords })
+    }
+
+    /// Gets the chords in the order they are pressed
+    pub fn get_chords(&self) -> &[Chord] {
+        &self.chords
+    }
+
+    /// Checks if one of the shortcuts is the start of the other, such shortcuts cannot be told apart in the same scope
+    ///
+    /// # Parameters
+    ///
+    /// other: The shortcut to compare with
+    fn overlaps(&self, other: &Shortcut) -> bool {
+        self.chords.starts_with(&other.chords) || other.chords.starts_with(&self.chords)
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = ShortcutError;
+
+    /// Parses a shortcut of chords separated by spaces such as "Ctrl+K Ctrl+C"
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let chords = source.split_whitespace().map(Chord::from_str).collect::<Result<Vec<Chord>, ShortcutError>>()?;
+
+        Self::new(chords)
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chords: Vec<String> = self.chords.iter().map(Chord::to_string).collect();
+        write!(f, "{}", chords.join(" "))
+    }
+}
+
+/// Where a shortcut is active
+#[derive(Clone, Debug)]
+pub enum Scope {
+    /// Active everywhere in the window
+    Global,
+    /// Active while the view with this owner token or one of its descendants is focused, it takes precedence over shortcuts of the scopes around it
+    Subtree(Weak<()>),
+}
+
+impl Scope {
+    /// Creates a scope for a view and all of its descendants, the shortcuts of the scope are removed once the view is dropped
+    ///
+    /// # Parameters
+    ///
+    /// view: The view at the top of the subtree
+    pub fn subtree(view: &View) -> Self {
+        Self::Subtree(view.get_owner())
+    }
+
+    /// Checks if both scopes cover the same views
+    ///
+    /// # Parameters
+    ///
+    /// other: The scope to compare with
+    fn is_same(&self, other: &Scope) -> bool {
+        match (self, other) {
+            (Self::Global, Self::Global) => true,
+            (Self::Subtree(own), Self::Subtree(other)) => own.ptr_eq(other),
+            _ => false,
+        }
+    }
+
+    /// Gets how specific the scope is for the focused view, None if the scope is not active.
+    /// The global scope is 0 and a subtree is deeper the closer it is to the focused view
+    ///
+    /// # Parameters
+    ///
+    /// path: The owner tokens of the focused view and its ancestors from the root down
+    fn get_depth(&self, path: &[Weak<()>]) -> Option<usize> {
+        match self {
+            Self::Global => Some(0),
+            Self::Subtree(view) => path.iter().position(|own| own.ptr_eq(view)).map(|pos| pos + 1),
+        }
+    }
+
+    /// Checks if the scope was tied to a view which has since been dropped
+    fn is_orphaned(&self) -> bool {
+        matches!(self, Self::Subtree(view) if view.strong_count() == 0)
+    }
+}
+
+/// Identifies a shortcut bound in a shortcut controller
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShortcutId(usize);
+
+/// A shortcut bound to an action
+struct Binding {
+    /// The id of the binding
+    id: ShortcutId,
+    /// The keys to press
+    shortcut: Shortcut,
+    /// Where the shortcut is active
+    scope: Scope,
+    /// The function to run
+    action: Action,
+}
+
+/// The result of pressing a chord
+enum Press {
+    /// No shortcut starts with the pressed chords
+    Unhandled,
+    /// A shortcut starts with the pressed chords, the next chord decides which one runs
+    Pending,
+    /// A shortcut was completed
+    Run(Action),
+}
+
+/// Holds all the shortcuts of an event loop, a key press which is not handled by the focused view or its ancestors is matched against them.
+/// Of all active shortcuts matching the pressed keys only those of the scope closest to the focused view are used
+#[derive(Default)]
+pub struct ShortcutController {
+    /// The bound shortcuts
+    bindings: Vec<Binding>,
+    /// The chords of a sequence which has been started but not completed
+    pending: Vec<Chord>,
+    /// The id given to the next binding
+    next_id: usize,
+}
+
+impl ShortcutController {
+    /// Binds a shortcut to an action.
+    /// Returns an error if it cannot be told apart from a shortcut bound in the same scope
+    ///
+    /// # Parameters
+    ///
+    /// shortcut: The keys to press
+    ///
+    /// scope: Where the shortcut is active
+    ///
+    /// action: The function to run, it may bind and unbind shortcuts
+    ///
+    /// # Errors
+    ///
+    /// ShortcutError::Conflict: If a shortcut of the same scope is the same or one of them starts with the other
+    pub fn bind<F: FnMut() + 'static>(&mut self, shortcut: Shortcut, scope: Scope, action: F) -> Result<ShortcutId, ShortcutError> {
+        self.bindings.retain(|binding| !binding.scope.is_orphaned());
+        if let Some(conflict) = self.get_conflicts(&shortcut, &scope).first() {
+            return Err(ShortcutError::Conflict(shortcut.to_string(), *conflict));
+        }
+
+        let id = ShortcutId(self.next_id);
+        self.next_id += 1;
+        self.bindings.push(Binding { id, shortcut, scope, action: Rc::new(RefCell::new(Box::new(action))) });
+
+        Ok(id)
+    }
+
+    /// Removes a shortcut, returns false if it was not bound
+    ///
+    /// # Parameters
+    ///
+    /// id: The shortcut to remove
+    pub fn unbind(&mut self, id: ShortcutId) -> bool {
+        let len = self.bindings.len();
+        self.bindings.retain(|binding| binding.id != id);
+
+        self.bindings.len() != len
+    }
+
+    /// Checks if a shortcut is still bound
+    ///
+    /// # Parameters
+    ///
+    /// id: The shortcut to check
+    pub fn is_bound(&self, id: ShortcutId) -> bool {
+        self.bindings.iter().any(|binding| binding.id == id)
+    }
+
+    /// Gets all the bound shortcuts which would conflict with a new shortcut
+    ///
+    /// # Parameters
+    ///
+    /// shortcut: The keys of the new shortcut
+    ///
+    /// scope: The scope of the new shortcut
+    pub fn get_conflicts(&self, shortcut: &Shortcut, scope: &Scope) -> Vec<ShortcutId> {
+        self.bindings.iter()
+            .filter(|binding| !binding.scope.is_orphaned() && binding.scope.is_same(scope) && binding.shortcut.overlaps(shortcut))
+            .map(|binding| binding.id)
+            .collect()
+    }
+
+    /// Gets the chords of a sequence which has been started but not completed
+    pub fn get_pending(&self) -> &[Chord] {
+        &self.pending
+    }
+
+    /// Adds a chord to the pressed sequence and finds the shortcut it completes
+    ///
+    /// # Parameters
+    ///
+    /// chord: The pressed chord
+    ///
+    /// path: The owner tokens of the focused view and its ancestors from the root down
+    fn press(&mut self, chord: Chord, path: &[Weak<()>]) -> Press {
+        self.bindings.retain(|binding| !binding.scope.is_orphaned());
+        self.pending.push(chord);
+
+        loop {
+            // Only the closest scope with a matching shortcut is used so subtrees can override the scopes around them
+            let matching: Vec<(usize, &Binding)> = self.bindings.iter()
+                .filter(|binding| binding.shortcut.chords.starts_with(&self.pending))
+                .filter_map(|binding| binding.scope.get_depth(path).map(|depth| (depth, binding)))
+                .collect();
+            if let Some(depth) = matching.iter().map(|(depth, _)| *depth).max() {
+                let complete = matching.iter().find(|(own, binding)| *own == depth && binding.shortcut.chords.len() == self.pending.len());
+                return match complete {
+                    Some((_, binding)) => {
+                        let action = Rc::clone(&binding.action);
+                        self.pending.clear();
+                        Press::Run(action)
+                    }
+                    None => Press::Pending,
+                };
+            }
+
+            // A broken sequence is dropped and the last chord is tried on its own
+            if self.pending.len() > 1 {
+                self.pending = vec![chord];
+                continue;
+            }
+            self.pending.clear();
+
+            return Press::Unhandled;
+        }
+    }
+}
+
+impl fmt::Debug for ShortcutController {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bindings: Vec<String> = self.bindings.iter().map(|binding| binding.shortcut.to_string()).collect();
+        f.debug_struct("ShortcutController")
+            .field("bindings", &bindings)
+            .field("pending", &self.pending)
+            .field("next_id", &self.next_id)
+            .finish()
+    }
+}
+
+/// Presses a chord and runs the action of the shortcut it completes, the action may change the shortcuts.
+/// Returns true if the chord belongs to a shortcut, including one which is not complete yet
+///
+/// # Parameters
+///
+/// controller: The controller of the shortcuts, it must not be borrowed
+///
+/// chord: The pressed chord
+///
+/// path: The owner tokens of the focused view and its ancestors from the root down
+pub(crate) fn press(controller: &RefCell<ShortcutController>, chord: Chord, path: &[Weak<()>]) -> bool {
+    let press = controller.borrow_mut().press(chord, path);
+    match press {
+        Press::Unhandled => false,
+        Press::Pending => true,
+        Press::Run(action) => {
+            (action.borrow_mut())();
+            true
+        }
+    }
+}
+
+/// Checks if a key is a modifier, pressing a modifier does not affect shortcuts
+///
+/// # Parameters
+///
+/// key: The key to check
+pub(crate) fn is_modifier(key: VirtualKeyCode) -> bool {
+    matches!(key, VirtualKeyCode::LShift | VirtualKeyCode::RShift | VirtualKeyCode::LControl | VirtualKeyCode::RControl | VirtualKeyCode::LAlt | VirtualKeyCode::RAlt | VirtualKeyCode::LWin | VirtualKeyCode::RWin)
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ShortcutError {
+    #[error("A shortcut must have at least one chord")]
+    Empty,
+    #[error("The key {:?} is unknown", .0)]
+    UnknownKey(String),
+    #[error("The modifier {:?} is unknown", .0)]
+    UnknownModifier(String),
+    #[error("The shortcut {} conflicts with the shortcut {:?} of the same scope", .0, .1)]
+    Conflict(String, ShortcutId),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{gen_set_update_info, gen_event_loop, gen_key_input};
+    use crate::view::{children, focus::{KeyEvent, FocusOperation}};
+    use winit::event::{WindowEvent, KeyboardInput, ElementState};
+
+    fn chord(source: &str) -> Chord {
+        source.parse().unwrap()
+    }
+
+    /// Binds a shortcut which records its name when it runs
+    fn bind(controller: &mut ShortcutController, source: &str, scope: Scope, runs: &Rc<RefCell<Vec<String>>>) -> Result<ShortcutId, ShortcutError> {
+        let (runs, name) = (Rc::clone(runs), source.to_string());
+        controller.bind(source.parse().unwrap(), scope, move || runs.borrow_mut().push(name.clone()))
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Chord::new(ModifiersState::CTRL | ModifiersState::SHIFT, VirtualKeyCode::K), chord("shift + Ctrl+k"));
+        assert_eq!(Chord::new(ModifiersState::empty(), VirtualKeyCode::Return), chord("Enter"));
+        assert_eq!("Ctrl+Shift+K", chord("Shift+Control+K").to_string());
+
+        let shortcut: Shortcut = "Ctrl+K  Ctrl+C".parse().unwrap();
+        assert_eq!(&[chord("Ctrl+K"), chord("Ctrl+C")], shortcut.get_chords());
+        assert_eq!("Ctrl+K Ctrl+C", shortcut.to_string());
+
+        assert_eq!(Err(ShortcutError::UnknownKey("Nope".to_string())), "Ctrl+Nope".parse::<Chord>());
+        assert_eq!(Err(ShortcutError::UnknownModifier("Hyper".to_string())), "Hyper+A".parse::<Chord>());
+        assert_eq!(Err(ShortcutError::UnknownKey("".to_string())), "Ctrl+".parse::<Chord>());
+        assert_eq!(Err(ShortcutError::Empty), " ".parse::<Shortcut>());
+    }
+
+    #[test]
+    fn sequences() {
+        let controller = RefCell::new(ShortcutController::default());
+        let runs = Rc::new(RefCell::new(Vec::new()));
+        bind(&mut controller.borrow_mut(), "Ctrl+S", Scope::Global, &runs).unwrap();
+        bind(&mut controller.borrow_mut(), "Ctrl+K Ctrl+C", Scope::Global, &runs).unwrap();
+        let uncomment = bind(&mut controller.borrow_mut(), "Ctrl+K Ctrl+U", Scope::Global, &runs).unwrap();
+
+        // Single chords run right away and sequences wait for their last chord
+        assert!(press(&controller, chord("Ctrl+S"), &[]));
+        assert!(press(&controller, chord("Ctrl+K"), &[]));
+        assert_eq!(&[chord("Ctrl+K")], controller.borrow().get_pending());
+        assert!(press(&controller, chord("Ctrl+C"), &[]));
+        assert_eq!(vec!["Ctrl+S", "Ctrl+K Ctrl+C"], *runs.borrow());
+        assert!(controller.borrow().get_pending().is_empty());
+
+        // A broken sequence starts over with the last chord, the exact modifiers must be held
+        assert!(!press(&controller, chord("Ctrl+Shift+S"), &[]));
+        assert!(press(&controller, chord("Ctrl+K"), &[]));
+        assert!(press(&controller, chord("Ctrl+S"), &[]));
+        assert!(!press(&controller, chord("C"), &[]));
+        assert_eq!(vec!["Ctrl+S", "Ctrl+K Ctrl+C", "Ctrl+S"], *runs.borrow());
+
+        // Removed shortcuts no longer run
+        assert!(controller.borrow_mut().unbind(uncomment));
+        assert!(!controller.borrow().is_bound(uncomment));
+        press(&controller, chord("Ctrl+K"), &[]);
+        assert!(!press(&controller, chord("Ctrl+U"), &[]));
+        assert_eq!(3, runs.borrow().len());
+    }
+
+    #[test]
+    fn conflicts() {
+        let root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        let child = View::new(gen_set_update_info(0.0, 0.5), Some(Rc::clone(&scheduler)));
+        let mut controller = ShortcutController::default();
+        let runs = Rc::new(RefCell::new(Vec::new()));
+        let save = bind(&mut controller, "Ctrl+S", Scope::Global, &runs).unwrap();
+        let comment = bind(&mut controller, "Ctrl+K Ctrl+C", Scope::Global, &runs).unwrap();
+
+        // The same keys or a prefix in the same scope cannot be told apart
+        assert_eq!(Err(ShortcutError::Conflict("Ctrl+S".to_string(), save)), bind(&mut controller, "Ctrl+S", Scope::Global, &runs));
+        assert_eq!(Err(ShortcutError::Conflict("Ctrl+K".to_string(), comment)), bind(&mut controller, "Ctrl+K", Scope::Global, &runs));
+        assert_eq!(vec![comment], controller.get_conflicts(&"Ctrl+K Ctrl+C Ctrl+D".parse().unwrap(), &Scope::Global));
+
+        // Other scopes may override them
+        bind(&mut controller, "Ctrl+S", Scope::subtree(&child), &runs).unwrap();
+        assert!(bind(&mut controller, "Ctrl+S", Scope::subtree(&child), &runs).is_err());
+        assert!(bind(&mut controller, "Ctrl+S", Scope::subtree(&root), &runs).is_ok());
+
+        // The shortcuts of a dropped view are removed even while its controllers are still held
+        let focus = child.get_focus_controller();
+        drop(child);
+        bind(&mut controller, "Ctrl+D", Scope::Global, &runs).unwrap();
+        assert_eq!(4, controller.bindings.len());
+        drop(focus);
+    }
+
+    #[test]
+    fn scopes() {
+        let mut root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        let child = View::new(gen_set_update_info(0.0, 0.5), Some(Rc::clone(&scheduler)));
+        let child_scope = Scope::subtree(&child);
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+        root.resolve_children();
+        let controller = RefCell::new(ShortcutController::default());
+        let runs = Rc::new(RefCell::new(Vec::new()));
+        bind(&mut controller.borrow_mut(), "Ctrl+S", Scope::Global, &runs).unwrap();
+        bind(&mut controller.borrow_mut(), "Ctrl+K Ctrl+C", Scope::Global, &runs).unwrap();
+        bind(&mut controller.borrow_mut(), "Ctrl+K", child_scope, &runs).unwrap();
+        bind(&mut controller.borrow_mut(), "Escape", Scope::subtree(&root), &runs).unwrap();
+
+        // A subtree is only active while it contains the focus and then shadows the global shortcuts
+        let path = vec![root.get_owner(), root.get_children()[0].get_owner()];
+        press(&controller, chord("Ctrl+K"), &path[..1]);
+        press(&controller, chord("Ctrl+C"), &path[..1]);
+        press(&controller, chord("Ctrl+K"), &path);
+        press(&controller, chord("Ctrl+C"), &path);
+        press(&controller, chord("Ctrl+S"), &path);
+        press(&controller, chord("Escape"), &path);
+        assert!(!press(&controller, chord("Escape"), &[]));
+        assert_eq!(vec!["Ctrl+K Ctrl+C", "Ctrl+K", "Ctrl+S", "Escape"], *runs.borrow());
+    }
+
+    #[test]
+    fn event_loop() {
+        let press = |key| gen_key_input(key, ElementState::Pressed);
+        let mut event_loop = gen_event_loop(&[gen_set_update_info(0.0, 0.5)]);
+        event_loop.run_frame(&mut |_, _, _| ());
+        let child = event_loop.get_root().get_children()[0].get_focus_controller();
+        child.borrow_mut().set_focusable(true);
+        event_loop.get_focus_scheduler().borrow_mut().push_operation(FocusOperation::Set(Rc::clone(&child))).unwrap();
+        event_loop.run_frame(&mut |_, _, _| ());
+
+        // The focused view takes the characters and escape, everything else reaches the shortcuts
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let child_received = Rc::clone(&received);
+        child.borrow_mut().add_key_handler(move |event| {
+            child_received.borrow_mut().push(*event);
+            matches!(event, KeyEvent::Character(_) | KeyEvent::Input(KeyboardInput { virtual_keycode: Some(VirtualKeyCode::Escape), .. }))
+        });
+        let saved = Rc::new(RefCell::new(0));
+        let counter = Rc::clone(&saved);
+        let shortcuts = event_loop.get_shortcut_controller();
+        shortcuts.borrow_mut().bind("Ctrl+S".parse().unwrap(), Scope::Global, move || *counter.borrow_mut() += 1).unwrap();
+        shortcuts.borrow_mut().bind("Escape".parse().unwrap(), Scope::Global, || panic!("The focused view handles escape")).unwrap();
+
+        event_loop.get_backend_mut().push_window_event(WindowEvent::ReceivedCharacter('s'));
+        event_loop.get_backend_mut().push_window_event(press(VirtualKeyCode::S));
+        event_loop.get_backend_mut().push_window_event(WindowEvent::ModifiersChanged(ModifiersState::CTRL));
+        event_loop.get_backend_mut().push_window_event(press(VirtualKeyCode::LControl));
+        event_loop.get_backend_mut().push_window_event(press(VirtualKeyCode::S));
+        event_loop.get_backend_mut().push_window_event(press(VirtualKeyCode::Escape));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert_eq!(1, *saved.borrow());
+        assert_eq!(6, received.borrow().len());
+        assert_eq!(KeyEvent::Character('s'), received.borrow()[0]);
+        assert_eq!(KeyEvent::Modifiers(ModifiersState::CTRL), received.borrow()[2]);
+
+        // A view which handles tab keeps the focus
+        event_loop.get_root().get_focus_controller().borrow_mut().set_focusable(true);
+        child.borrow_mut().add_key_handler(|event| matches!(event, KeyEvent::Input(KeyboardInput { virtual_keycode: Some(VirtualKeyCode::Tab), .. })));
+        event_loop.get_backend_mut().push_window_event(WindowEvent::ModifiersChanged(ModifiersState::empty()));
+        event_loop.get_backend_mut().push_window_event(press(VirtualKeyCode::Tab));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(child.borrow().is_focused());
+    }
+}
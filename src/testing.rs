@@ -0,0 +1,339 @@
+use crate::{event_loop::{EventLoop, Headless}, render::{Bitmap, Color, Framebuffer, ImageError, SoftwareRenderer}, view::{self, View, children}, theme};
+use std::{fs, path::{Path, PathBuf}};
+use image::ImageEncoder;
+use thiserror::Error;
+
+/// The environment variable which makes snapshot tests store the rendered image instead of comparing it when it is set to anything but 0
+pub const BLESS_VAR: &str = "VIEWS_BLESS";
+
+/// Golden image testing, a view tree is laid out and rendered for a virtual window and compared to a stored PNG.
+/// Run the tests with the environment variable from BLESS_VAR set to 1 to store new snapshots
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    /// The directory the snapshots are stored in
+    dir: PathBuf,
+    /// The size of the virtual window in pixels
+    window_size: (u32, u32),
+    /// The largest difference allowed between a channel of the rendered and the stored pixel
+    tolerance: u8,
+    /// The color the framebuffer is cleared with before drawing
+    clear_color: Color,
+    /// The theme the views are styled with, None to use the styles of the views
+    theme: Option<theme::Theme>,
+}
+
+impl Snapshot {
+    /// Creates a new snapshot tester with a transparent background and no tolerance
+    ///
+    /// # Parameters
+    ///
+    /// dir: The directory the snapshots are stored in
+    ///
+    /// width: The width of the virtual window in pixels
+    ///
+    /// height: The height of the virtual window in pixels
+    pub fn new<P: AsRef<Path>>(dir: P, width: u32, height: u32) -> Self {
+        Self { dir: dir.as_ref().to_path_buf(), window_size: (width, height), tolerance: 0, clear_color: Color::TRANSPARENT, theme: None }
+    }
+
+    /// Sets the largest difference allowed between a channel of the rendered and the stored pixel
+    ///
+    /// # Parameters
+    ///
+    /// tolerance: The allowed difference, 0 requires the images to be identical
+    pub fn set_tolerance(&mut self, tolerance: u8) {
+        self.tolerance = tolerance;
+    }
+
+    /// Sets the color the framebuffer is cleared with before drawing
+    ///
+    /// # Parameters
+    ///
+    /// clear_color: The new color
+    pub fn set_clear_color(&mut self, clear_color: Color) {
+        self.clear_color = clear_color;
+    }
+
+    /// Sets the theme the views are styled with
+    ///
+    /// # Parameters
+    ///
+    /// theme: The new theme, None to use the styles of the views
+    pub fn set_theme(&mut self, theme: Option<theme::Theme>) {
+        self.theme = theme;
+    }
+
+    /// Gets the path of the stored image of a snapshot
+    ///
+    /// # Parameters
+    ///
+    /// name: The name of the snapshot
+    pub fn get_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.png", name))
+    }
+
+    /// Gets the path the difference image of a snapshot is written to when it does not match
+    ///
+    /// # Parameters
+    ///
+    /// name: The name of the snapshot
+    pub fn get_diff_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.diff.png", name))
+    }
+
+    /// Lays out the view tree for the virtual window and renders it, returns an error if the tree could not be laid out
+    ///
+    /// # Parameters
+    ///
+    /// root: The root of the view tree
+    pub fn render(&self, root: View) -> Result<Framebuffer, SnapshotError> {
+        let (width, height) = self.window_size;
+        let mut event_loop = EventLoop::from_backend(root, Headless::<()>::new(width, height))?;
+        event_loop.get_theme_controller().borrow_mut().set_theme(self.theme.clone());
+        event_loop.run_frame(&mut |_, _, _| ());
+
+        // A tree which could not be laid out would be drawn where the views happened to be
+        if let Some(err) = event_loop.get_layout_error() {
+            return Err(SnapshotError::Layout(err.clone()));
+        }
+
+        let mut renderer = SoftwareRenderer::new(width, height);
+        renderer.set_clear_color(self.clear_color);
+        renderer.render(event_loop.get_root());
+
+        Ok(renderer.get_framebuffer().clone())
+    }
+
+    /// Renders the view tree and compares it to the stored snapshot.
+    /// If blessing is turned on the snapshot is stored instead, otherwise a difference image is written next to the snapshot if they do not match
+    ///
+    /// # Parameters
+    ///
+    /// name: The name of the snapshot, it is used as the file name
+    ///
+    /// root: The root of the view tree
+    pub fn check(&self, name: &str, root: View) -> Result<(), SnapshotError> {
+        let actual = self.render(root)?;
+        let path = self.get_path(name);
+        let diff_path = self.get_diff_path(name);
+
+        // Store the new snapshot and remove any old difference
+        if is_blessing() {
+            fs::create_dir_all(&self.dir)?;
+            write_png(&path, &actual)?;
+            if diff_path.exists() {
+                fs::remove_file(&diff_path)?;
+            }
+            return Ok(());
+        }
+
+        if !path.exists() {
+            return Err(SnapshotError::Missing(path));
+        }
+        let expected = Bitmap::from_file(&path)?;
+        if (expected.get_width(), expected.get_height()) != (actual.get_width(), actual.get_height()) {
+            return Err(SnapshotError::Size((expected.get_width(), expected.get_height()), (actual.get_width(), actual.get_height())));
+        }
+
+        let (diff, count, max_difference) = self.compare(&expected, &actual);
+        if count > 0 {
+            write_png(&diff_path, &diff)?;
+            return Err(SnapshotError::Mismatch(path, count, max_difference, diff_path));
+        }
+
+        // A passing snapshot leaves no difference behind
+        if diff_path.exists() {
+            fs::remove_file(&diff_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Renders the view tree and compares it to the stored snapshot, panics if they do not match
+    ///
+    /// # Parameters
+    ///
+    /// name: The name of the snapshot, it is used as the file name
+    ///
+    /// root: The root of the view tree
+    pub fn assert(&self, name: &str, root: View) {
+        if let Err(err) = self.check(name, root) {
+            panic!("Snapshot {} failed: {}", name, err);
+        }
+    }
+
+    /// Compares two images of the same size.
+    ///
+    /// Returns a difference image with the differing pixels in red and the others faded, the number of differing pixels and the largest difference of a channel
+    ///
+    /// # Parameters
+    ///
+    /// expected: The stored image
+    ///
+    /// actual: The rendered image
+    fn compare(&self, expected: &Bitmap, actual: &Framebuffer) -> (Framebuffer, usize, u8) {
+        let mut diff = Framebuffer::new(actual.get_width(), actual.get_height());
+        let mut count = 0;
+        let mut max_difference = 0;
+        for y in 0..actual.get_height() {
+            for x in 0..actual.get_width() {
+                let (expected, actual) = (expected.get_pixel(x, y).unwrap_or_default(), actual.get_pixel(x, y).unwrap_or_default());
+                let difference = [expected.r.abs_diff(actual.r), expected.g.abs_diff(actual.g), expected.b.abs_diff(actual.b), expected.a.abs_diff(actual.a)].into_iter().max().unwrap_or(0);
+                max_difference = max_difference.max(difference);
+
+                if difference > self.tolerance {
+                    count += 1;
+                    diff.set_pixel(x, y, Color::RED);
+                } else {
+                    let gray = ((expected.r as u32 + expected.g as u32 + expected.b as u32) / 3) as u8;
+                    diff.set_pixel(x, y, Color::new(gray, gray, gray, expected.a / 4));
+                }
+            }
+        }
+
+        (diff, count, max_difference)
+    }
+}
+
+/// Checks if snapshots should be stored instead of compared
+fn is_blessing() -> bool {
+    std::env::var(BLESS_VAR).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Writes a framebuffer to a PNG file
+///
+/// # Parameters
+///
+/// path: The path of the file
+///
+/// framebuffer: The image to write
+fn write_png(path: &Path, framebuffer: &Framebuffer) -> Result<(), SnapshotError> {
+    let file = fs::File::create(path)?;
+    let encoder = image::codecs::png::PngEncoder::new(std::io::BufWriter::new(file));
+    encoder.write_image(framebuffer.get_pixels(), framebuffer.get_width(), framebuffer.get_height(), image::ColorType::Rgba8).map_err(ImageError::from)?;
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("The view tree is invalid: {:?}", .0)]
+    Invalid(children::ValidateError),
+    #[error("The view tree could not be laid out: {}", .0)]
+    Layout(view::UpdateError),
+    #[error("The snapshot file could not be read or written: {:?}", .0)]
+    Io(std::io::Error),
+    #[error("The snapshot image could not be decoded or encoded: {}", .0)]
+    Image(ImageError),
+    #[error("The snapshot {:?} does not exist, run with {}=1 to create it", .0, BLESS_VAR)]
+    Missing(PathBuf),
+    #[error("The snapshot is {:?} pixels but the rendered image is {:?} pixels", .0, .1)]
+    Size((u32, u32), (u32, u32)),
+    #[error("{1} pixels differ from the snapshot {0:?} by up to {2}, the differences are marked in {3:?}")]
+    Mismatch(PathBuf, usize, u8, PathBuf),
+}
+
+impl From<children::ValidateError> for SnapshotError {
+    fn from(err: children::ValidateError) -> Self {
+        SnapshotError::Invalid(err)
+    }
+}
+
+impl From<view::UpdateError> for SnapshotError {
+    fn from(err: view::UpdateError) -> Self {
+        SnapshotError::Layout(err)
+    }
+}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(err: std::io::Error) -> Self {
+        SnapshotError::Io(err)
+    }
+}
+
+impl From<ImageError> for SnapshotError {
+    fn from(err: ImageError) -> Self {
+        SnapshotError::Image(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{gen_set_update_info, gen_update_info};
+    use crate::view::{style, extent};
+    use std::rc::Rc;
+
+    /// A padded, bordered root with a rounded child and a faded child on top
+    fn gen_root(child_pos: f32) -> View {
+        let root = View::new_root();
+        root.get_style_controller().borrow_mut().set_style(style::Style { background: Some(Color::WHITE), border_width: 2.0, border_color: Color::BLACK, padding: style::Insets::uniform(0.1), ..style::Style::default() });
+        let scheduler = root.get_children_scheduler();
+        let rounded = View::new(gen_set_update_info(child_pos, 0.5), Some(Rc::clone(&scheduler)));
+        rounded.get_style_controller().borrow_mut().set_style(style::Style { background: Some(Color::RED), corner_radius: 6.0, ..style::Style::default() });
+        let faded = View::new(gen_set_update_info(0.4, 0.5), Some(Rc::clone(&scheduler)));
+        faded.get_style_controller().borrow_mut().set_style(style::Style { background: Some(Color::BLUE), opacity: 0.5, ..style::Style::default() });
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(rounded)).unwrap();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(faded)).unwrap();
+
+        *root
+    }
+
+    #[test]
+    fn golden() {
+        // The stored snapshot guards the layout and the software renderer against regressions
+        let snapshot = Snapshot::new(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/snapshots"), 40, 30);
+        snapshot.assert("layout", gen_root(0.0));
+    }
+
+    #[test]
+    fn compare() {
+        // The mechanics are tested in a directory of their own so blessing the real snapshots does not affect them
+        if is_blessing() {
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("views_snapshot_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut snapshot = Snapshot::new(&dir, 40, 30);
+        assert!(matches!(snapshot.check("moved", gen_root(0.0)), Err(SnapshotError::Missing(_))));
+
+        // Store the snapshot, it matches itself
+        write_png(&snapshot.get_path("moved"), &snapshot.render(gen_root(0.0)).unwrap()).unwrap();
+        snapshot.check("moved", gen_root(0.0)).unwrap();
+
+        // Moving a view fails and marks the differences
+        match snapshot.check("moved", gen_root(0.1)) {
+            Err(SnapshotError::Mismatch(_, count, 255, diff_path)) => {
+                let diff = Bitmap::from_file(diff_path).unwrap();
+                assert_eq!(count, diff.get_pixels().iter().filter(|pixel| **pixel == Color::RED).count());
+            }
+            result => panic!("Expected a mismatch, got {:?}", result),
+        }
+
+        // Enough tolerance lets it pass and removes the difference image
+        snapshot.set_tolerance(255);
+        snapshot.check("moved", gen_root(0.1)).unwrap();
+        assert!(!snapshot.get_diff_path("moved").exists());
+
+        // A different window size never matches
+        let other = Snapshot::new(&dir, 20, 30);
+        assert!(matches!(other.check("moved", gen_root(0.0)), Err(SnapshotError::Size((40, 30), (20, 30)))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn layout_error() {
+        // A reference which breaks after the view was added cannot be laid out
+        let root = gen_root(0.0);
+        let scheduler = root.get_children_scheduler();
+        let broken = View::new(gen_set_update_info(0.0, 0.5), Some(Rc::clone(&scheduler)));
+        let anchor = extent::PositionType::Anchor(extent::AnchorPoint { ref_view: extent::RefView::Id(5), ref_point: 1.0 });
+        let controller = broken.get_extent_controller();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(broken)).unwrap();
+        controller.borrow_mut().set_update_info(gen_update_info(anchor, extent::SizeType::Set(0.5)));
+
+        let snapshot = Snapshot::new(std::env::temp_dir(), 40, 30);
+        assert!(matches!(snapshot.render(root), Err(SnapshotError::Layout(view::UpdateError::InvalidReference(path, _))) if path == vec![2]));
+    }
+}
@@ -0,0 +1,302 @@
+use crate::view::{View, style};
+use serde::{Serialize, Deserialize};
+
+/// Maps selectors to style properties, the views of a tree are restyled from the theme when it is switched or when their tag, classes, state or overrides change
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    /// The properties the root view inherits, only inheritable properties such as the text color have an effect
+    #[serde(default)]
+    pub root: style::StyleProperties,
+    /// The rules in order of declaration, with the same specificity later rules win
+    #[serde(default)]
+    pub rules: Vec<StyleRule>,
+}
+
+/// A single rule of a theme
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StyleRule {
+    /// Decides which views the rule applies to
+    #[serde(default)]
+    pub selector: Selector,
+    /// The properties to set for the views
+    #[serde(default)]
+    pub properties: style::StyleProperties,
+}
+
+/// Selects views by tag, classes and interaction state, a view must match everything in the selector.
+/// The empty selector matches all views
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Selector {
+    /// The tag the view must have, None for any tag
+    pub tag: Option<String>,
+    /// The classes the view must all have
+    pub classes: Vec<String>,
+    /// The interaction states the view must all be in
+    pub state: style::State,
+}
+
+impl Theme {
+    /// Parses a theme from a string
+    /// 
+    /// # Parameters
+    /// 
+    /// source: The contents of the theme file
+    pub fn parse(source: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    /// Finds the style for a view, the inheritable properties come from the parent and the overrides of the view are applied last
+    /// 
+    /// # Parameters
+    /// 
+    /// controller: The style controller of the view
+    /// 
+    /// parent: The style of the parent, the default style for the root
+    pub fn resolve(&self, controller: &style::StyleController, parent: &style::Style) -> style::Style {
+        // Sort the matching rules by specificity, the sort is stable so the declaration order is kept for equal specificity
+        let mut matching: Vec<&StyleRule> = self.rules.iter().filter(|rule| rule.selector.matches(controller)).collect();
+        matching.sort_by_key(|rule| rule.selector.specificity());
+
+        let mut style = parent.inherited();
+        for rule in matching {
+            rule.properties.apply_to(&mut style);
+        }
+        controller.get_overrides().apply_to(&mut style);
+
+        style
+    }
+
+    /// Restyles the view and all of its resolved children in a single pass, only views whose style changes are marked dirty
+    /// 
+    /// # Parameters
+    /// 
+    /// root: The view to start from, it is styled as the root
+    pub fn apply(&self, root: &View) {
+        self.apply_tree(root, &self.get_base());
+    }
+
+    /// Restyles only the resolved views which need it together with their children, the other views keep their style.
+    /// A view needs it once its tag, classes, state or overrides changed or its style was replaced
+    /// 
+    /// # Parameters
+    /// 
+    /// root: The view to start from, it is styled as the root
+    pub fn apply_changed(&self, root: &View) {
+        self.apply_changed_tree(root, &self.get_base());
+    }
+
+    /// Gets the style the root inherits from
+    fn get_base(&self) -> style::Style {
+        let mut base = style::Style::default();
+        self.root.apply_to(&mut base);
+
+        base
+    }
+
+    /// Restyles a view and all of its children
+    /// 
+    /// # Parameters
+    /// 
+    /// view: The view to restyle
+    /// 
+    /// parent: The style of the parent
+    fn apply_tree(&self, view: &View, parent: &style::Style) {
+        let controller = view.get_style_controller();
+        let style = self.resolve(&controller.borrow(), parent);
+        controller.borrow_mut().set_themed_style(style);
+
+        // The children inherit what is shown, including the animated properties
+        let style = controller.borrow().get_style();
+        for child in view.get_children() {
+            self.apply_tree(child, &style);
+        }
+    }
+
+    /// Restyles the views which need it together with their children
+    /// 
+    /// # Parameters
+    /// 
+    /// view: The view to check
+    /// 
+    /// parent: The style of the parent
+    fn apply_changed_tree(&self, view: &View, parent: &style::Style) {
+        if view.get_style_controller().borrow().needs_restyle() {
+            return self.apply_tree(view, parent);
+        }
+
+        let style = view.get_style();
+        for child in view.get_children() {
+            self.apply_changed_tree(child, &style);
+        }
+    }
+}
+
+impl Selector {
+    /// Checks if a view matches the selector
+    /// 
+    /// # Parameters
+    /// 
+    /// controller: The style controller of the view
+    pub fn matches(&self, controller: &style::StyleController) -> bool {
+        let tag_matches = match &self.tag {
+            Some(tag) => controller.get_tag() == Some(tag.as_str()),
+            None => true,
+        };
+
+        tag_matches && self.classes.iter().all(|class| controller.has_class(class)) && controller.get_state().contains(self.state)
+    }
+
+    /// Gets the specificity of the selector, rules with a higher specificity win.
+    /// Each class and state counts more than a tag
+    pub fn specificity(&self) -> usize {
+        let tag = if self.tag.is_some() { 1 } else { 0 };
+
+        tag + 10 * (self.classes.len() + self.state.bits().count_ones() as usize)
+    }
+}
+
+/// A container for the theme used by an event loop, it can be changed while the event loop runs
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ThemeController {
+    /// The current theme, None to leave the styles of the views alone
+    theme: Option<Theme>,
+    /// True if the theme was switched since the last update
+    changed: bool,
+}
+
+impl ThemeController {
+    /// Gets the current theme
+    pub fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    /// Switches to another theme, the entire tree is restyled during the next update
+    /// 
+    /// # Parameters
+    /// 
+    /// theme: The new theme, None to stop styling the views
+    pub fn set_theme(&mut self, theme: Option<Theme>) {
+        self.theme = theme;
+        self.changed = true;
+    }
+
+    /// Checks if the theme was switched since the last check and resets the check
+    pub(crate) fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::gen_set_update_info;
+    use crate::{render::Color, view::children, event_loop::{EventLoop, Headless}};
+    use std::rc::Rc;
+
+    fn gen_theme(background: Color, text_color: Color) -> Theme {
+        let root = style::StyleProperties { text_color: Some(text_color), ..Default::default() };
+        let rules = vec![
+            StyleRule { selector: Selector::default(), properties: style::StyleProperties { corner_radius: Some(1.0), ..Default::default() } },
+            StyleRule { selector: Selector { tag: Some("button".to_string()), ..Default::default() }, properties: style::StyleProperties { background: Some(background), border_width: Some(1.0), ..Default::default() } },
+            StyleRule { selector: Selector { tag: Some("button".to_string()), state: style::State::HOVERED, ..Default::default() }, properties: style::StyleProperties { border_width: Some(2.0), ..Default::default() } },
+            StyleRule { selector: Selector { classes: vec!["danger".to_string()], ..Default::default() }, properties: style::StyleProperties { background: Some(Color::RED), ..Default::default() } },
+        ];
+
+        Theme { root, rules }
+    }
+
+    #[test]
+    fn parse() {
+        let source = "(rules: [(selector: (tag: Some(\"button\"), classes: [\"primary\"], state: (\"HOVERED | PRESSED\")), properties: (background: Some((r: 0, g: 0, b: 255, a: 255))))])";
+        let theme = Theme::parse(source).unwrap();
+        assert_eq!(style::State::HOVERED | style::State::PRESSED, theme.rules[0].selector.state);
+        assert_eq!(Some(Color::BLUE), theme.rules[0].properties.background);
+        assert_eq!(None, theme.rules[0].properties.opacity);
+        assert!(Theme::parse("(rules: [(selector: (tag: 1))])").is_err());
+    }
+
+    #[test]
+    fn resolve() {
+        let theme = gen_theme(Color::BLUE, Color::WHITE);
+        let parent = style::Style { text_color: Color::GREEN, border_width: 5.0, ..style::Style::default() };
+
+        // Only the rule without a selector matches, the border is not inherited
+        let mut controller = style::StyleController::default();
+        assert_eq!(style::Style { text_color: Color::GREEN, corner_radius: 1.0, ..style::Style::default() }, theme.resolve(&controller, &parent));
+
+        // The state and the class are more specific than the tag
+        controller.set_tag(Some("button".to_string()));
+        controller.set_state(style::State::HOVERED, true);
+        controller.add_class("danger");
+        let style = theme.resolve(&controller, &parent);
+        assert_eq!(Some(Color::RED), style.background);
+        assert_eq!(2.0, style.border_width);
+
+        // The overrides win over everything
+        controller.set_overrides(style::StyleProperties { border_width: Some(3.0), ..Default::default() });
+        assert_eq!(3.0, theme.resolve(&controller, &parent).border_width);
+
+        // Without any matching rules inheritable properties come from the parent
+        assert_eq!(Color::GREEN, Theme::default().resolve(&style::StyleController::default(), &parent).text_color);
+    }
+
+    #[test]
+    fn apply() {
+        // A root with a button containing a plain view
+        let root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        let button = View::new(gen_set_update_info(0.0, 1.0), Some(Rc::clone(&scheduler)));
+        button.get_style_controller().borrow_mut().set_tag(Some("button".to_string()));
+        button.get_style_controller().borrow_mut().set_overrides(style::StyleProperties { text_color: Some(Color::GREEN), ..Default::default() });
+        let label = View::new(gen_set_update_info(0.0, 1.0), Some(button.get_children_scheduler()));
+        button.get_children_scheduler().borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(label)).unwrap();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(button)).unwrap();
+
+        let mut event_loop = EventLoop::from_backend(*root, Headless::<()>::new(10, 10)).unwrap();
+        let theme_controller = event_loop.get_theme_controller();
+        theme_controller.borrow_mut().set_theme(Some(gen_theme(Color::WHITE, Color::BLACK)));
+        event_loop.run_frame(&mut |_, _, _| ());
+        let button = &event_loop.get_root().get_children()[0];
+        assert_eq!(Some(Color::WHITE), button.get_style().background);
+        assert_eq!(Color::GREEN, button.get_children()[0].get_style().text_color);
+        assert_eq!(None, button.get_children()[0].get_style().background);
+
+        // Switching the theme restyles everything during the next frame
+        theme_controller.borrow_mut().set_theme(Some(gen_theme(Color::BLACK, Color::WHITE)));
+        event_loop.get_root().get_children()[0].get_style_controller().borrow_mut().set_overrides(style::StyleProperties::default());
+        event_loop.run_frame(&mut |_, _, _| ());
+        let button = &event_loop.get_root().get_children()[0];
+        assert_eq!(Some(Color::BLACK), button.get_style().background);
+        assert_eq!(Color::WHITE, button.get_children()[0].get_style().text_color);
+        assert_eq!(Color::WHITE, event_loop.get_root().get_style().text_color);
+    }
+
+    #[test]
+    fn apply_changed() {
+        // A root with two plain views
+        let mut root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        for _ in 0..2 {
+            let child = View::new(gen_set_update_info(0.0, 1.0), Some(Rc::clone(&scheduler)));
+            scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+        }
+        root.resolve_children();
+        gen_theme(Color::WHITE, Color::BLACK).apply(&root);
+        root.update((10.0, 10.0), std::time::Instant::now(), &mut crate::render::Damage::new()).unwrap();
+
+        // Only the view whose classes changed is styled by the other theme
+        let other = gen_theme(Color::WHITE, Color::GREEN);
+        root.get_children()[1].get_style_controller().borrow_mut().add_class("danger");
+        other.apply_changed(&root);
+        assert_eq!(Color::BLACK, root.get_children()[0].get_style().text_color);
+        assert_eq!(Some(Color::RED), root.get_children()[1].get_style().background);
+
+        // A replaced style is restyled as well
+        root.update((10.0, 10.0), std::time::Instant::now(), &mut crate::render::Damage::new()).unwrap();
+        root.get_children()[0].get_style_controller().borrow_mut().set_style(style::Style { opacity: 0.5, ..style::Style::default() });
+        other.apply_changed(&root);
+        assert_eq!(1.0, root.get_children()[0].get_style().opacity);
+        assert_eq!(Color::BLACK, root.get_children()[0].get_style().text_color);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::view::View;
+use std::{cell::RefCell, rc::{Rc, Weak}, fmt, time::{Duration, Instant}};
+
+/// The function run when a timer fires
+type Callback = Rc<RefCell<Box<dyn FnMut()>>>;
+
+/// A callback which runs once after a delay or repeatedly at an interval, it is started at the end of the frame it is added in or at the start of the next frame if it was added between frames
+pub struct Timer {
+    /// The time from the start until the first run
+    delay: Duration,
+    /// The time between runs after the first one, None to only run once
+    interval: Option<Duration>,
+    /// The token of the view which owns the timer, the timer is cancelled once the view is deleted
+    owner: Option<Weak<()>>,
+    /// The function to run
+    callback: Callback,
+}
+
+impl Timer {
+    /// Creates a timer which runs once
+    ///
+    /// # Parameters
+    ///
+    /// delay: The time from the start until it runs
+    ///
+    /// callback: The function to run
+    pub fn after<F: FnMut() + 'static>(delay: Duration, callback: F) -> Self {
+        Self { delay, interval: None, owner: None, callback: Rc::new(RefCell::new(Box::new(callback))) }
+    }
+
+    /// Creates a timer which runs repeatedly until it is cancelled, runs missed because the loop was busy are skipped.
+    /// An interval of 0 runs the callback every frame
+    ///
+    /// # Parameters
+    ///
+    /// interval: The time from the start until the first run and between runs
+    ///
+    /// callback: The function to run
+    pub fn every<F: FnMut() + 'static>(interval: Duration, callback: F) -> Self {
+        Self { delay: interval, interval: Some(interval), owner: None, callback: Rc::new(RefCell::new(Box::new(callback))) }
+    }
+
+    /// Ties the timer to a view, it is cancelled without running once the view is deleted
+    ///
+    /// # Parameters
+    ///
+    /// view: The view which owns the timer
+    pub fn set_owner(&mut self, view: &View) {
+        self.owner = Some(view.get_owner());
+    }
+
+    /// Checks if the timer was tied to a view which has since been deleted
+    fn is_orphaned(&self) -> bool {
+        self.owner.as_ref().is_some_and(|owner| owner.strong_count() == 0)
+    }
+}
+
+impl fmt::Debug for Timer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timer")
+            .field("delay", &self.delay)
+            .field("interval", &self.interval)
+            .field("owned", &self.owner.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Identifies a timer added to a timer controller
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimerId(usize);
+
+/// Holds all the running timers of an event loop, due timers run at the start of a frame before the views are updated
+#[derive(Debug, Default)]
+pub struct TimerController {
+    /// The running timers with the time they run next, None if they have not been started yet
+    timers: Vec<(TimerId, Timer, Option<Instant>)>,
+    /// The id given to the next timer
+    next_id: usize,
+}
+
+impl TimerController {
+    /// Adds a timer, it is started at the end of the current frame or at the start of the next one
+    ///
+    /// # Parameters
+    ///
+    /// timer: The timer to run
+    pub fn add(&mut self, timer: Timer) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.push((id, timer, None));
+
+        id
+    }
+
+    /// Stops a timer, this can be done from any callback including its own.
+    /// Returns false if the timer already ran or was cancelled
+    ///
+    /// # Parameters
+    ///
+    /// id: The timer to stop
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|(own, _, _)| *own != id);
+
+        self.timers.len() != len
+    }
+
+    /// Checks if a timer will still run
+    ///
+    /// # Parameters
+    ///
+    /// id: The timer to check
+    pub fn is_running(&self, id: TimerId) -> bool {
+        self.timers.iter().any(|(own, _, _)| *own == id)
+    }
+
+    /// Checks if no timers are running
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    /// Gets the earliest time a started timer runs, None if no timer is started
+    pub fn get_next_deadline(&self) -> Option<Instant> {
+        self.timers.iter().filter_map(|(_, _, deadline)| *deadline).min()
+    }
+
+    /// Starts all the timers added since the last start and removes the timers of deleted views
+    ///
+    /// # Parameters
+    ///
+    /// time: The time the timers start at
+    pub(crate) fn start(&mut self, time: Instant) {
+        self.timers.retain(|(_, timer, _)| !timer.is_orphaned());
+        for (_, timer, deadline) in self.timers.iter_mut() {
+            deadline.get_or_insert(time + timer.delay);
+        }
+    }
+
+    /// Gets the callbacks of all the timers which are due in the order of their deadlines
+    ///
+    /// # Parameters
+    ///
+    /// time: The current time
+    fn get_due(&mut self, time: Instant) -> Vec<(TimerId, Callback)> {
+        self.start(time);
+
+        let mut due: Vec<(Instant, TimerId, Callback)> = self.timers.iter()
+            .filter_map(|(id, timer, deadline)| deadline.filter(|deadline| *deadline <= time).map(|deadline| (deadline, *id, Rc::clone(&timer.callback))))
+            .collect();
+        due.sort_by_key(|(deadline, _, _)| *deadline);
+
+        due.into_iter().map(|(_, id, callback)| (id, callback)).collect()
+    }
+
+    /// Marks a due timer as run, a single run is removed and a repeating timer is scheduled again.
+    /// Returns false if the timer was cancelled
+    ///
+    /// # Parameters
+    ///
+    /// id: The timer which runs
+    ///
+    /// time: The current time
+    fn finish(&mut self, id: TimerId, time: Instant) -> bool {
+        let pos = match self.timers.iter().position(|(own, _, _)| *own == id) {
+            Some(pos) => pos,
+            None => return false,
+        };
+
+        // Runs missed while the loop was busy are skipped
+        let (_, timer, deadline) = &mut self.timers[pos];
+        match (timer.interval, *deadline) {
+            (Some(interval), Some(old)) => {
+                let next = old + interval;
+                *deadline = Some(if next <= time { time + interval } else { next });
+            }
+            _ => {
+                self.timers.remove(pos);
+            }
+        }
+
+        true
+    }
+}
+
+/// Runs all the timers of a controller which are due, the callbacks may add and cancel timers.
+/// A timer cancelled by an earlier callback does not run
+///
+/// # Parameters
+///
+/// controller: The controller of the timers, it must not be borrowed
+///
+/// time: The current time
+pub(crate) fn run_due(controller: &RefCell<TimerController>, time: Instant) {
+    let due = controller.borrow_mut().get_due(time);
+    for (id, callback) in due {
+        if controller.borrow_mut().finish(id, time) {
+            (callback.borrow_mut())();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::gen_set_update_info;
+    use crate::view::children;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn after() {
+        let controller = RefCell::new(TimerController::default());
+        let runs = Rc::new(RefCell::new(Vec::new()));
+        let (first_runs, second_runs) = (Rc::clone(&runs), Rc::clone(&runs));
+        let first = controller.borrow_mut().add(Timer::after(millis(100), move || first_runs.borrow_mut().push(1)));
+        let second = controller.borrow_mut().add(Timer::after(millis(50), move || second_runs.borrow_mut().push(2)));
+        assert_eq!(None, controller.borrow().get_next_deadline());
+
+        // The timers start at the end of the frame and run in the order of their deadlines
+        let start = Instant::now();
+        controller.borrow_mut().start(start);
+        assert_eq!(Some(start + millis(50)), controller.borrow().get_next_deadline());
+        run_due(&controller, start + millis(40));
+        assert!(runs.borrow().is_empty());
+        run_due(&controller, start + millis(200));
+        assert_eq!(vec![2, 1], *runs.borrow());
+        assert!(!controller.borrow().is_running(first) && !controller.borrow().is_running(second));
+        assert!(controller.borrow().is_empty());
+    }
+
+    #[test]
+    fn every() {
+        let controller = Rc::new(RefCell::new(TimerController::default()));
+        let runs = Rc::new(RefCell::new(0));
+        let counter = Rc::clone(&runs);
+        let id = controller.borrow_mut().add(Timer::every(millis(100), move || *counter.borrow_mut() += 1));
+
+        let start = Instant::now();
+        controller.borrow_mut().start(start);
+        run_due(&controller, start + millis(100));
+        run_due(&controller, start + millis(150));
+        assert_eq!(1, *runs.borrow());
+        assert_eq!(Some(start + millis(200)), controller.borrow().get_next_deadline());
+
+        // Missed runs are skipped instead of running all at once
+        run_due(&controller, start + millis(550));
+        assert_eq!(2, *runs.borrow());
+        assert_eq!(Some(start + millis(650)), controller.borrow().get_next_deadline());
+
+        // A callback can cancel a timer due in the same frame
+        let cancelling = Rc::clone(&controller);
+        controller.borrow_mut().add(Timer::after(Duration::ZERO, move || assert!(cancelling.borrow_mut().cancel(id))));
+        controller.borrow_mut().start(start + millis(600));
+        run_due(&controller, start + millis(700));
+        assert_eq!(2, *runs.borrow());
+        assert!(controller.borrow().is_empty());
+    }
+
+    #[test]
+    fn owner() {
+        let root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        let child = View::new(gen_set_update_info(0.0, 0.5), Some(Rc::clone(&scheduler)));
+        let controller = RefCell::new(TimerController::default());
+        let mut timer = Timer::after(Duration::ZERO, || panic!("The timer of a deleted view ran"));
+        timer.set_owner(&child);
+        let id = controller.borrow_mut().add(timer);
+        let style = child.get_style_controller();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+
+        // Deleting the view through the scheduler cancels the timer even while its controllers are still held
+        let mut root = root;
+        root.resolve_children();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(0)).unwrap();
+        root.resolve_children();
+        run_due(&controller, Instant::now());
+        assert!(!controller.borrow().is_running(id));
+        assert_eq!(1, Rc::strong_count(&style));
+    }
+}
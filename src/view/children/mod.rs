@@ -3,15 +3,18 @@ mod scheduler;
 use super::View;
 use std::{cell::RefCell, rc::Rc};
 
-pub use scheduler::{ChildrenScheduler, ValidateError};
+pub use scheduler::{ChildrenScheduler, ChildrenScheduleOperation, ValidateError};
 
 /// All data related to children including the list and the sheduler for changing the list
 #[derive(Clone, Debug)]
 pub(super) struct Children {
     /// The list of current children
+    #[allow(clippy::vec_box)]
     list: Vec<Box<View>>,
     /// The sheduler for the list
     scheduler: Rc<RefCell<scheduler::ChildrenScheduler>>,
+    /// True if the list has changed since the last time it was checked
+    changed: bool,
 }
 
 impl Children {
@@ -24,7 +27,22 @@ impl Children {
         let list = Vec::new();
         let scheduler = Rc::new(RefCell::new(scheduler::ChildrenScheduler::new(parent_scheduler)));
 
-        Self { list, scheduler }
+        let changed = false;
+
+        Self { list, scheduler, changed }
+    }
+
+    /// Creates a children struct from a list of views, the list gets a new scheduler without a parent
+    /// 
+    /// # Parameters
+    /// 
+    /// list: The views to use as the current children
+    #[allow(clippy::vec_box)]
+    pub(super) fn from_list(list: Vec<Box<View>>) -> Self {
+        let scheduler = Rc::new(RefCell::new(scheduler::ChildrenScheduler::new(None)));
+        let changed = false;
+
+        Self { list, scheduler, changed }
     }
 
     /// Gets an instance of the children scheduler
@@ -32,8 +50,30 @@ impl Children {
         Rc::clone(&self.scheduler)
     }
     
+    /// Gets the list of current children
+    pub(super) fn get_list(&self) -> &[Box<View>] {
+        &self.list
+    }
+
+    /// Gets the list of current children as mutable
+    pub(super) fn get_list_mut(&mut self) -> &mut [Box<View>] {
+        &mut self.list
+    }
+
     /// Resolves all the scheduled changes
     pub(super) fn resolve(&mut self) {
-        self.scheduler.borrow_mut().resolve(&mut self.list);
+        if self.scheduler.borrow_mut().resolve(&mut self.list) {
+            self.changed = true;
+        }
+    }
+
+    /// Checks if there are scheduled changes for these children or any of their descendants, or if the list changed since the last check
+    pub(super) fn is_pending(&self) -> bool {
+        self.changed || self.scheduler.borrow().has_operations()
+    }
+
+    /// Checks if the list has changed since the last check and resets the check
+    pub(super) fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
     }
 }
\ No newline at end of file
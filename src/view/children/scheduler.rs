@@ -24,7 +24,7 @@ impl ChildrenScheduler {
     /// operation: The operation to push
     pub fn push_operation(&mut self, operation: ChildrenScheduleOperation) -> Result<(), ValidateError> {
         // Make sure the operation is valid
-        operation.validate(&self.children_extent_controllers)?;
+        self.check_operation(&operation)?;
 
         // Update extents
         operation.update(&mut self.children_extent_controllers);
@@ -40,6 +40,71 @@ impl ChildrenScheduler {
         Ok(())
     }
 
+    /// Checks if an operation would be accepted after all the operations already on the queue without pushing it
+    /// 
+    /// # Parameters
+    /// 
+    /// operation: The operation to check
+    pub fn check_operation(&self, operation: &ChildrenScheduleOperation) -> Result<(), ValidateError> {
+        operation.validate(&self.children_extent_controllers)
+    }
+
+    /// Replaces the update info of a child after checking that it only references existing older siblings.
+    /// If the child has a motion it moves from where it is shown to its new extent starting with the next update.
+    /// The position is counted after all the operations already on the queue
+    /// 
+    /// # Parameters
+    /// 
+    /// pos: The position of the child
+    /// 
+    /// update_info: The new information on how to update the extent of the child
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use std::time::Duration;
+    /// use views::{view::{View, extent, children::{ChildrenScheduleOperation, ValidateError}}, animation::{Motion, Transition, Easing}, event_loop::{EventLoop, Headless}};
+    /// 
+    /// let place = |pos| {
+    ///     let locate = extent::ExtentLocate { pos: extent::PositionType::Set(pos), size: extent::SizeType::Set(0.5) };
+    ///     let single = extent::ExtentUpdateSingle { extent_type: extent::ExtentUpdateType::Locate(locate), scale_rel: 1.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0 };
+    ///     extent::ExtentUpdate { x: single, y: single }
+    /// };
+    /// 
+    /// let root = View::new_root();
+    /// let scheduler = root.get_children_scheduler();
+    /// let child = View::new(place(0.0), Some(scheduler.clone()));
+    /// let controller = child.get_extent_controller();
+    /// controller.borrow_mut().set_motion(Some(Motion::Transition(Transition::new(Duration::from_millis(100), Easing::Linear))));
+    /// scheduler.borrow_mut().push_operation(ChildrenScheduleOperation::Push(child)).unwrap();
+    /// let mut event_loop = EventLoop::from_backend(*root, Headless::<()>::new(10, 10)).unwrap();
+    /// event_loop.run_frame(&mut |_, _, _| ());
+    /// 
+    /// // The first child has no older sibling to reference
+    /// let mut anchored = place(0.0);
+    /// anchored.x.extent_type = extent::ExtentUpdateType::Locate(extent::ExtentLocate { pos: extent::PositionType::Anchor(extent::AnchorPoint { ref_view: extent::RefView::Prev, ref_point: 1.0 }), size: extent::SizeType::Set(0.5) });
+    /// assert!(matches!(scheduler.borrow_mut().set_update_info(0, anchored), Err(ValidateError::InvalidNew(_))));
+    /// assert!(matches!(scheduler.borrow_mut().set_update_info(1, place(0.5)), Err(ValidateError::InvalidPos(1, 1))));
+    /// 
+    /// // A valid change starts the transition
+    /// scheduler.borrow_mut().set_update_info(0, place(0.5)).unwrap();
+    /// event_loop.run_frame(&mut |_, _, _| ());
+    /// assert!(controller.borrow().is_animating());
+    /// ```
+    pub fn set_update_info(&mut self, pos: usize, update_info: extent::ExtentUpdate) -> Result<(), ValidateError> {
+        // Make sure the position is valid
+        let controller = match self.children_extent_controllers.get(pos) {
+            Some(controller) => controller,
+            None => return Err(ValidateError::InvalidPos(pos, self.children_extent_controllers.len())),
+        };
+
+        // Only older siblings may be referenced, the same as for a new view at this position
+        update_info.validate(&self.children_extent_controllers[..pos])?;
+        controller.borrow_mut().set_update_info(update_info);
+
+        Ok(())
+    }
+
     /// Creates a new children scheduler
     /// 
     /// # Parameters
@@ -52,9 +117,16 @@ impl ChildrenScheduler {
         Self { queue, flags, parent_scheduler, children_extent_controllers: Vec::new() }
     }
 
-    // resolves all the operations and clears the queue
-    pub(super) fn resolve(&mut self, children: &mut Vec<Box<View>>) {
+    /// Checks if there are operations on the queue or on the queue of any descendant
+    pub(crate) fn has_operations(&self) -> bool {
+        !self.queue.is_empty() || self.flags.contains(ChildrenCheduleFlags::CHILDREN_QUEUE_ITEM)
+    }
+
+    // resolves all the operations and clears the queue, returns true if the list of children changed
+    #[allow(clippy::vec_box)]
+    pub(super) fn resolve(&mut self, children: &mut Vec<Box<View>>) -> bool {
         // Resolve all the operations
+        let changed = !self.queue.is_empty();
         for operation in self.queue.drain(..) {
             operation.resolve(children);
         }
@@ -68,6 +140,8 @@ impl ChildrenScheduler {
             // Clear the flag
             self.flags.remove(ChildrenCheduleFlags::CHILDREN_QUEUE_ITEM)
         }
+
+        changed
     }
     
     // Adds the flag to tell one of the children has an operation
@@ -101,6 +175,7 @@ pub enum ChildrenScheduleOperation {
 
 impl ChildrenScheduleOperation {
     /// Resolves the operation
+    #[allow(clippy::vec_box)]
     fn resolve(self, children: &mut Vec<Box<View>>) {
         match self {
             // Push the view onto the end
@@ -123,7 +198,7 @@ impl ChildrenScheduleOperation {
     }
 
     /// Validates the operation
-    fn validate(&self, children_extent: &Vec<Rc<RefCell<extent::ExtentController>>>) -> Result<(), ValidateError> {
+    fn validate(&self, children_extent: &[Rc<RefCell<extent::ExtentController>>]) -> Result<(), ValidateError> {
         match &self {
             // Just validate the view itself
             Self::Push(view) => view.validate(children_extent)?,
@@ -161,7 +236,7 @@ impl ChildrenScheduleOperation {
                     if *from == 0 && children_extent[1].borrow().check_prev() {
                         return Err(ValidateError::NoPrev(1));
                     }
-                    for (pos, sibling) in children_extent[*from + 1..*to].iter().enumerate() {
+                    for (pos, sibling) in children_extent[*from + 1..=*to].iter().enumerate() {
                         if sibling.borrow().check_id(*from) {
                             return Err(ValidateError::InvalidId(pos + *from + 1));
                         }
@@ -188,6 +263,19 @@ impl ChildrenScheduleOperation {
         Ok(())
     }
 
+    /// Validates the operation and applies it to a list of extent controllers as if it had been pushed, this checks a series of operations before any of them is pushed.
+    /// The controllers in the list and the one of an inserted view are changed so they must not belong to live views
+    ///
+    /// # Parameters
+    ///
+    /// children_extent: The stand-in extent controllers of the children
+    pub(crate) fn simulate(&self, children_extent: &mut Vec<Rc<RefCell<extent::ExtentController>>>) -> Result<(), ValidateError> {
+        self.validate(children_extent)?;
+        self.update(children_extent);
+
+        Ok(())
+    }
+
     /// Update the extents
     fn update(&self, children_extent: &mut Vec<Rc<RefCell<extent::ExtentController>>>) {
         match &self {
@@ -255,10 +343,53 @@ pub enum ValidateError {
     InvalidId(usize),
     #[error("The new view cannot be inserted because it is invalid: {:?}", .0)]
     InvalidNew(extent::ValidateError),
+    #[error("The new view cannot be inserted because its descendant at {:?} is invalid: {:?}", .0, .1)]
+    InvalidDescendant(Vec<usize>, extent::ValidateError),
 }
 
 impl From<extent::ValidateError> for ValidateError {
     fn from(err: extent::ValidateError) -> ValidateError {
         ValidateError::InvalidNew(err)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::gen_update_info;
+
+    #[test]
+    fn move_forward() {
+        let root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        let anchor = extent::PositionType::Anchor(extent::AnchorPoint { ref_view: extent::RefView::Id(0), ref_point: 1.0 });
+        for pos in [extent::PositionType::Set(0.0), extent::PositionType::Set(0.5), anchor] {
+            let child = View::new(gen_update_info(pos, extent::SizeType::Set(0.25)), Some(Rc::clone(&scheduler)));
+            scheduler.borrow_mut().push_operation(ChildrenScheduleOperation::Push(child)).unwrap();
+        }
+
+        // The view which lands right in front of the moved view must not reference it
+        assert!(matches!(scheduler.borrow().check_operation(&ChildrenScheduleOperation::Move(0, 2)), Err(ValidateError::InvalidId(2))));
+        assert!(scheduler.borrow().check_operation(&ChildrenScheduleOperation::Move(0, 1)).is_ok());
+        assert!(scheduler.borrow().check_operation(&ChildrenScheduleOperation::Move(1, 2)).is_ok());
+    }
+
+    #[test]
+    fn set_update_info() {
+        let root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        for pos in [0.0, 0.5] {
+            let child = View::new(gen_update_info(extent::PositionType::Set(pos), extent::SizeType::Set(0.25)), Some(Rc::clone(&scheduler)));
+            scheduler.borrow_mut().push_operation(ChildrenScheduleOperation::Push(child)).unwrap();
+        }
+
+        // A child may only reference queued siblings in front of it
+        let anchor = |id| gen_update_info(extent::PositionType::Anchor(extent::AnchorPoint { ref_view: extent::RefView::Id(id), ref_point: 1.0 }), extent::SizeType::Set(0.25));
+        assert!(matches!(scheduler.borrow_mut().set_update_info(1, anchor(1)), Err(ValidateError::InvalidNew(_))));
+        assert!(matches!(scheduler.borrow_mut().set_update_info(2, anchor(0)), Err(ValidateError::InvalidPos(2, 2))));
+        scheduler.borrow_mut().set_update_info(1, anchor(0)).unwrap();
+        assert_eq!(anchor(0), scheduler.borrow().children_extent_controllers[1].borrow().get_update_info());
+
+        // The reference now blocks moving the referenced view behind it
+        assert!(matches!(scheduler.borrow().check_operation(&ChildrenScheduleOperation::Move(0, 1)), Err(ValidateError::InvalidId(1))));
+    }
+}
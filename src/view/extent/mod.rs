@@ -1,16 +1,25 @@
 mod update;
-use std::{cell::{RefCell, Ref}, rc::Rc, ops::Range};
+use std::{cell::{RefCell, Ref}, rc::Rc, ops::Range, time::Instant};
 
 use super::View;
+use crate::{render::Drawable, animation};
 
-pub use update::{ExtentUpdate, ExtentUpdateSingle, ExtentUpdateType, ExtentRatio, ExtentLocate, SizeType, ExtentStretch, PositionType, AnchorPoint, RefView};
-pub use update::ValidateError;
+pub use update::{ExtentUpdate, ExtentUpdateSingle, ExtentUpdateType, ExtentRatio, RatioSource, ExtentLocate, SizeType, ExtentStretch, PositionType, AnchorPoint, RefView, Dim};
+pub use update::{ValidateError, ValidateWarning, Diagnostic, DiagnosticKind, DiagnosticReport};
 
 /// A container for the extent update info
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ExtentController {
     /// The update info
     update_info: update::ExtentUpdate,
+    /// How the extent moves when the update info changes, None to jump to the new extent
+    motion: Option<animation::Motion>,
+    /// The running animation of the extent, None if the extent is not animating
+    animation: Option<ExtentAnimation>,
+    /// The speed as (x, y, w, h) per second handed off to the next update, None if there is none
+    handoff: Option<(f32, f32, f32, f32)>,
+    /// True if the update info was last changed by a timeline, it then changes every frame and must not start a transition
+    animated: bool,
 }
 
 impl ExtentController {
@@ -19,8 +28,78 @@ impl ExtentController {
     /// # Parameters
     /// 
     /// update_info: The information on how to update the extent
-    fn new(update_info: update::ExtentUpdate) -> Self {
-        Self { update_info }
+    pub(crate) fn new(update_info: update::ExtentUpdate) -> Self {
+        Self { update_info, motion: None, animation: None, handoff: None, animated: false }
+    }
+
+    /// Gets a copy of the current update info
+    pub fn get_update_info(&self) -> update::ExtentUpdate {
+        self.update_info
+    }
+
+    /// Replaces the update info without validating it, the caller must make sure it is valid for the current siblings.
+    /// Outside of the crate it is changed with ChildrenScheduler::set_update_info of the parent, which validates it
+    /// 
+    /// # Parameters
+    /// 
+    /// update_info: The new information on how to update the extent
+    pub(crate) fn set_update_info(&mut self, update_info: update::ExtentUpdate) {
+        self.update_info = update_info;
+        self.animated = false;
+    }
+
+    /// Replaces the update info for a single frame of a timeline, this does not start a transition.
+    /// Only properties which never change the references may be changed
+    /// 
+    /// # Parameters
+    /// 
+    /// update_info: The new information on how to update the extent
+    pub(crate) fn set_animated_update_info(&mut self, update_info: update::ExtentUpdate) {
+        self.update_info = update_info;
+        self.animated = true;
+    }
+
+    /// Gets how the extent moves when the update info changes, None if it jumps to the new extent
+    pub fn get_motion(&self) -> Option<animation::Motion> {
+        self.motion
+    }
+
+    /// Sets how the extent moves when the update info changes, a running animation is not affected
+    /// 
+    /// # Parameters
+    /// 
+    /// motion: The motion to use for later changes, None to jump to the new extent
+    pub fn set_motion(&mut self, motion: Option<animation::Motion>) {
+        self.motion = motion;
+    }
+
+    /// Checks if the extent is moving towards the extent given by the update info
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Stops the running animation, the extent jumps to the extent given by the update info during the next update
+    pub fn cancel_animation(&mut self) {
+        self.animation = None;
+        self.handoff = None;
+    }
+
+    /// Gets the speed of the extent as (x, y, w, h) relative to the parent per second, it is only known while a spring moves the extent and 0 otherwise
+    pub fn get_velocity(&self) -> (f32, f32, f32, f32) {
+        match &self.animation {
+            Some(ExtentAnimation::Spring { values, .. }) => (values[0].get_velocity(), values[1].get_velocity(), values[2].get_velocity(), values[3].get_velocity()),
+            _ => (0.0, 0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Hands off a speed to the extent, for example the speed of a drag which just ended so the view keeps moving and settles naturally.
+    /// With a spring motion the extent moves with this speed from the next update, with any other motion it is ignored
+    /// 
+    /// # Parameters
+    /// 
+    /// velocity: The speed as (x, y, w, h) relative to the parent per second
+    pub fn set_velocity(&mut self, velocity: (f32, f32, f32, f32)) {
+        self.handoff = Some(velocity);
     }
 
     /// Checks whether the update info has any invalid references. Returns an error in case of an invalid reference.
@@ -32,13 +111,24 @@ impl ExtentController {
         self.update_info.validate(siblings)
     }
 
+    /// Finds every error and warning in the update info, unlike validate this does not stop at the first problem
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: A slice of all the previous siblings of this view
+    /// 
+    /// path: The child indices from the root to this view
+    pub(crate) fn diagnose(&self, siblings: &[Rc<RefCell<ExtentController>>], path: &[usize]) -> Vec<update::Diagnostic> {
+        self.update_info.diagnose(siblings, path)
+    }
+
     /// Checks if the range of ID's are being references
     /// 
     /// # Parameters
     /// 
     /// range: The range to check for
     pub(crate) fn check_id_range(&self, range: Range<usize>) -> bool {
-        self.update_info.check_id_range(range)
+        self.update_info.check_id_range(&range)
     }
 
     /// Checks if the ID is being referenced
@@ -89,8 +179,85 @@ impl ExtentController {
     /// # Parameters
     /// 
     /// siblings: All the older siblings
-    fn get(&self, siblings: &[Box<View>], parent_ratio: Ratio) -> (f32, f32, f32, f32) {
-        self.update_info.get(siblings, parent_ratio)
+    /// 
+    /// parent_ratio: The aspect ratio of the parent
+    /// 
+    /// content: The content of the view used for intrinsic sizes
+    fn get(&self, siblings: &[Box<View>], parent_ratio: Ratio, content: &Content) -> Result<(f32, f32, f32, f32), update::ValidateError> {
+        self.update_info.get(siblings, parent_ratio, content)
+    }
+}
+
+/// An extent moving from where it was shown towards the extent given by the update info
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ExtentAnimation {
+    /// The extent follows an easing curve
+    Eased {
+        /// The extent as (x, y, w, h) when the animation started
+        from: (f32, f32, f32, f32),
+        /// The frame time the animation started at
+        start: Instant,
+        /// How the extent moves
+        transition: animation::Transition,
+    },
+    /// The extent is pulled by springs
+    Spring {
+        /// The springs of x, y, w and h
+        values: [animation::SpringValue; 4],
+        /// The frame time the springs were last moved at
+        time: Instant,
+    },
+}
+
+impl ExtentAnimation {
+    /// Creates springs starting at an extent without any speed
+    /// 
+    /// # Parameters
+    /// 
+    /// spring: The parameters of the springs
+    /// 
+    /// from: The extent as (x, y, w, h) to start at
+    /// 
+    /// time: The current frame time
+    fn spring(spring: animation::Spring, from: (f32, f32, f32, f32), time: Instant) -> Self {
+        let values = [from.0, from.1, from.2, from.3].map(|value| animation::SpringValue::new(spring, value));
+
+        Self::Spring { values, time }
+    }
+
+    /// Gets the extent to show at a frame time, None if the animation is done
+    /// 
+    /// # Parameters
+    /// 
+    /// to: The extent given by the update info, it is resolved again every frame so the animation follows moving siblings
+    /// 
+    /// time: The frame time
+    fn get(&mut self, to: (f32, f32, f32, f32), time: Instant) -> Option<(f32, f32, f32, f32)> {
+        match self {
+            Self::Eased { from, start, transition } => {
+                let elapsed = time.saturating_duration_since(*start);
+                if transition.is_done(elapsed) {
+                    return None;
+                }
+
+                let progress = transition.get_progress(elapsed);
+                let lerp = animation::lerp;
+                Some((lerp(from.0, to.0, progress), lerp(from.1, to.1, progress), lerp(from.2, to.2, progress), lerp(from.3, to.3, progress)))
+            }
+
+            // The springs are done once all of them have settled
+            Self::Spring { values, time: last } => {
+                let elapsed = time.saturating_duration_since(*last);
+                *last = time;
+                let mut settled = true;
+                for (value, target) in values.iter_mut().zip([to.0, to.1, to.2, to.3]) {
+                    value.set_target(target);
+                    settled &= value.step(elapsed);
+                }
+
+                (!settled).then(|| (values[0].get_value(), values[1].get_value(), values[2].get_value(), values[3].get_value()))
+            }
+        }
     }
 }
 
@@ -109,6 +276,8 @@ pub struct Extent {
     ratio: Option<Ratio>,
     /// The update information
     update_info: Rc<RefCell<ExtentController>>,
+    /// The update info used during the last update, None if the extent has not been updated yet
+    last_update_info: Option<update::ExtentUpdate>,
 }
 
 impl Extent {
@@ -131,17 +300,154 @@ impl Extent {
     pub(super) fn new(update_info: update::ExtentUpdate) -> Self {
         let ratio = Ratio::new(1.0, 1.0);
         let update_info = Rc::new(RefCell::new(ExtentController::new(update_info)));
-        Self { x: 0.0, y: 0.0, w: 1.0, h: 1.0, update_info , ratio}
+        Self { x: 0.0, y: 0.0, w: 1.0, h: 1.0, update_info , ratio, last_update_info: None }
+    }
+
+    /// Copies the extent as it was last laid out with a controller of its own holding the same update info, nothing is shared with the original
+    pub(super) fn detached(&self) -> Self {
+        let update_info = Rc::new(RefCell::new(ExtentController::new(self.borrow_controller().get_update_info())));
+
+        Self { update_info, ..self.clone() }
     }
 
-    /// Updates the extent
+    /// Checks if the extent may change during the next update because the update info changed or the extent is moving
+    pub(super) fn needs_update(&self) -> bool {
+        let controller = self.update_info.borrow();
+        self.last_update_info != Some(controller.update_info) || controller.animation.is_some() || controller.handoff.is_some()
+    }
+
+    /// Gets the current extent as (x, y, w, h) relative to the parent
+    pub(super) fn get(&self) -> (f32, f32, f32, f32) {
+        (self.x, self.y, self.w, self.h)
+    }
+
+    /// Updates the extent, returns an error and keeps the old extent if a reference is missing.
+    /// If the update info changed since the last update and there is a motion the extent starts moving from where it is towards the new extent
     /// 
     /// # Parameters
     /// 
     /// siblings: All the older siblings
-    pub(super) fn update(&mut self, siblings: &[Box<View>], parent_ratio: Ratio) {
-        (self.x, self.y, self.w, self.h) = self.update_info.borrow().get(siblings, parent_ratio);
-        self.ratio = Ratio::new(self.w, self.h);
+    /// 
+    /// content: The content of the view and the size of the parent
+    /// 
+    /// time: The time of the frame being updated
+    pub(super) fn update(&mut self, siblings: &[Box<View>], content: &Content, time: Instant) -> Result<(), update::ValidateError> {
+        // A parent without any area lays out its children as if it was square, they end up without any area on the screen but stay finite
+        let parent_ratio = match Ratio::new(content.parent_size.0, content.parent_size.1) {
+            Some(ratio) => ratio,
+            None => Ratio::new(1.0, 1.0).unwrap(),
+        };
+
+        let target = self.update_info.borrow().get(siblings, parent_ratio, content)?;
+
+        // A change during an animation starts from where the extent is shown so it never jumps
+        let mut controller = self.update_info.borrow_mut();
+        let update_info = controller.get_update_info();
+        let changed = self.last_update_info.is_some_and(|last| last != update_info) && !controller.animated;
+        let handoff = controller.handoff.take();
+        self.last_update_info = Some(update_info);
+        controller.animated = false;
+        match controller.motion {
+            _ if !changed && handoff.is_none() => (),
+            Some(animation::Motion::Transition(transition)) if changed => controller.animation = Some(ExtentAnimation::Eased { from: self.get(), start: time, transition }),
+            Some(animation::Motion::Transition(_)) => (),
+
+            // A running spring keeps its speed unless a new speed is handed off
+            Some(animation::Motion::Spring(spring)) => {
+                let mut animation = match controller.animation {
+                    Some(animation @ ExtentAnimation::Spring { .. }) => animation,
+                    _ => ExtentAnimation::spring(spring, self.get(), time),
+                };
+                if let ExtentAnimation::Spring { values, .. } = &mut animation {
+                    values.iter_mut().for_each(|value| value.set_spring(spring));
+                    if let Some(velocity) = handoff {
+                        values.iter_mut().zip([velocity.0, velocity.1, velocity.2, velocity.3]).for_each(|(value, velocity)| value.set_velocity(velocity));
+                    }
+                }
+                controller.animation = Some(animation);
+            }
+            None => controller.animation = None,
+        }
+
+        (self.x, self.y, self.w, self.h) = match controller.animation.as_mut().and_then(|animation| animation.get(target, time)) {
+            Some(extent) => extent,
+            None => {
+                controller.animation = None;
+                target
+            }
+        };
+        drop(controller);
+        self.ratio = Ratio::new(self.w * parent_ratio.get_x(), self.h);
+
+        Ok(())
+    }
+}
+
+/// The information needed to measure the natural size of the content of a view during an update
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Content<'a> {
+    /// The content of the view, None if it has no content
+    drawable: Option<&'a dyn Drawable>,
+    /// The size of the area of the parent the view is placed in, in pixels
+    parent_size: (f32, f32),
+    /// The size of the area inside the padding of the view relative to the view
+    padding_size: (f32, f32),
+}
+
+impl<'a> Content<'a> {
+    /// Creates new content information
+    /// 
+    /// # Parameters
+    /// 
+    /// drawable: The content of the view, None if it has no content
+    /// 
+    /// parent_size: The size of the area of the parent the view is placed in, in pixels
+    /// 
+    /// padding_size: The size of the area inside the padding of the view relative to the view
+    pub(crate) fn new(drawable: Option<&'a dyn Drawable>, parent_size: (f32, f32), padding_size: (f32, f32)) -> Self {
+        Self { drawable, parent_size, padding_size }
+    }
+
+    /// Creates content information for a view without content in a square parent
+    #[cfg(test)]
+    pub(crate) fn empty() -> Self {
+        Self::new(None, (1.0, 1.0), (1.0, 1.0))
+    }
+
+    /// Gets the natural size of the content including the padding relative to the parent, 0 if there is no natural size.
+    /// Non-finite results are turned into 0 by the extent update
+    /// 
+    /// # Parameters
+    /// 
+    /// dim: The dimension to measure
+    /// 
+    /// other_size: The size of the view in the other dimension relative to the parent, if it is known
+    pub(crate) fn get_intrinsic(&self, dim: Dim, other_size: Option<f32>) -> f32 {
+        let drawable = match self.drawable {
+            Some(drawable) => drawable,
+            None => return 0.0,
+        };
+
+        let size = match dim {
+            // The width never wraps
+            Dim::X => drawable.measure(None).map(|(w, _)| w / self.padding_size.0 / self.parent_size.0),
+
+            // The height may depend on the width of the content
+            Dim::Y => {
+                let max_width = other_size.map(|w| w * self.parent_size.0 * self.padding_size.0);
+                drawable.measure(max_width).map(|(_, h)| h / self.padding_size.1 / self.parent_size.1)
+            }
+        };
+
+        size.unwrap_or(0.0)
+    }
+
+    /// Gets the ratio of w to h of the view on the screen which gives the area inside the padding the natural aspect ratio of the content,
+    /// None if the content has no natural aspect ratio
+    pub(crate) fn get_ratio(&self) -> Option<Ratio> {
+        let ratio = self.drawable?.aspect_ratio()?;
+
+        Ratio::new(ratio.get_x() * self.padding_size.1, self.padding_size.0)
     }
 }
 
@@ -153,7 +459,7 @@ pub struct Ratio {
 }
 
 impl Ratio {
-    /// Creates a new ratio (w / h) or None if it is invalid (w or h <= 0)
+    /// Creates a new ratio (w / h) or None if it is invalid (w or h <= 0, or the ratio is not finite)
     /// 
     /// # Parameters
     /// 
@@ -161,8 +467,9 @@ impl Ratio {
     /// 
     /// h: The height
     pub fn new(w: f32, h: f32) -> Option<Self> {
-        if w > 0.0 && h > 0.0 {
-            Some(Self { value: w / h })
+        let value = w / h;
+        if w > 0.0 && h > 0.0 && value.is_finite() && value > 0.0 {
+            Some(Self { value })
         } else {
             None
         }
@@ -196,6 +503,15 @@ mod tests {
 
             let ratio_no_h = Ratio::new(2.0, 0.0);
             assert!(ratio_no_h.is_none());
+
+            let ratio_infinite = Ratio::new(f32::INFINITY, 5.0);
+            assert!(ratio_infinite.is_none());
+
+            let ratio_nan = Ratio::new(f32::NAN, 5.0);
+            assert!(ratio_nan.is_none());
+
+            let ratio_overflow = Ratio::new(f32::MAX, 0.5);
+            assert!(ratio_overflow.is_none());
         }
 
         #[test]
@@ -0,0 +1,535 @@
+use super::{ExtentUpdate, ExtentUpdateType, ExtentUpdateSingle, ExtentStretch, ExtentLocate, ExtentRatio, SizeType, PositionType, AnchorPoint, RefView, Dim, ValidateError, ValidateWarning, super::ExtentController};
+use super::validate::check_finite;
+use std::{rc::Rc, cell::RefCell, fmt};
+
+impl ExtentUpdate {
+    /// Finds every error and warning in both dimensions and checks that they do not both use ratio mode, unlike validate this does not stop at the first problem
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: A slice of all the previous siblings of this view
+    /// 
+    /// path: The child indices from the root to this view
+    pub(crate) fn diagnose(&self, siblings: &[Rc<RefCell<ExtentController>>], path: &[usize]) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        // Make sure both dimensions are not using ratio mode
+        if let (ExtentUpdateType::Ratio(_), ExtentUpdateType::Ratio(_)) = (self.x.extent_type, self.y.extent_type) {
+            diagnostics.push(Diagnostic::new(path, None, vec!["extent_type"], DiagnosticKind::Error(ValidateError::BothRatio)));
+        }
+
+        // Find the problems in each dimension
+        for (dim, single) in [(Dim::X, &self.x), (Dim::Y, &self.y)] {
+            let mut found = Vec::new();
+            single.diagnose(siblings, &mut vec![], &mut found);
+            diagnostics.extend(found.into_iter().map(|(field, kind)| Diagnostic::new(path, Some(dim), field, kind)));
+        }
+
+        diagnostics
+    }
+}
+
+impl ExtentUpdateSingle {
+    /// Finds non-finite scales and offsets, a negative relative scale and the problems of the extent type
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: A slice of all the previous siblings of this view
+    /// 
+    /// field: The field path to this value
+    /// 
+    /// found: The list to add all problems to together with their field path
+    fn diagnose(&self, siblings: &[Rc<RefCell<ExtentController>>], field: &mut Vec<&'static str>, found: &mut Vec<(Vec<&'static str>, DiagnosticKind)>) {
+        // Check the scales and offsets
+        for (name, value) in [("scale_rel", self.scale_rel), ("scale_abs", self.scale_abs), ("offset_rel", self.offset_rel), ("offset_abs", self.offset_abs)] {
+            field.push(name);
+            report_finite(value, field, found);
+            field.pop();
+        }
+        if self.scale_rel < 0.0 {
+            field.push("scale_rel");
+            found.push((field.clone(), DiagnosticKind::Warning(ValidateWarning::NegativeScale(self.scale_rel))));
+            field.pop();
+        }
+
+        field.push("extent_type");
+        self.extent_type.diagnose(siblings, field, found);
+        field.pop();
+    }
+}
+
+impl ExtentUpdateType {
+    /// Finds the problems of whichever mode is used, the field path gets the name of the mode
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: A slice of all the previous siblings of this view
+    /// 
+    /// field: The field path to this value
+    /// 
+    /// found: The list to add all problems to together with their field path
+    fn diagnose(&self, siblings: &[Rc<RefCell<ExtentController>>], field: &mut Vec<&'static str>, found: &mut Vec<(Vec<&'static str>, DiagnosticKind)>) {
+        match self {
+            // Check stretch mode
+            Self::Stretch(stretch) => {
+                field.push("Stretch");
+                stretch.diagnose(siblings, field, found);
+            }
+
+            // Check locate mode
+            Self::Locate(locate) => {
+                field.push("Locate");
+                locate.diagnose(siblings, field, found);
+            }
+
+            // Check ratio mode
+            Self::Ratio(ratio) => {
+                field.push("Ratio");
+                ratio.diagnose(siblings, field, found);
+            }
+        }
+        field.pop();
+    }
+}
+
+impl ExtentRatio {
+    /// Finds the problems of the position, the ratio itself is always valid
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: A slice of all the previous siblings of this view
+    /// 
+    /// field: The field path to this value
+    /// 
+    /// found: The list to add all problems to together with their field path
+    fn diagnose(&self, siblings: &[Rc<RefCell<ExtentController>>], field: &mut Vec<&'static str>, found: &mut Vec<(Vec<&'static str>, DiagnosticKind)>) {
+        field.push("pos");
+        self.pos.diagnose(siblings, field, found);
+        field.pop();
+    }
+}
+
+impl ExtentLocate {
+    /// Finds the problems of the position and the size
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: A slice of all the previous siblings of this view
+    /// 
+    /// field: The field path to this value
+    /// 
+    /// found: The list to add all problems to together with their field path
+    fn diagnose(&self, siblings: &[Rc<RefCell<ExtentController>>], field: &mut Vec<&'static str>, found: &mut Vec<(Vec<&'static str>, DiagnosticKind)>) {
+        field.push("pos");
+        self.pos.diagnose(siblings, field, found);
+        field.pop();
+
+        field.push("size");
+        self.size.diagnose(siblings, field, found);
+        field.pop();
+    }
+}
+
+impl SizeType {
+    /// Finds invalid references and stretches, and sizes which are not finite or negative
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: A slice of all the previous siblings of this view
+    /// 
+    /// field: The field path to this value
+    /// 
+    /// found: The list to add all problems to together with their field path
+    fn diagnose(&self, siblings: &[Rc<RefCell<ExtentController>>], field: &mut Vec<&'static str>, found: &mut Vec<(Vec<&'static str>, DiagnosticKind)>) {
+        match self {
+            // Check the stretch
+            Self::Stretch(stretch) => {
+                field.push("Stretch");
+                stretch.diagnose(siblings, field, found);
+                field.pop();
+            }
+
+            // Check the reference
+            Self::Relative(ref_view) => {
+                field.push("Relative");
+                ref_view.diagnose(siblings, field, found);
+                field.pop();
+            }
+
+            // Check the size is finite and not negative
+            Self::Set(size) => {
+                field.push("Set");
+                report_finite(*size, field, found);
+                if *size < 0.0 {
+                    found.push((field.clone(), DiagnosticKind::Warning(ValidateWarning::NegativeSize(*size))));
+                }
+                field.pop();
+            }
+
+            // The content decides the size
+            Self::Intrinsic => (),
+        }
+    }
+}
+
+impl ExtentStretch {
+    /// Finds the problems of both positions and set positions which are in the wrong order
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: A slice of all the previous siblings of this view
+    /// 
+    /// field: The field path to this value
+    /// 
+    /// found: The list to add all problems to together with their field path
+    fn diagnose(&self, siblings: &[Rc<RefCell<ExtentController>>], field: &mut Vec<&'static str>, found: &mut Vec<(Vec<&'static str>, DiagnosticKind)>) {
+        field.push("pos1");
+        self.pos1.diagnose(siblings, field, found);
+        field.pop();
+
+        field.push("pos2");
+        self.pos2.diagnose(siblings, field, found);
+        field.pop();
+
+        // Warn if two set positions are in the wrong order
+        if let (PositionType::Set(pos1), PositionType::Set(pos2)) = (self.pos1, self.pos2) {
+            if pos2 < pos1 {
+                found.push((field.clone(), DiagnosticKind::Warning(ValidateWarning::InvertedStretch(pos1, pos2))));
+            }
+        }
+    }
+}
+
+impl PositionType {
+    /// Finds the problems of an anchor point or a set position which is not finite
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: A slice of all the previous siblings of this view
+    /// 
+    /// field: The field path to this value
+    /// 
+    /// found: The list to add all problems to together with their field path
+    fn diagnose(&self, siblings: &[Rc<RefCell<ExtentController>>], field: &mut Vec<&'static str>, found: &mut Vec<(Vec<&'static str>, DiagnosticKind)>) {
+        match self {
+            // Check the anchor point
+            Self::Anchor(anchor) => {
+                field.push("Anchor");
+                anchor.diagnose(siblings, field, found);
+                field.pop();
+            }
+
+            // Check the position is finite
+            Self::Set(pos) => {
+                field.push("Set");
+                report_finite(*pos, field, found);
+                field.pop();
+            }
+        }
+    }
+}
+
+impl AnchorPoint {
+    /// Finds a reference point which is not finite or outside of the referenced view and an invalid referenced view
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: A slice of all the previous siblings of this view
+    /// 
+    /// field: The field path to this value
+    /// 
+    /// found: The list to add all problems to together with their field path
+    fn diagnose(&self, siblings: &[Rc<RefCell<ExtentController>>], field: &mut Vec<&'static str>, found: &mut Vec<(Vec<&'static str>, DiagnosticKind)>) {
+        // Check the point is finite and on the referenced view
+        field.push("ref_point");
+        report_finite(self.ref_point, field, found);
+        if !(0.0..=1.0).contains(&self.ref_point) && self.ref_point.is_finite() {
+            found.push((field.clone(), DiagnosticKind::Warning(ValidateWarning::RefPointOutside(self.ref_point))));
+        }
+        field.pop();
+
+        field.push("ref_view");
+        self.ref_view.diagnose(siblings, field, found);
+        field.pop();
+    }
+}
+
+impl RefView {
+    /// Adds the problem to the list if the reference is invalid
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: A slice of all the previous siblings of this view
+    /// 
+    /// field: The field path to this value
+    /// 
+    /// found: The list to add all problems to together with their field path
+    fn diagnose(&self, siblings: &[Rc<RefCell<ExtentController>>], field: &[&'static str], found: &mut Vec<(Vec<&'static str>, DiagnosticKind)>) {
+        if let Err(error) = self.validate(siblings) {
+            found.push((field.to_vec(), DiagnosticKind::Error(error)));
+        }
+    }
+}
+
+/// Adds an error to the list if the value is NaN or infinite
+/// 
+/// # Parameters
+/// 
+/// value: The value to check
+/// 
+/// field: The field path to the value
+/// 
+/// found: The list to add all problems to together with their field path
+fn report_finite(value: f32, field: &[&'static str], found: &mut Vec<(Vec<&'static str>, DiagnosticKind)>) {
+    if let Err(error) = check_finite(value) {
+        found.push((field.to_vec(), DiagnosticKind::Error(error)));
+    }
+}
+
+impl ValidateError {
+    /// Gets a suggestion for how to fix the problem
+    pub fn suggestion(&self) -> String {
+        match *self {
+            Self::InvalidId(_, 0) => "The view has no older siblings, use a set value instead of a reference".to_string(),
+            Self::InvalidId(_, len) => format!("Views can only reference older siblings, use an ID smaller than {}", len),
+            Self::NoPrev => "The view is the first child, use a set value or reference the view by ID once it has older siblings".to_string(),
+            Self::BothRatio => "Use Locate or Stretch for one of the dimensions, a ratio needs the size of the other dimension".to_string(),
+            Self::NonFinite => "Use a finite number, NaN and infinity cannot be laid out".to_string(),
+        }
+    }
+}
+
+impl ValidateWarning {
+    /// Gets a suggestion for how to fix the problem
+    pub fn suggestion(&self) -> String {
+        match *self {
+            Self::RefPointOutside(_) => "Use a reference point between 0 and 1 and move the view with offset_rel or offset_abs instead".to_string(),
+            Self::NegativeScale(_) => "Use a positive relative scale, a negative size is always clamped to 0".to_string(),
+            Self::NegativeSize(_) => "Use a positive size, a negative size is always clamped to 0".to_string(),
+            Self::InvertedStretch(pos1, pos2) => format!("Swap the positions so pos1 is {:?} and pos2 is {:?}", pos2, pos1),
+        }
+    }
+}
+
+/// The kind of problem found
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiagnosticKind {
+    /// The view is invalid and cannot be laid out
+    Error(ValidateError),
+    /// The view can be laid out but the value is most likely a mistake
+    Warning(ValidateWarning),
+}
+
+impl DiagnosticKind {
+    /// Checks if the problem is an error
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Error(_))
+    }
+
+    /// Gets a suggestion for how to fix the problem
+    pub fn suggestion(&self) -> String {
+        match self {
+            Self::Error(error) => error.suggestion(),
+            Self::Warning(warning) => warning.suggestion(),
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error(error) => write!(f, "{}", error),
+            Self::Warning(warning) => write!(f, "{}", warning),
+        }
+    }
+}
+
+/// Describes a single problem in a view tree
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// The child indices from the root to the view with the problem
+    pub path: Vec<usize>,
+    /// The dimension of the problem, None if it concerns both dimensions
+    pub dim: Option<Dim>,
+    /// The field path inside the update info of the dimension
+    pub field: Vec<&'static str>,
+    /// The problem
+    pub kind: DiagnosticKind,
+    /// A suggestion on how to fix the problem
+    pub suggestion: String,
+}
+
+impl Diagnostic {
+    /// Creates a new diagnostic
+    /// 
+    /// # Parameters
+    /// 
+    /// path: The child indices from the root to the view with the problem
+    /// 
+    /// dim: The dimension of the problem
+    /// 
+    /// field: The field path inside the update info of the dimension
+    /// 
+    /// kind: The problem
+    pub(crate) fn new(path: &[usize], dim: Option<Dim>, field: Vec<&'static str>, kind: DiagnosticKind) -> Self {
+        let suggestion = kind.suggestion();
+
+        Self { path: path.to_vec(), dim, field, kind, suggestion }
+    }
+
+    /// Gets the full field path inside the update info as a string, e.g. "x.extent_type.Locate.pos"
+    pub fn field_path(&self) -> String {
+        match self.dim {
+            Some(dim) => format!("{}.{}", dim, self.field.join(".")),
+            None => self.field.join("."),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = if self.kind.is_error() {
+            "error"
+        } else {
+            "warning"
+        };
+
+        writeln!(f, "{} at view {:?}, field {}: {}", severity, self.path, self.field_path(), self.kind)?;
+        write!(f, "  help: {}", self.suggestion)
+    }
+}
+
+/// A collection of all problems found in a view tree
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct DiagnosticReport {
+    /// All the problems in the order they were found
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticReport {
+    /// Creates an empty report
+    pub fn new() -> Self {
+        Self { diagnostics: Vec::new() }
+    }
+
+    /// Checks if no problems were found
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Checks if any of the problems are errors, warnings alone do not stop a view from being laid out
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|diagnostic| diagnostic.kind.is_error())
+    }
+}
+
+impl fmt::Display for DiagnosticReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in self.diagnostics.iter() {
+            writeln!(f, "{}", diagnostic)?;
+        }
+
+        let errors = self.diagnostics.iter().filter(|diagnostic| diagnostic.kind.is_error()).count();
+        let warnings = self.diagnostics.len() - errors;
+        let plural = |count: usize, name: &str| if count == 1 {
+            format!("1 {}", name)
+        } else {
+            format!("{} {}s", count, name)
+        };
+
+        if self.diagnostics.is_empty() {
+            write!(f, "no problems found")
+        } else {
+            write!(f, "{} and {} found", plural(errors, "error"), plural(warnings, "warning"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::RatioSource;
+
+    fn gen_controller() -> Rc<RefCell<ExtentController>> {
+        let extent_single = ExtentUpdateSingle { scale_rel: 1.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0, extent_type: ExtentUpdateType::Locate(ExtentLocate { pos: PositionType::Set(0.0), size: SizeType::Set(1.0) }) };
+        let extent_info = ExtentUpdate { x: extent_single, y: extent_single };
+        Rc::new(RefCell::new(ExtentController::new(extent_info)))
+    }
+
+    #[test]
+    fn extent_update_single() {
+        let siblings = vec![gen_controller()];
+
+        let extent_stretch = ExtentStretch { pos1: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }), pos2: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }) };
+        let extent_update_single = ExtentUpdateSingle { extent_type: ExtentUpdateType::Locate(ExtentLocate { pos: PositionType::Set(0.0), size: SizeType::Stretch(extent_stretch) }), scale_rel: 1.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0 };
+
+        let mut found = Vec::new();
+        extent_update_single.diagnose(&siblings, &mut vec![], &mut found);
+        assert_eq!(vec![(vec!["extent_type", "Locate", "size", "Stretch", "pos2", "Anchor", "ref_view"], DiagnosticKind::Error(ValidateError::InvalidId(1, 1)))], found);
+
+        let mut found_all = Vec::new();
+        extent_update_single.diagnose(&siblings[..0], &mut vec![], &mut found_all);
+        assert_eq!(vec![
+            (vec!["extent_type", "Locate", "size", "Stretch", "pos1", "Anchor", "ref_view"], DiagnosticKind::Error(ValidateError::NoPrev)),
+            (vec!["extent_type", "Locate", "size", "Stretch", "pos2", "Anchor", "ref_view"], DiagnosticKind::Error(ValidateError::InvalidId(1, 0))),
+        ], found_all);
+    }
+
+    #[test]
+    fn extent_update_single_values() {
+        let siblings = vec![gen_controller()];
+
+        let extent_stretch = ExtentStretch { pos1: PositionType::Set(1.0), pos2: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: f32::NAN }) };
+        let extent_locate = ExtentLocate { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 1.5 }), size: SizeType::Set(-1.0) };
+        let extent_update_single = ExtentUpdateSingle { extent_type: ExtentUpdateType::Locate(extent_locate), scale_rel: -1.0, scale_abs: f32::INFINITY, offset_rel: 0.0, offset_abs: 0.0 };
+
+        let mut found = Vec::new();
+        extent_update_single.diagnose(&siblings, &mut vec![], &mut found);
+        assert_eq!(vec![
+            (vec!["scale_abs"], DiagnosticKind::Error(ValidateError::NonFinite)),
+            (vec!["scale_rel"], DiagnosticKind::Warning(ValidateWarning::NegativeScale(-1.0))),
+            (vec!["extent_type", "Locate", "pos", "Anchor", "ref_point"], DiagnosticKind::Warning(ValidateWarning::RefPointOutside(1.5))),
+            (vec!["extent_type", "Locate", "size", "Set"], DiagnosticKind::Warning(ValidateWarning::NegativeSize(-1.0))),
+        ], found);
+
+        let mut found_stretch = Vec::new();
+        extent_stretch.diagnose(&siblings, &mut vec![], &mut found_stretch);
+        assert_eq!(vec![(vec!["pos2", "Anchor", "ref_point"], DiagnosticKind::Error(ValidateError::NonFinite))], found_stretch);
+
+        let extent_stretch_inverted = ExtentStretch { pos1: PositionType::Set(1.0), pos2: PositionType::Set(0.5) };
+        let mut found_inverted = Vec::new();
+        extent_stretch_inverted.diagnose(&siblings, &mut vec![], &mut found_inverted);
+        assert_eq!(vec![(vec![], DiagnosticKind::Warning(ValidateWarning::InvertedStretch(1.0, 0.5)))], found_inverted);
+    }
+
+    #[test]
+    fn extent_update() {
+        let ratio = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }), source: RatioSource::Square });
+        let extent_update_single = ExtentUpdateSingle { extent_type: ratio, scale_rel: 1.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0 };
+        let extent_update = ExtentUpdate { x: extent_update_single, y: extent_update_single };
+
+        let diagnostics = extent_update.diagnose(&[], &[0, 2]);
+        assert_eq!(3, diagnostics.len());
+        assert_eq!(Diagnostic::new(&[0, 2], None, vec!["extent_type"], DiagnosticKind::Error(ValidateError::BothRatio)), diagnostics[0]);
+        assert_eq!(Some(Dim::X), diagnostics[1].dim);
+        assert_eq!("x.extent_type.Ratio.pos.Anchor.ref_view", diagnostics[1].field_path());
+        assert_eq!(Some(Dim::Y), diagnostics[2].dim);
+        assert_eq!(DiagnosticKind::Error(ValidateError::NoPrev), diagnostics[2].kind);
+    }
+
+    #[test]
+    fn report() {
+        let report_empty = DiagnosticReport::new();
+        assert!(report_empty.is_empty());
+        assert_eq!("no problems found", report_empty.to_string());
+        assert!(!report_empty.has_errors());
+
+        let report_warning = DiagnosticReport { diagnostics: vec![Diagnostic::new(&[0], Some(Dim::X), vec!["scale_rel"], DiagnosticKind::Warning(ValidateWarning::NegativeScale(-2.0)))] };
+        assert!(!report_warning.has_errors());
+        assert!(report_warning.to_string().starts_with("warning at view [0], field x.scale_rel: "));
+        assert!(report_warning.to_string().ends_with("0 errors and 1 warning found"));
+
+        let report = DiagnosticReport { diagnostics: vec![Diagnostic::new(&[1], Some(Dim::Y), vec!["extent_type", "Locate", "size", "Relative"], DiagnosticKind::Error(ValidateError::InvalidId(3, 1)))] };
+        assert_eq!("error at view [1], field y.extent_type.Locate.size.Relative: A sibling ID of 3 is too large, it must be smaller than 1\n  help: Views can only reference older siblings, use an ID smaller than 1\n1 error and 0 warnings found", report.to_string());
+    }
+}
@@ -1,5 +1,5 @@
-use super::{View, Ratio};
-use super::{ExtentUpdate, ExtentUpdateType, ExtentUpdateSingle, ExtentStretch, ExtentLocate, ExtentRatio, Dim, SizeType, PositionType, AnchorPoint, RefView};
+use super::{View, Ratio, Content, ValidateError};
+use super::{ExtentUpdate, ExtentUpdateType, ExtentUpdateSingle, ExtentStretch, ExtentLocate, ExtentRatio, RatioSource, Dim, SizeType, PositionType, AnchorPoint, RefView};
 
 impl ExtentUpdate {
     /// Retrieves the extent
@@ -9,27 +9,31 @@ impl ExtentUpdate {
     /// dim: The dimension to use
     /// 
     /// siblings: The list of older siblings
-    pub(crate) fn get(&self, siblings: &[Box<View>], parent_ratio: Ratio) -> (f32, f32, f32, f32) {
+    /// 
+    /// parent_ratio: The aspect ratio of the parent
+    /// 
+    /// content: The content of the view used for intrinsic sizes
+    pub(crate) fn get(&self, siblings: &[Box<View>], parent_ratio: Ratio, content: &Content) -> Result<(f32, f32, f32, f32), ValidateError> {
         // Get the x and y components
         let (x, y) = match self.x.extent_type {
             // y must be evaluated before x
             ExtentUpdateType::Ratio(_) => {
-                let y = self.y.get(Dim::Y, siblings, parent_ratio, 0.0);
-                let x = self.x.get(Dim::X, siblings, parent_ratio, y.1);
+                let y = self.y.get(Dim::Y, siblings, parent_ratio, content, None)?;
+                let x = self.x.get(Dim::X, siblings, parent_ratio, content, Some(y.1))?;
     
                 (x, y)  
             }
 
             // x must be evaluated before y
             _ => {
-                let x = self.x.get(Dim::X, siblings, parent_ratio, 0.0);
-                let y = self.y.get(Dim::Y, siblings, parent_ratio, x.1);
+                let x = self.x.get(Dim::X, siblings, parent_ratio, content, None)?;
+                let y = self.y.get(Dim::Y, siblings, parent_ratio, content, Some(x.1))?;
     
                 (x, y)  
             }
         };
 
-        (x.0, y.0, x.1, y.1)
+        Ok((x.0, y.0, x.1, y.1))
     }
 }
 
@@ -44,22 +48,29 @@ impl ExtentUpdateSingle {
     ///
     /// parent_ratio: The aspect ratio of the parent, used if extent type is ratio
     /// 
-    /// other_size: The sizze of the other dimension, used if extent type is ratio
-    fn get(&self, dim: Dim, siblings: &[Box<View>], parent_ratio: Ratio, other_size: f32) -> (f32, f32) {
+    /// content: The content of the view used for intrinsic sizes
+    /// 
+    /// other_size: The size of the other dimension if it has already been evaluated
+    fn get(&self, dim: Dim, siblings: &[Box<View>], parent_ratio: Ratio, content: &Content, other_size: Option<f32>) -> Result<(f32, f32), ValidateError> {
         // Get the base position and size
-        let (mut pos, mut size) = self.extent_type.get(dim, siblings, parent_ratio, other_size);
+        let (mut pos, mut size) = self.extent_type.get(dim, siblings, parent_ratio, content, other_size)?;
 
         // Apply changes
         pos += self.offset_abs + self.offset_rel * size;
         size *= self.scale_rel;
         size += self.scale_abs;
 
-        // Make sure size is not negative
-        if size < 0.0 {
+        // Make sure size is not negative, NaN or infinite
+        if !(size >= 0.0 && size.is_finite()) {
             size = 0.0;
         }
 
-        (pos, size)
+        // Make sure the position is not NaN or infinite, this can only happen if the values overflow
+        if !pos.is_finite() {
+            pos = 0.0;
+        }
+
+        Ok((pos, size))
     }
 }
 
@@ -74,17 +85,19 @@ impl ExtentUpdateType {
     /// 
     /// parent_ratio: The aspect ratio of the parent, used if extent type is ratio
     /// 
-    /// other_size: The sizze of the other dimension, used if extent type is ratio
-    fn get(&self, dim: Dim, siblings: &[Box<View>], parent_ratio: Ratio, other_size: f32) -> (f32, f32) {
+    /// content: The content of the view used for intrinsic sizes
+    /// 
+    /// other_size: The size of the other dimension if it has already been evaluated
+    fn get(&self, dim: Dim, siblings: &[Box<View>], parent_ratio: Ratio, content: &Content, other_size: Option<f32>) -> Result<(f32, f32), ValidateError> {
         match self {
             // Get from the stretch method
             Self::Stretch(stretch) => stretch.get(dim, siblings),
 
             // Get from the locate method
-            Self::Locate(locate) => locate.get(dim, siblings),
+            Self::Locate(locate) => locate.get(dim, siblings, content, other_size),
 
             // Get from the ratio method
-            Self::Ratio(ratio) => ratio.get(dim, siblings, parent_ratio, other_size),
+            Self::Ratio(ratio) => ratio.get(dim, siblings, parent_ratio, content, other_size),
         }
     }
 }
@@ -100,18 +113,26 @@ impl ExtentRatio {
     /// 
     /// parent_ratio: The aspect ratio of the parent
     /// 
-    /// other_size: The sizze of the other dimension
-    fn get(&self, dim: Dim, siblings: &[Box<View>], parent_ratio: Ratio, other_size: f32) -> (f32, f32) {
+    /// content: The content of the view used if the ratio comes from the content
+    /// 
+    /// other_size: The size of the other dimension, it is always evaluated first
+    fn get(&self, dim: Dim, siblings: &[Box<View>], parent_ratio: Ratio, content: &Content, other_size: Option<f32>) -> Result<(f32, f32), ValidateError> {
         // Get the position and size
-        let pos = self.pos.get(dim, siblings);
+        let pos = self.pos.get(dim, siblings)?;
+
+        // Find the ratio of the view on the screen
+        let ratio = match self.source {
+            RatioSource::Square => None,
+            RatioSource::Content => content.get_ratio(),
+        }.unwrap_or(Ratio::new(1.0, 1.0).unwrap());
 
         // Divide by parent ratio to make up for it
-        let size = other_size / match dim {
-            Dim::X => parent_ratio.get_x(),
-            Dim::Y => parent_ratio.get_y(),
+        let size = other_size.unwrap_or(0.0) * match dim {
+            Dim::X => ratio.get_x() / parent_ratio.get_x(),
+            Dim::Y => ratio.get_y() / parent_ratio.get_y(),
         };
 
-        (pos, size)
+        Ok((pos, size))
     }
 }
 
@@ -123,12 +144,16 @@ impl ExtentLocate {
     /// dim: The dimension to use
     /// 
     /// siblings: The list of older siblings
-    fn get(&self, dim: Dim, siblings: &[Box<View>]) -> (f32, f32) {
+    /// 
+    /// content: The content of the view used for intrinsic sizes
+    /// 
+    /// other_size: The size of the other dimension if it has already been evaluated
+    fn get(&self, dim: Dim, siblings: &[Box<View>], content: &Content, other_size: Option<f32>) -> Result<(f32, f32), ValidateError> {
         // Get the position and size
-        let pos = self.pos.get(dim, siblings);
-        let size = self.size.get(dim, siblings);
+        let pos = self.pos.get(dim, siblings)?;
+        let size = self.size.get(dim, siblings, content, other_size)?;
 
-        (pos, size)
+        Ok((pos, size))
     }
 }
 
@@ -140,16 +165,23 @@ impl SizeType {
     /// dim: The dimension to use
     /// 
     /// siblings: The list of older siblings
-    fn get(&self, dim: Dim, siblings: &[Box<View>]) -> f32 {
+    /// 
+    /// content: The content of the view used for intrinsic sizes
+    /// 
+    /// other_size: The size of the other dimension if it has already been evaluated
+    fn get(&self, dim: Dim, siblings: &[Box<View>], content: &Content, other_size: Option<f32>) -> Result<f32, ValidateError> {
         match self {
             // Use the size from a stretch
-            Self::Stretch(stretch) => stretch.get(dim, siblings).1,
+            Self::Stretch(stretch) => Ok(stretch.get(dim, siblings)?.1),
 
             // Get the size from another view
-            Self::Relative(ref_view) => ref_view.get(dim, siblings).1,
+            Self::Relative(ref_view) => Ok(ref_view.get(dim, siblings)?.1),
 
             // Use a static size
-            Self::Set(size) => *size,
+            Self::Set(size) => Ok(*size),
+
+            // Measure the content
+            Self::Intrinsic => Ok(content.get_intrinsic(dim, other_size)),
         }
     }
 }
@@ -162,12 +194,12 @@ impl ExtentStretch {
     /// dim: The dimension to use
     /// 
     /// siblings: The list of older siblings
-    fn get(&self, dim: Dim, siblings: &[Box<View>]) -> (f32, f32) {
+    fn get(&self, dim: Dim, siblings: &[Box<View>]) -> Result<(f32, f32), ValidateError> {
         // Get the two positions
-        let pos1 = self.pos1.get(dim, siblings);
-        let pos2 = self.pos2.get(dim, siblings);
+        let pos1 = self.pos1.get(dim, siblings)?;
+        let pos2 = self.pos2.get(dim, siblings)?;
 
-        (pos1, pos2 - pos1)
+        Ok((pos1, pos2 - pos1))
     }
 }
 
@@ -179,13 +211,13 @@ impl PositionType {
     /// dim: The dimension to use
     /// 
     /// siblings: The list of older siblings
-    fn get(&self, dim: Dim, siblings: &[Box<View>]) -> f32 {
+    fn get(&self, dim: Dim, siblings: &[Box<View>]) -> Result<f32, ValidateError> {
         match self {
             // Get from an anchor point
             Self::Anchor(anchor) => anchor.get(dim, siblings),
 
             // Get a static position
-            Self::Set(pos) => *pos,
+            Self::Set(pos) => Ok(*pos),
         }
     }
 }
@@ -198,12 +230,12 @@ impl AnchorPoint {
     /// dim: The dimension to use
     /// 
     /// siblings: The list of older siblings
-    fn get(&self, dim: Dim, siblings: &[Box<View>]) -> f32 {
+    fn get(&self, dim: Dim, siblings: &[Box<View>]) -> Result<f32, ValidateError> {
         // Get the position and size
-        let (pos, size) = self.ref_view.get(dim, siblings);
+        let (pos, size) = self.ref_view.get(dim, siblings)?;
 
         // Get the correct position
-        pos + self.ref_point * size
+        Ok(pos + self.ref_point * size)
     }
 }
 
@@ -215,13 +247,19 @@ impl RefView {
     /// dim: The dimension to use
     /// 
     /// siblings: The list of older siblings
-    fn get(&self, dim: Dim, siblings: &[Box<View>]) -> (f32, f32) {
+    fn get(&self, dim: Dim, siblings: &[Box<View>]) -> Result<(f32, f32), ValidateError> {
         match *self {
             // Get size from previous sibling
-            Self::Prev => dim.get_from_view(siblings.last().unwrap()),
+            Self::Prev => match siblings.last() {
+                Some(view) => Ok(dim.get_from_view(view)),
+                None => Err(ValidateError::NoPrev),
+            },
 
             // Get size from id
-            Self::Id(n) => dim.get_from_view(siblings.get(n).unwrap()),
+            Self::Id(n) => match siblings.get(n) {
+                Some(view) => Ok(dim.get_from_view(view)),
+                None => Err(ValidateError::InvalidId(n, siblings.len())),
+            },
         }
     }
 }
@@ -239,7 +277,7 @@ mod tests {
         extent.y = y;
         extent.w = w;
         extent.h = h;
-        View { children: Children::new(None), extent: extent, sibling_id: Some(sibling_id) }
+        View { children: Children::new(None), extent, style: Default::default(), focus: Default::default(), sibling_id: Some(sibling_id), drawable: Default::default(), clip: false, dirty: false, painted: None, owner: Default::default() }
     }
 
     #[test]
@@ -249,12 +287,15 @@ mod tests {
         let siblings = vec![Box::new(sibling1), Box::new(sibling2)];
         
         let ref_view_prev = RefView::Prev;
-        assert_eq!((5.0, 7.0), ref_view_prev.get(Dim::X, &siblings));
-        assert_eq!((6.0, 8.0), ref_view_prev.get(Dim::Y, &siblings));
+        assert_eq!(Ok((5.0, 7.0)), ref_view_prev.get(Dim::X, &siblings));
+        assert_eq!(Ok((6.0, 8.0)), ref_view_prev.get(Dim::Y, &siblings));
 
         let ref_view_id = RefView::Id(0);
-        assert_eq!((1.0, 3.0), ref_view_id.get(Dim::X, &siblings));
-        assert_eq!((2.0, 4.0), ref_view_id.get(Dim::Y, &siblings));
+        assert_eq!(Ok((1.0, 3.0)), ref_view_id.get(Dim::X, &siblings));
+        assert_eq!(Ok((2.0, 4.0)), ref_view_id.get(Dim::Y, &siblings));
+
+        assert_eq!(Err(ValidateError::NoPrev), ref_view_prev.get(Dim::X, &siblings[..0]));
+        assert_eq!(Err(ValidateError::InvalidId(2, 2)), RefView::Id(2).get(Dim::X, &siblings));
     }
 
     #[test]
@@ -263,10 +304,10 @@ mod tests {
         let siblings = vec![Box::new(sibling1)];
 
         let anchor_point_1 = AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 };
-        assert_eq!(1.0, anchor_point_1.get(Dim::X, &siblings));
+        assert_eq!(Ok(1.0), anchor_point_1.get(Dim::X, &siblings));
 
         let anchor_point_2 = AnchorPoint { ref_view: RefView::Prev, ref_point: 1.0 };
-        assert_eq!(4.0, anchor_point_2.get(Dim::X, &siblings));
+        assert_eq!(Ok(4.0), anchor_point_2.get(Dim::X, &siblings));
     }
 
     #[test]
@@ -275,10 +316,10 @@ mod tests {
         let siblings = vec![Box::new(sibling1)];
 
         let position_type_anchor = PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 });
-        assert_eq!(1.0, position_type_anchor.get(Dim::X, &siblings));
+        assert_eq!(Ok(1.0), position_type_anchor.get(Dim::X, &siblings));
 
         let position_type_set = PositionType::Set(11.0);
-        assert_eq!(11.0, position_type_set.get(Dim::X, &siblings));
+        assert_eq!(Ok(11.0), position_type_set.get(Dim::X, &siblings));
     }
 
     #[test]
@@ -287,7 +328,7 @@ mod tests {
         let siblings = vec![Box::new(sibling1)];
 
         let extent_stretch = ExtentStretch { pos1: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }), pos2: PositionType::Set(6.0) };
-        assert_eq!((2.5, 3.5), extent_stretch.get(Dim::X, &siblings));
+        assert_eq!(Ok((2.5, 3.5)), extent_stretch.get(Dim::X, &siblings));
     }
 
     #[test]
@@ -296,14 +337,14 @@ mod tests {
         let siblings = vec![Box::new(sibling1)];
 
         let size_type_set = SizeType::Set(5.0);
-        assert_eq!(5.0, size_type_set.get(Dim::X, &siblings));
+        assert_eq!(Ok(5.0), size_type_set.get(Dim::X, &siblings, &Content::empty(), None));
 
         let size_type_relative = SizeType::Relative(RefView::Prev);
-        assert_eq!(4.0, size_type_relative.get(Dim::Y, &siblings));
+        assert_eq!(Ok(4.0), size_type_relative.get(Dim::Y, &siblings, &Content::empty(), None));
 
         let extent_stretch = ExtentStretch { pos1: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }), pos2: PositionType::Set(6.0) };
         let size_type_stretch = SizeType::Stretch(extent_stretch);
-        assert_eq!(3.5, size_type_stretch.get(Dim::X, &siblings));
+        assert_eq!(Ok(3.5), size_type_stretch.get(Dim::X, &siblings, &Content::empty(), None));
     }
 
     #[test]
@@ -312,7 +353,7 @@ mod tests {
         let siblings = vec![Box::new(sibling1)];
 
         let extent_locate = ExtentLocate { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }), size: SizeType::Set(7.0) };
-        assert_eq!((2.5, 7.0), extent_locate.get(Dim::X, &siblings));
+        assert_eq!(Ok((2.5, 7.0)), extent_locate.get(Dim::X, &siblings, &Content::empty(), None));
     }
 
     #[test]
@@ -320,8 +361,8 @@ mod tests {
         let sibling1 = gen_view(1.0, 2.0, 3.0, 4.0, 0);
         let siblings = vec![Box::new(sibling1)];
 
-        let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }) };
-        assert_eq!((2.5, 20.0), extent_ratio.get(Dim::X, &siblings, Ratio::new(2.0, 8.0).unwrap(), 5.0));
+        let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }), source: RatioSource::Square };
+        assert_eq!(Ok((2.5, 20.0)), extent_ratio.get(Dim::X, &siblings, Ratio::new(2.0, 8.0).unwrap(), &Content::empty(), Some(5.0)));
     }
 
     #[test]
@@ -331,15 +372,15 @@ mod tests {
 
         let extent_locate = ExtentLocate { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }), size: SizeType::Set(7.0) };
         let extent_update_type_locate = ExtentUpdateType::Locate(extent_locate);
-        assert_eq!((2.5, 7.0), extent_update_type_locate.get(Dim::X, &siblings, Ratio::new(2.0, 8.0).unwrap(), 5.0));
+        assert_eq!(Ok((2.5, 7.0)), extent_update_type_locate.get(Dim::X, &siblings, Ratio::new(2.0, 8.0).unwrap(), &Content::empty(), Some(5.0)));
 
         let extent_stretch = ExtentStretch { pos1: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }), pos2: PositionType::Set(6.0) };
         let extent_update_type_stretch = ExtentUpdateType::Stretch(extent_stretch);
-        assert_eq!((2.5, 3.5), extent_update_type_stretch.get(Dim::X, &siblings, Ratio::new(2.0, 8.0).unwrap(), 5.0));
+        assert_eq!(Ok((2.5, 3.5)), extent_update_type_stretch.get(Dim::X, &siblings, Ratio::new(2.0, 8.0).unwrap(), &Content::empty(), Some(5.0)));
 
-        let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }) };
+        let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }), source: RatioSource::Square };
         let extent_update_type_ratio = ExtentUpdateType::Ratio(extent_ratio);
-        assert_eq!((2.5, 20.0), extent_update_type_ratio.get(Dim::X, &siblings, Ratio::new(2.0, 8.0).unwrap(), 5.0));
+        assert_eq!(Ok((2.5, 20.0)), extent_update_type_ratio.get(Dim::X, &siblings, Ratio::new(2.0, 8.0).unwrap(), &Content::empty(), Some(5.0)));
     }
 
     #[test]
@@ -347,9 +388,13 @@ mod tests {
         let sibling1 = gen_view(1.0, 2.0, 3.0, 4.0, 0);
         let siblings = vec![Box::new(sibling1)];
 
-        let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }) };
+        let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }), source: RatioSource::Square };
         let extent_update_single = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(extent_ratio), offset_rel: 0.5, offset_abs: 3.0, scale_rel: 0.2, scale_abs: -1.0 };
-        assert_eq!((15.5, 3.0), extent_update_single.get(Dim::X, &siblings, Ratio::new(2.0, 8.0).unwrap(), 5.0));
+        assert_eq!(Ok((15.5, 3.0)), extent_update_single.get(Dim::X, &siblings, Ratio::new(2.0, 8.0).unwrap(), &Content::empty(), Some(5.0)));
+
+        let extent_locate_overflow = ExtentLocate { pos: PositionType::Set(f32::MAX), size: SizeType::Set(f32::MAX) };
+        let extent_update_single_overflow = ExtentUpdateSingle { extent_type: ExtentUpdateType::Locate(extent_locate_overflow), offset_rel: 1.0, offset_abs: 0.0, scale_rel: 2.0, scale_abs: 0.0 };
+        assert_eq!(Ok((0.0, 0.0)), extent_update_single_overflow.get(Dim::X, &siblings, Ratio::new(2.0, 8.0).unwrap(), &Content::empty(), Some(5.0)));
     }
 
     #[test]
@@ -357,16 +402,16 @@ mod tests {
         let sibling1 = gen_view(1.0, 2.0, 3.0, 4.0, 0);
         let siblings = vec![Box::new(sibling1)];
 
-        let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }) };
+        let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.5 }), source: RatioSource::Square };
         let extent_update_single_x = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(extent_ratio), scale_rel: 1.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0 };
 
         let extent_locate = ExtentLocate { pos: PositionType::Set(5.0), size: SizeType::Set(2.0) };
         let extent_update_single_y = ExtentUpdateSingle { extent_type: ExtentUpdateType::Locate(extent_locate), scale_rel: 1.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0 };
 
         let extent_update = ExtentUpdate { x: extent_update_single_x, y: extent_update_single_y };
-        assert_eq!((2.5, 5.0, 8.0, 2.0), extent_update.get(&siblings, Ratio::new(1.0, 4.0).unwrap()));
+        assert_eq!(Ok((2.5, 5.0, 8.0, 2.0)), extent_update.get(&siblings, Ratio::new(1.0, 4.0).unwrap(), &Content::empty()));
 
         let extent_update_invert = ExtentUpdate { x: extent_update_single_y, y: extent_update_single_x };
-        assert_eq!((5.0, 4.0, 2.0, 0.5), extent_update_invert.get(&siblings, Ratio::new(1.0, 4.0).unwrap()));
+        assert_eq!(Ok((5.0, 4.0, 2.0, 0.5)), extent_update_invert.get(&siblings, Ratio::new(1.0, 4.0).unwrap(), &Content::empty()));
     }
 }
\ No newline at end of file
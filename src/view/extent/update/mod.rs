@@ -1,13 +1,18 @@
-use super::{View, Ratio};
+use super::{View, Ratio, Content};
+use serde::{Serialize, Deserialize};
+use std::fmt;
 
 mod validate;
+mod diagnose;
 mod get;
+#[allow(clippy::module_inception)]
 mod update;
 
-pub use validate::ValidateError;
+pub use validate::{ValidateError, ValidateWarning};
+pub use diagnose::{Diagnostic, DiagnosticKind, DiagnosticReport};
 
 /// Defines how the entire extent should update
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ExtentUpdate {
     /// Defines how the x-dimension should update
     pub x: ExtentUpdateSingle,
@@ -16,7 +21,7 @@ pub struct ExtentUpdate {
 }
 
 /// Defines how a single dimension should update
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ExtentUpdateSingle {
     /// Defines how the base extent should update
     pub extent_type: ExtentUpdateType,
@@ -24,25 +29,34 @@ pub struct ExtentUpdateSingle {
     /// scale_rel = 1 keeps the size the same
     /// 
     /// Scaling is applied after offsets and relative scaling is applied before absolute scaling
+    #[serde(default = "default_scale_rel")]
     pub scale_rel: f32,
     /// Scales the size by an absolute amount.
     /// scale_abs = 0 keeps the size the same
     /// 
     /// Scaling is applied after offsets and absolute scaling is applied after relative scaling
+    #[serde(default)]
     pub scale_abs: f32,
     /// Offsets the base extent relative to the size, 
     /// offset_rel = 1 moves the extent exactly one size in the positive direction.
     /// 
     /// Offsets are applied before scaling
+    #[serde(default)]
     pub offset_rel: f32,
     /// Offsets the base extent by a set amount.
     /// 
     /// Offsets are applied before scaling
+    #[serde(default)]
     pub offset_abs: f32,    
 }
 
+/// The default value of scale_rel when it is left out of a layout file
+fn default_scale_rel() -> f32 {
+    1.0
+}
+
 /// The different types of methods to update the extent
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ExtentUpdateType {
     /// The extent is updated by stretching it between two points
     Stretch(ExtentStretch),
@@ -53,14 +67,28 @@ pub enum ExtentUpdateType {
 }
 
 /// Defines how to update the extent when a fixed ratio between w and h is used
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ExtentRatio {
     /// The position of the extent
     pub pos: PositionType,
+    /// Where the ratio between w and h on the screen comes from
+    #[serde(default)]
+    pub source: RatioSource,
+}
+
+/// The different sources for the ratio between w and h used by ExtentRatio
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RatioSource {
+    /// The view is square on the screen
+    #[default]
+    Square,
+    /// The area inside the padding has the natural aspect ratio of the content of the view, such as an image.
+    /// The view is square if the content has no natural aspect ratio
+    Content,
 }
 
 /// Defines how to update the extent in Locate mode
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ExtentLocate {
     /// Defines how the position is updated
     pub pos: PositionType,
@@ -69,7 +97,7 @@ pub struct ExtentLocate {
 }
 
 /// The different ways to update the size
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SizeType {
     /// Update size by stretching between two points
     Stretch(ExtentStretch),
@@ -77,10 +105,13 @@ pub enum SizeType {
     Relative(RefView),
     /// Update the size by giving it a static value
     Set(f32),
+    /// Use the natural size of the content of the view including its padding, 0 if the content has no natural size.
+    /// In y the content is measured at the width of the view if x is evaluated first
+    Intrinsic,
 }
 
 /// Defines how to stretch between two points
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ExtentStretch {
     /// Defines the position at the lowest coordinate
     pub pos1: PositionType,
@@ -89,7 +120,7 @@ pub struct ExtentStretch {
 }
 
 /// The different ways to get a position
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum PositionType {
     /// Get the position relative from another view
     Anchor(AnchorPoint),
@@ -98,7 +129,7 @@ pub enum PositionType {
 }
 
 /// Defines how to get a point from another view
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AnchorPoint {
     /// The view to get it from
     pub ref_view: RefView,
@@ -107,7 +138,7 @@ pub struct AnchorPoint {
 }
 
 /// The different ways to reference another view
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum RefView {
     /// Use the previous sibling view, useful for lists
     Prev,
@@ -116,8 +147,8 @@ pub enum RefView {
 }
 
 /// Describes what dimension to get the coordinate from
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Dim {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dim {
     /// The x direction
     X,
     /// The y-direction
@@ -143,6 +174,15 @@ impl Dim {
     }
 }
 
+impl fmt::Display for Dim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::X => write!(f, "x"),
+            Self::Y => write!(f, "y"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,7 +196,7 @@ mod tests {
         extent.y = y;
         extent.w = w;
         extent.h = h;
-        View { children: Children::new(None), extent: extent, sibling_id: Some(sibling_id) }
+        View { children: Children::new(None), extent, style: Default::default(), focus: Default::default(), sibling_id: Some(sibling_id), drawable: Default::default(), clip: false, dirty: false, painted: None, owner: Default::default() }
     }
 
     #[test]
@@ -162,7 +162,7 @@ impl ExtentLocate {
     /// pos: The position that the sibling was inserted into
     pub(crate) fn update_insert(&mut self, pos: usize) {
         self.pos.update_insert(pos);
-        self.size.update_delete(pos);
+        self.size.update_insert(pos);
     }
 
     /// Updates possible references by ID on movement of a sibling before this one
@@ -202,8 +202,8 @@ impl SizeType {
             // The size is stretched between two points
             Self::Stretch(stretch) => stretch.update_insert(pos),
 
-            // Set never references anything
-            Self::Set(_) => (),
+            // Set and intrinsic never reference anything
+            Self::Set(_) | Self::Intrinsic => (),
         }
     }
 
@@ -222,8 +222,8 @@ impl SizeType {
             // The size is stretched between two points
             Self::Stretch(stretch) => stretch.update_move(from, to),
 
-            // Set never references anything
-            Self::Set(_) => (),
+            // Set and intrinsic never reference anything
+            Self::Set(_) | Self::Intrinsic => (),
         }
     }
 
@@ -240,8 +240,8 @@ impl SizeType {
             // The size is stretched between two points
             Self::Stretch(stretch) => stretch.update_delete(pos),
 
-            // Set never references anything
-            Self::Set(_) => (),
+            // Set and intrinsic never reference anything
+            Self::Set(_) | Self::Intrinsic => (),
         }
     }
 }
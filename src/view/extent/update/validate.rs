@@ -52,6 +52,12 @@ impl ExtentUpdateSingle {
     /// 
     /// siblings: A slice of all the previous siblings of this view
     fn validate(&self, siblings: &[Rc<RefCell<ExtentController>>]) -> Result<(), ValidateError> {
+        // Make sure all scales and offsets are finite
+        check_finite(self.scale_rel)?;
+        check_finite(self.scale_abs)?;
+        check_finite(self.offset_rel)?;
+        check_finite(self.offset_abs)?;
+
         // Make sure the extent is valid
         self.extent_type.validate(siblings)
     }
@@ -234,8 +240,11 @@ impl SizeType {
             // Make sure reference is valid
             Self::Relative(ref_view) => ref_view.validate(siblings),
 
-            // Set is always valid
-            Self::Set(_) => Ok(()),
+            // Make sure the size is finite
+            Self::Set(size) => check_finite(*size),
+
+            // The content decides the size
+            Self::Intrinsic => Ok(()),
         }
     }
 
@@ -252,8 +261,8 @@ impl SizeType {
             // The size is stretched between two points
             Self::Stretch(stretch) => stretch.check_id_range(range),
 
-            // Set never references anything
-            Self::Set(_) => false,
+            // Set and intrinsic never reference anything
+            Self::Set(_) | Self::Intrinsic => false,
         }
     }
 
@@ -270,8 +279,8 @@ impl SizeType {
             // The size is stretched between two points
             Self::Stretch(stretch) => stretch.check_id(id),
 
-            // Set never references anything
-            Self::Set(_) => false,
+            // Set and intrinsic never reference anything
+            Self::Set(_) | Self::Intrinsic => false,
         }
     }
 
@@ -284,8 +293,8 @@ impl SizeType {
             // The size is stretched between two points
             Self::Stretch(stretch) => stretch.check_prev(),
 
-            // Set never references anything
-            Self::Set(_) => false,
+            // Set and intrinsic never reference anything
+            Self::Set(_) | Self::Intrinsic => false,
         }
     }
 }
@@ -337,8 +346,8 @@ impl PositionType {
             // Make sure the anchor point is valid
             Self::Anchor(anchor) => anchor.validate(siblings),
 
-            // Set is always valid
-            Self::Set(_) => Ok(()),
+            // Make sure the position is finite
+            Self::Set(pos) => check_finite(*pos),
         }
     }
 
@@ -391,6 +400,9 @@ impl AnchorPoint {
     /// 
     /// siblings: A slice of all the previous siblings of this view
     fn validate(&self, siblings: &[Rc<RefCell<ExtentController>>]) -> Result<(), ValidateError> {
+        // Make sure the point is finite
+        check_finite(self.ref_point)?;
+
         // Make sure the reference is valid
         self.ref_view.validate(siblings)
     }
@@ -425,7 +437,7 @@ impl RefView {
     /// # Parameters
     /// 
     /// siblings: A slice of all the previous siblings of this view
-    fn validate(&self, siblings: &[Rc<RefCell<ExtentController>>]) -> Result<(), ValidateError> {
+    pub(super) fn validate(&self, siblings: &[Rc<RefCell<ExtentController>>]) -> Result<(), ValidateError> {
         match *self {
             // Make sure the index is within the sibling list
             Self::Id(index) => {
@@ -438,7 +450,7 @@ impl RefView {
 
             // Make sure there is a sibling if it references the previous
             Self::Prev => {
-                if siblings.len() == 0 {
+                if siblings.is_empty() {
                     Err(ValidateError::NoPrev)
                 } else {
                     Ok(())
@@ -454,11 +466,7 @@ impl RefView {
     /// range: The range to check for
     pub(crate) fn check_id_range(&self, range: &Range<usize>) -> bool {
         if let Self::Id(id) = *self {
-            if range.contains(&id) {
-                true
-            } else {
-                false
-            }
+            range.contains(&id)
         } else {
             false
         }
@@ -471,11 +479,7 @@ impl RefView {
     /// id: The ID to check
     pub(crate) fn check_id(&self, id: usize) -> bool {
         if let Self::Id(use_id) = *self {
-            if id == use_id {
-                true
-            } else {
-                false
-            }
+            id == use_id
         } else {
             false
         }
@@ -483,11 +487,20 @@ impl RefView {
 
     /// Checks if this view references the previous sibling
     pub(crate) fn check_prev(&self) -> bool {
-        if let Self::Prev = *self {
-            true
-        } else {
-            false
-        }
+        matches!(*self, Self::Prev)
+    }
+}
+
+/// Makes sure a value is neither NaN nor infinite
+/// 
+/// # Parameters
+/// 
+/// value: The value to check
+pub(super) fn check_finite(value: f32) -> Result<(), ValidateError> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(ValidateError::NonFinite)
     }
 }
 
@@ -499,11 +512,26 @@ pub enum ValidateError {
     NoPrev,
     #[error("An extent cannot use aspect mode for both dimensions")]
     BothRatio,
+    #[error("A value is NaN or infinite")]
+    NonFinite,
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum ValidateWarning {
+    #[error("The reference point {:?} is outside of the referenced view", .0)]
+    RefPointOutside(f32),
+    #[error("The relative scale {:?} is negative so the size will be clamped to 0", .0)]
+    NegativeScale(f32),
+    #[error("The set size {:?} is negative so it will be clamped to 0", .0)]
+    NegativeSize(f32),
+    #[error("The stretch ends at {:?} before it starts at {:?} so the size will be clamped to 0", .1, .0)]
+    InvertedStretch(f32, f32),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::RatioSource;
 
     fn gen_controller() -> Rc<RefCell<ExtentController>> {
         let extent_single = ExtentUpdateSingle { scale_rel: 1.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0, extent_type: ExtentUpdateType::Locate(ExtentLocate { pos: PositionType::Set(0.0), size: SizeType::Set(1.0) }) };
@@ -534,6 +562,9 @@ mod tests {
             let anchor_point = AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 };
             assert_eq!(Ok(()), anchor_point.validate(&siblings));
             assert_eq!(Err(ValidateError::NoPrev), anchor_point.validate(&siblings[..0]));
+
+            let anchor_point_nan = AnchorPoint { ref_view: RefView::Prev, ref_point: f32::NAN };
+            assert_eq!(Err(ValidateError::NonFinite), anchor_point_nan.validate(&siblings));
         }
 
         #[test]
@@ -543,6 +574,9 @@ mod tests {
             let position_type_set = PositionType::Set(0.0);
             assert_eq!(Ok(()), position_type_set.validate(&siblings));
 
+            let position_type_infinite = PositionType::Set(f32::INFINITY);
+            assert_eq!(Err(ValidateError::NonFinite), position_type_infinite.validate(&siblings));
+
             let position_type_anchor = PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 });
             assert_eq!(Ok(()), position_type_anchor.validate(&siblings));
             assert_eq!(Err(ValidateError::NoPrev), position_type_anchor.validate(&siblings[..0]));
@@ -576,6 +610,9 @@ mod tests {
 
             let size_type_set = SizeType::Set(0.0);
             assert_eq!(Ok(()), size_type_set.validate(&siblings[..0]));
+
+            let size_type_nan = SizeType::Set(f32::NAN);
+            assert_eq!(Err(ValidateError::NonFinite), size_type_nan.validate(&siblings));
         }
 
         #[test]
@@ -596,7 +633,7 @@ mod tests {
         fn extent_ratio() {
             let siblings = vec![gen_controller()];
 
-            let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }) };
+            let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }), source: RatioSource::Square };
             assert_eq!(Ok(()), extent_ratio.validate(&siblings));
             assert_eq!(Err(ValidateError::NoPrev), extent_ratio.validate(&siblings[..0]));
         }
@@ -613,7 +650,7 @@ mod tests {
             assert_eq!(Ok(()), extent_update_type_locate.validate(&siblings));
             assert_eq!(Err(ValidateError::NoPrev), extent_update_type_locate.validate(&siblings[..0]));
 
-            let extent_update_type_ratio = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }) });
+            let extent_update_type_ratio = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }), source: RatioSource::Square });
             assert_eq!(Ok(()), extent_update_type_ratio.validate(&siblings));
             assert_eq!(Err(ValidateError::NoPrev), extent_update_type_ratio.validate(&siblings[..0]));
         }
@@ -622,16 +659,19 @@ mod tests {
         fn extent_update_single() {
             let siblings = vec![gen_controller()];
 
-            let extent_update_single = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }) }), scale_rel: 0.0, scale_abs: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
+            let extent_update_single = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }), source: RatioSource::Square }), scale_rel: 0.0, scale_abs: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
             assert_eq!(Ok(()), extent_update_single.validate(&siblings));
             assert_eq!(Err(ValidateError::NoPrev), extent_update_single.validate(&siblings[..0]));
+
+            let extent_update_single_nan = ExtentUpdateSingle { offset_abs: f32::NAN, ..extent_update_single };
+            assert_eq!(Err(ValidateError::NonFinite), extent_update_single_nan.validate(&siblings));
         }
 
         #[test]
         fn extent_update() {
             let siblings = vec![gen_controller()];
 
-            let ratio = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }) });
+            let ratio = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }), source: RatioSource::Square });
             let locate = ExtentUpdateType::Locate(ExtentLocate { pos: PositionType::Set(0.0), size: SizeType::Set(0.0) });
             let extent_update_success = ExtentUpdate { x: ExtentUpdateSingle { extent_type: ratio, scale_rel: 0.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0 }, y: ExtentUpdateSingle { extent_type: locate, scale_rel: 0.0, scale_abs: 0.0, offset_rel: 0.0, offset_abs: 0.0 } };
             assert_eq!(Ok(()), extent_update_success.validate(&siblings));
@@ -715,7 +755,7 @@ mod tests {
 
         #[test]
         fn extent_ratio() {
-            let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }) };
+            let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), source: RatioSource::Square };
             assert!(!extent_ratio.check_id(0));
             assert!(extent_ratio.check_id(1));
         }
@@ -730,22 +770,22 @@ mod tests {
             assert!(!extent_update_type_locate.check_id(0));
             assert!(extent_update_type_locate.check_id(1));
 
-            let extent_update_type_ratio = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }) });
+            let extent_update_type_ratio = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), source: RatioSource::Square });
             assert!(!extent_update_type_ratio.check_id(0));
             assert!(extent_update_type_ratio.check_id(1));
         }
 
         #[test]
         fn extent_update_single() {
-            let extent_update_single = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }) }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
+            let extent_update_single = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), source: RatioSource::Square }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
             assert!(!extent_update_single.check_id(0));
             assert!(extent_update_single.check_id(1));
         }
 
         #[test]
         fn extent_update() {
-            let extent_update_single_id = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }) }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
-            let extent_update_single_set = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Set(0.0) }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
+            let extent_update_single_id = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), source: RatioSource::Square }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
+            let extent_update_single_set = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Set(0.0), source: RatioSource::Square }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
 
             let extent_update_x = ExtentUpdate { x: extent_update_single_id, y: extent_update_single_set };
             assert!(!extent_update_x.check_id(0));
@@ -763,107 +803,107 @@ mod tests {
         #[test]
         fn ref_view() {
             let ref_view_id = RefView::Id(1);
-            assert!(!ref_view_id.check_id_range(0..1));
-            assert!(ref_view_id.check_id_range(1..10));
+            assert!(!ref_view_id.check_id_range(&(0..1)));
+            assert!(ref_view_id.check_id_range(&(1..10)));
 
             let ref_view_prev = RefView::Prev;
-            assert!(!ref_view_prev.check_id_range(1..10));
+            assert!(!ref_view_prev.check_id_range(&(1..10)));
         }
 
         #[test]
         fn anchor_point() {
             let anchor_point = AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 };
-            assert!(!anchor_point.check_id_range(0..1));
-            assert!(anchor_point.check_id_range(1..10));
+            assert!(!anchor_point.check_id_range(&(0..1)));
+            assert!(anchor_point.check_id_range(&(1..10)));
         }
 
         #[test]
         fn position_type() {
             let position_type_anchor = PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 });
-            assert!(!position_type_anchor.check_id_range(0..1));
-            assert!(position_type_anchor.check_id_range(1..10));
+            assert!(!position_type_anchor.check_id_range(&(0..1)));
+            assert!(position_type_anchor.check_id_range(&(1..10)));
 
             let position_type_set = PositionType::Set(0.0);
-            assert!(!position_type_set.check_id_range(1..10));
+            assert!(!position_type_set.check_id_range(&(1..10)));
         }
 
         #[test]
         fn extent_stretch() {
             let extent_stretch_1 = ExtentStretch { pos1: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), pos2: PositionType::Set(0.0) };
-            assert!(!extent_stretch_1.check_id_range(0..1));
-            assert!(extent_stretch_1.check_id_range(1..10));
+            assert!(!extent_stretch_1.check_id_range(&(0..1)));
+            assert!(extent_stretch_1.check_id_range(&(1..10)));
 
             let extent_stretch_2 = ExtentStretch { pos1: PositionType::Set(0.0), pos2: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }) };
-            assert!(!extent_stretch_2.check_id_range(0..1));
-            assert!(extent_stretch_2.check_id_range(1..10));
+            assert!(!extent_stretch_2.check_id_range(&(0..1)));
+            assert!(extent_stretch_2.check_id_range(&(1..10)));
         }
 
         #[test]
         fn size_type() {
             let size_type_stretch = SizeType::Stretch(ExtentStretch { pos1: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), pos2: PositionType::Set(0.0) });
-            assert!(!size_type_stretch.check_id_range(0..1));
-            assert!(size_type_stretch.check_id_range(1..10));
+            assert!(!size_type_stretch.check_id_range(&(0..1)));
+            assert!(size_type_stretch.check_id_range(&(1..10)));
 
             let size_type_relative = SizeType::Relative(RefView::Id(1));
-            assert!(!size_type_relative.check_id_range(0..1));
-            assert!(size_type_relative.check_id_range(1..10));
+            assert!(!size_type_relative.check_id_range(&(0..1)));
+            assert!(size_type_relative.check_id_range(&(1..10)));
 
             let size_type_set = SizeType::Set(0.0);
-            assert!(!size_type_set.check_id_range(1..10));
+            assert!(!size_type_set.check_id_range(&(1..10)));
         }
 
         #[test]
         fn extent_locate() {
             let extent_locate_pos = ExtentLocate { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), size: SizeType::Set(0.0) };
-            assert!(!extent_locate_pos.check_id_range(0..1));
-            assert!(extent_locate_pos.check_id_range(1..10));
+            assert!(!extent_locate_pos.check_id_range(&(0..1)));
+            assert!(extent_locate_pos.check_id_range(&(1..10)));
 
             let extent_locate_size = ExtentLocate { pos: PositionType::Set(0.0), size: SizeType::Relative(RefView::Id(1)) };
-            assert!(!extent_locate_size.check_id_range(0..1));
-            assert!(extent_locate_size.check_id_range(1..10));
+            assert!(!extent_locate_size.check_id_range(&(0..1)));
+            assert!(extent_locate_size.check_id_range(&(1..10)));
         }
 
         #[test]
         fn extent_ratio() {
-            let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }) };
-            assert!(!extent_ratio.check_id_range(0..1));
-            assert!(extent_ratio.check_id_range(1..10));
+            let extent_ratio = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), source: RatioSource::Square };
+            assert!(!extent_ratio.check_id_range(&(0..1)));
+            assert!(extent_ratio.check_id_range(&(1..10)));
         }
 
         #[test]
         fn extent_update_type() {
             let extent_update_type_stretch = ExtentUpdateType::Stretch(ExtentStretch { pos1: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), pos2: PositionType::Set(0.0) });
-            assert!(!extent_update_type_stretch.check_id_range(0..1));
-            assert!(extent_update_type_stretch.check_id_range(1..10));
+            assert!(!extent_update_type_stretch.check_id_range(&(0..1)));
+            assert!(extent_update_type_stretch.check_id_range(&(1..10)));
 
             let extent_update_type_locate = ExtentUpdateType::Locate(ExtentLocate { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), size: SizeType::Set(0.0) });
-            assert!(!extent_update_type_locate.check_id_range(0..1));
-            assert!(extent_update_type_locate.check_id_range(1..10));
+            assert!(!extent_update_type_locate.check_id_range(&(0..1)));
+            assert!(extent_update_type_locate.check_id_range(&(1..10)));
 
-            let extent_update_type_ratio = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }) });
-            assert!(!extent_update_type_ratio.check_id_range(0..1));
-            assert!(extent_update_type_ratio.check_id_range(1..10));
+            let extent_update_type_ratio = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), source: RatioSource::Square });
+            assert!(!extent_update_type_ratio.check_id_range(&(0..1)));
+            assert!(extent_update_type_ratio.check_id_range(&(1..10)));
         }
 
         #[test]
         fn extent_update_single() {
-            let extent_update_single = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }) }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
-            assert!(!extent_update_single.check_id_range(0..1));
-            assert!(extent_update_single.check_id_range(1..10));
+            let extent_update_single = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), source: RatioSource::Square }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
+            assert!(!extent_update_single.check_id_range(&(0..1)));
+            assert!(extent_update_single.check_id_range(&(1..10)));
         }
 
         #[test]
         fn extent_update() {
-            let extent_update_single_id = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }) }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
-            let extent_update_single_set = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Set(0.0) }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
+            let extent_update_single_id = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Id(1), ref_point: 0.0 }), source: RatioSource::Square }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
+            let extent_update_single_set = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Set(0.0), source: RatioSource::Square }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
 
             let extent_update_x = ExtentUpdate { x: extent_update_single_id, y: extent_update_single_set };
-            assert!(!extent_update_x.check_id_range(0..1));
-            assert!(extent_update_x.check_id_range(1..10));
+            assert!(!extent_update_x.check_id_range(&(0..1)));
+            assert!(extent_update_x.check_id_range(&(1..10)));
 
             let extent_update_y = ExtentUpdate { x: extent_update_single_set, y: extent_update_single_id };
-            assert!(!extent_update_y.check_id_range(0..1));
-            assert!(extent_update_y.check_id_range(1..10));
+            assert!(!extent_update_y.check_id_range(&(0..1)));
+            assert!(extent_update_y.check_id_range(&(1..10)));
         }
     }
 
@@ -944,10 +984,10 @@ mod tests {
 
         #[test]
         fn extent_ratio() {
-            let extent_ratio_true = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }) };
+            let extent_ratio_true = ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }), source: RatioSource::Square };
             assert!(extent_ratio_true.check_prev());
 
-            let extent_ratio_false = ExtentRatio { pos: PositionType::Set(0.0) };
+            let extent_ratio_false = ExtentRatio { pos: PositionType::Set(0.0), source: RatioSource::Square };
             assert!(!extent_ratio_false.check_prev());
         }
 
@@ -965,26 +1005,26 @@ mod tests {
             let extent_update_type_locate_false = ExtentUpdateType::Locate(ExtentLocate { pos: PositionType::Set(0.0), size: SizeType::Set(0.0) });
             assert!(!extent_update_type_locate_false.check_prev());
 
-            let extent_update_type_ratio_true = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }) });
+            let extent_update_type_ratio_true = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }), source: RatioSource::Square });
             assert!(extent_update_type_ratio_true.check_prev());
 
-            let extent_update_type_ratio_false = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Set(0.0) });
+            let extent_update_type_ratio_false = ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Set(0.0), source: RatioSource::Square });
             assert!(!extent_update_type_ratio_false.check_prev());
         }
 
         #[test]
         fn extent_update_single() {
-            let extent_update_single_true = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }) }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
+            let extent_update_single_true = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }), source: RatioSource::Square }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
             assert!(extent_update_single_true.check_prev());
 
-            let extent_update_single_false = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Set(0.0) }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
+            let extent_update_single_false = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Set(0.0), source: RatioSource::Square }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
             assert!(!extent_update_single_false.check_prev());
         }
 
         #[test]
         fn extent_update() {
-            let extent_update_single_prev = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }) }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
-            let extent_update_single_set = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Set(0.0) }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
+            let extent_update_single_prev = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Anchor(AnchorPoint { ref_view: RefView::Prev, ref_point: 0.0 }), source: RatioSource::Square }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
+            let extent_update_single_set = ExtentUpdateSingle { extent_type: ExtentUpdateType::Ratio(ExtentRatio { pos: PositionType::Set(0.0), source: RatioSource::Square }), scale_abs: 0.0, scale_rel: 0.0, offset_abs: 0.0, offset_rel: 0.0 };
 
             let extent_update_x = ExtentUpdate { x: extent_update_single_prev, y: extent_update_single_set };
             assert!(extent_update_x.check_prev());
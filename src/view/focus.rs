@@ -0,0 +1,636 @@
+use crate::view::{View, style};
+use std::{cell::RefCell, rc::{Rc, Weak}, fmt};
+use thiserror::Error;
+
+/// The function run when a view gains or loses the focus
+type Callback = Rc<RefCell<Box<dyn FnMut(FocusEvent)>>>;
+
+/// The function run when a key event reaches a view, returns true if the event was handled
+type KeyHandler = Rc<RefCell<Box<dyn FnMut(&KeyEvent) -> bool>>>;
+
+/// The number of times focus requests made by focus callbacks are resolved in the same frame, further requests wait for the next frame
+const MAX_PASSES: usize = 8;
+
+/// The change of focus a view is notified about
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FocusEvent {
+    /// The view gained the focus
+    In,
+    /// The view lost the focus
+    Out,
+}
+
+/// A keyboard event sent to the focused view, it bubbles up to the ancestors until a view handles it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyEvent {
+    /// A key was pressed or released
+    Input(winit::event::KeyboardInput),
+    /// The modifier keys which are held down changed
+    Modifiers(winit::event::ModifiersState),
+    /// A character was typed
+    Character(char),
+}
+
+/// Controls whether a view can be focused and where it is in the tab order
+#[derive(Default)]
+pub struct FocusController {
+    /// True if the view can hold the focus
+    focusable: bool,
+    /// The position in the tab order, positive indices come first in ascending order, 0 follows the tree order and negative indices are skipped by tab
+    tab_index: i32,
+    /// True if the view holds the focus
+    focused: bool,
+    /// The style controller of the view, its focused state follows the focus
+    style: Weak<RefCell<style::StyleController>>,
+    /// The functions to run when the view gains or loses the focus
+    callbacks: Vec<Callback>,
+    /// The functions to run when a key event reaches the view
+    key_handlers: Vec<KeyHandler>,
+}
+
+impl FocusController {
+    /// Creates a focus controller which is not focusable
+    ///
+    /// # Parameters
+    ///
+    /// style: The style controller of the view, it gets the focused state while the view holds the focus
+    pub(crate) fn new(style: Weak<RefCell<style::StyleController>>) -> Self {
+        Self { style, ..Default::default() }
+    }
+
+    /// Checks if the view can hold the focus
+    pub fn get_focusable(&self) -> bool {
+        self.focusable
+    }
+
+    /// Sets whether the view can hold the focus, a focused view which is no longer focusable passes the focus on at the next frame
+    ///
+    /// # Parameters
+    ///
+    /// focusable: True if the view can hold the focus
+    pub fn set_focusable(&mut self, focusable: bool) {
+        self.focusable = focusable;
+    }
+
+    /// Gets the position in the tab order
+    pub fn get_tab_index(&self) -> i32 {
+        self.tab_index
+    }
+
+    /// Sets the position in the tab order
+    ///
+    /// # Parameters
+    ///
+    /// tab_index: Positive indices are visited first in ascending order with ties in tree order, 0 is visited in tree order afterwards and negative indices can only be focused directly
+    pub fn set_tab_index(&mut self, tab_index: i32) {
+        self.tab_index = tab_index;
+    }
+
+    /// Checks if the view holds the focus as of the last frame
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Marks the view as focused or not and updates the focused state of its style
+    ///
+    /// # Parameters
+    ///
+    /// focused: True if the view holds the focus
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if let Some(style) = self.style.upgrade() {
+            style.borrow_mut().set_state(style::State::FOCUSED, focused);
+        }
+    }
+
+    /// Adds a function to run whenever the view gains or loses the focus, it may push new focus operations
+    ///
+    /// # Parameters
+    ///
+    /// callback: The function to run
+    pub fn add_focus_callback<F: FnMut(FocusEvent) + 'static>(&mut self, callback: F) {
+        self.callbacks.push(Rc::new(RefCell::new(Box::new(callback))));
+    }
+
+    /// Adds a function to run when a key event reaches the view, either because it is focused or because a focused descendant did not handle it.
+    /// The handlers of a view run in the order they were added until one returns true
+    ///
+    /// # Parameters
+    ///
+    /// handler: The function to run, returns true if the event was handled and should not be passed on
+    pub fn add_key_handler<F: FnMut(&KeyEvent) -> bool + 'static>(&mut self, handler: F) {
+        self.key_handlers.push(Rc::new(RefCell::new(Box::new(handler))));
+    }
+}
+
+impl fmt::Debug for FocusController {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FocusController")
+            .field("focusable", &self.focusable)
+            .field("tab_index", &self.tab_index)
+            .field("focused", &self.focused)
+            .field("callbacks", &self.callbacks.len())
+            .field("key_handlers", &self.key_handlers.len())
+            .finish()
+    }
+}
+
+/// The different ways to move the focus
+#[derive(Clone, Debug)]
+pub enum FocusOperation {
+    /// Focus the view with this focus controller
+    Set(Rc<RefCell<FocusController>>),
+    /// Focus the next view in the tab order, wrapping around at the end
+    Next,
+    /// Focus the previous view in the tab order, wrapping around at the start
+    Prev,
+    /// Remove the focus from all views
+    Clear,
+}
+
+/// Schedules changes to the focus of a window, the operations take effect at the start of the next frame once the children are resolved.
+/// Only a single view of the window holds the focus
+#[derive(Debug, Default)]
+pub struct FocusScheduler {
+    /// The queued operations, the first element is the first operation which takes effect
+    queue: Vec<FocusOperation>,
+    /// The focus controller of the focused view
+    focused: Option<Rc<RefCell<FocusController>>>,
+    /// The focusable views in tree order as of the last frame, used to pass the focus on when the focused view is deleted
+    order: Vec<Weak<RefCell<FocusController>>>,
+}
+
+impl FocusScheduler {
+    /// Pushes an operation to the queue
+    ///
+    /// # Parameters
+    ///
+    /// operation: The operation to push
+    ///
+    /// # Errors
+    ///
+    /// FocusError::NotFocusable: If the view to focus cannot hold the focus
+    pub fn push_operation(&mut self, operation: FocusOperation) -> Result<(), FocusError> {
+        // Make sure the view can be focused
+        if let FocusOperation::Set(controller) = &operation {
+            if !controller.borrow().focusable {
+                return Err(FocusError::NotFocusable);
+            }
+        }
+
+        self.queue.push(operation);
+
+        Ok(())
+    }
+
+    /// Gets the focus controller of the focused view as of the last frame, None if no view is focused
+    pub fn get_focused(&self) -> Option<Rc<RefCell<FocusController>>> {
+        self.focused.clone()
+    }
+
+    /// Checks if a view holds the focus as of the last frame
+    ///
+    /// # Parameters
+    ///
+    /// view: The view to check
+    pub fn is_focused(&self, view: &View) -> bool {
+        self.focused.as_ref().is_some_and(|focused| Rc::ptr_eq(focused, &view.get_focus_controller()))
+    }
+
+    /// Gets the focus controllers of the focused view and all of its ancestors from the root down, empty if no view in the tree is focused
+    ///
+    /// # Parameters
+    ///
+    /// root: The root view
+    pub(crate) fn get_path(&self, root: &View) -> Vec<Rc<RefCell<FocusController>>> {
+        self.get_views(root).iter().map(|view| view.get_focus_controller()).collect()
+    }
+
+    /// Gets the owner tokens of the focused view and all of its ancestors from the root down, empty if no view in the tree is focused
+    ///
+    /// # Parameters
+    ///
+    /// root: The root view
+    pub(crate) fn get_owner_path(&self, root: &View) -> Vec<Weak<()>> {
+        self.get_views(root).iter().map(|view| view.get_owner()).collect()
+    }
+
+    /// Gets the focused view and all of its ancestors from the root down, empty if no view in the tree is focused
+    ///
+    /// # Parameters
+    ///
+    /// root: The root view
+    fn get_views<'a>(&self, root: &'a View) -> Vec<&'a View> {
+        let mut path = Vec::new();
+        if let Some(focused) = &self.focused {
+            if !find_path(root, focused, &mut path) {
+                path.clear();
+            }
+        }
+
+        path
+    }
+
+    /// Checks if there are operations on the queue
+    pub(crate) fn has_operations(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    /// Checks if the focus must be resolved even though the tree did not change, this is the case if there are operations or the focused view is no longer focusable
+    pub(crate) fn needs_resolve(&self) -> bool {
+        self.has_operations() || self.focused.as_ref().is_some_and(|focused| !focused.borrow().focusable)
+    }
+
+    /// Resolves all operations and passes the focus on if the focused view was deleted or is no longer focusable.
+    /// Returns the callbacks to notify about the change, they must be run once the scheduler is no longer borrowed
+    ///
+    /// # Parameters
+    ///
+    /// root: The root view, its children must be resolved
+    fn resolve(&mut self, root: &View) -> Vec<(Callback, FocusEvent)> {
+        let mut order = Vec::new();
+        collect(root, &mut order);
+        let old = self.focused.clone();
+
+        // A focused view which is gone passes the focus to the closest focusable view following it in the old tree order, or else preceding it
+        if let Some(focused) = &self.focused {
+            if !contains(&order, focused) {
+                let pos = self.order.iter().position(|view| std::ptr::eq(view.as_ptr(), Rc::as_ptr(focused)));
+                let following = pos.into_iter().flat_map(|pos| self.order[pos + 1..].iter().chain(self.order[..pos].iter().rev()));
+                self.focused = following.filter_map(Weak::upgrade).find(|view| contains(&order, view));
+            }
+        }
+
+        // Run the operations in order, a view which was removed or is no longer focusable is skipped
+        let tab_order = get_tab_order(&order);
+        for operation in self.queue.drain(..) {
+            match operation {
+                FocusOperation::Set(controller) if contains(&order, &controller) => self.focused = Some(controller),
+                FocusOperation::Set(_) => (),
+                FocusOperation::Next | FocusOperation::Prev if tab_order.is_empty() => (),
+
+                // Step through the tab order, a view outside of it starts from the ends
+                FocusOperation::Next => {
+                    let pos = self.focused.as_ref().and_then(|focused| tab_order.iter().position(|view| Rc::ptr_eq(view, focused)));
+                    self.focused = Some(Rc::clone(&tab_order[pos.map_or(0, |pos| (pos + 1) % tab_order.len())]));
+                }
+                FocusOperation::Prev => {
+                    let pos = self.focused.as_ref().and_then(|focused| tab_order.iter().position(|view| Rc::ptr_eq(view, focused)));
+                    self.focused = Some(Rc::clone(&tab_order[pos.map_or(tab_order.len() - 1, |pos| (pos + tab_order.len() - 1) % tab_order.len())]));
+                }
+
+                FocusOperation::Clear => self.focused = None,
+            }
+        }
+        self.order = order.iter().map(Rc::downgrade).collect();
+
+        // Notify the views which lost and gained the focus, a view which keeps the focus is not notified
+        let mut notifications = Vec::new();
+        match (&old, &self.focused) {
+            (Some(old), Some(new)) if Rc::ptr_eq(old, new) => (),
+            (old, new) => {
+                if let Some(old) = old {
+                    old.borrow_mut().set_focused(false);
+                    notifications.extend(old.borrow().callbacks.iter().map(|callback| (Rc::clone(callback), FocusEvent::Out)));
+                }
+                if let Some(new) = new {
+                    new.borrow_mut().set_focused(true);
+                    notifications.extend(new.borrow().callbacks.iter().map(|callback| (Rc::clone(callback), FocusEvent::In)));
+                }
+            }
+        }
+
+        notifications
+    }
+}
+
+/// Resolves the focus operations of a scheduler and notifies the views, operations pushed by the callbacks are resolved right away.
+/// Only needs to be run when there are operations or the tree may have changed
+///
+/// # Parameters
+///
+/// scheduler: The focus scheduler, it must not be borrowed
+///
+/// root: The root view, its children must be resolved
+pub(crate) fn resolve(scheduler: &RefCell<FocusScheduler>, root: &View) {
+    for _ in 0..MAX_PASSES {
+        let notifications = scheduler.borrow_mut().resolve(root);
+        for (callback, event) in notifications {
+            (callback.borrow_mut())(event);
+        }
+
+        if !scheduler.borrow().has_operations() {
+            break;
+        }
+    }
+}
+
+/// Sends a key event to the last view of a path and bubbles it up to the first one until it is handled.
+/// Returns true if a view handled the event
+///
+/// # Parameters
+///
+/// path: The focus controllers of the focused view and its ancestors from the root down
+///
+/// event: The event to send
+pub(crate) fn dispatch(path: &[Rc<RefCell<FocusController>>], event: &KeyEvent) -> bool {
+    for view in path.iter().rev() {
+        // The handlers are cloned so they can borrow the controller of their own view
+        let handlers = view.borrow().key_handlers.clone();
+        for handler in handlers {
+            if (handler.borrow_mut())(event) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Finds the views from a view down to one of its descendants, returns false if the descendant is not in the tree
+///
+/// # Parameters
+///
+/// view: The view to start at
+///
+/// target: The focus controller to find
+///
+/// path: The list to add the views to, it is only complete if the target is found
+fn find_path<'a>(view: &'a View, target: &Rc<RefCell<FocusController>>, path: &mut Vec<&'a View>) -> bool {
+    path.push(view);
+    if Rc::ptr_eq(&view.focus, target) || view.get_children().iter().any(|child| find_path(child, target, path)) {
+        return true;
+    }
+    path.pop();
+
+    false
+}
+
+/// Adds the focus controllers of a view and all of its descendants which can hold the focus in tree order
+///
+/// # Parameters
+///
+/// view: The view to start at
+///
+/// order: The list to add the focus controllers to
+fn collect(view: &View, order: &mut Vec<Rc<RefCell<FocusController>>>) {
+    let controller = view.get_focus_controller();
+    if controller.borrow().focusable {
+        order.push(controller);
+    }
+
+    for child in view.get_children() {
+        collect(child, order);
+    }
+}
+
+/// Checks if a focus controller is in a list
+///
+/// # Parameters
+///
+/// order: The list to search
+///
+/// controller: The controller to find
+fn contains(order: &[Rc<RefCell<FocusController>>], controller: &Rc<RefCell<FocusController>>) -> bool {
+    order.iter().any(|view| Rc::ptr_eq(view, controller))
+}
+
+/// Gets the order tab moves the focus in from the focusable views in tree order
+///
+/// # Parameters
+///
+/// order: The focusable views in tree order
+fn get_tab_order(order: &[Rc<RefCell<FocusController>>]) -> Vec<Rc<RefCell<FocusController>>> {
+    let mut indexed: Vec<(i32, Rc<RefCell<FocusController>>)> = order.iter()
+        .map(|view| (view.borrow().tab_index, Rc::clone(view)))
+        .filter(|(tab_index, _)| *tab_index >= 0)
+        .collect();
+
+    // The sort is stable so equal indices stay in tree order
+    indexed.sort_by_key(|(tab_index, _)| if *tab_index == 0 { i32::MAX } else { *tab_index });
+
+    indexed.into_iter().map(|(_, view)| view).collect()
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum FocusError {
+    #[error("The view cannot be focused since it is not focusable")]
+    NotFocusable,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{gen_set_update_info, gen_event_loop, gen_key_input};
+    use crate::view::children;
+    use winit::event::{WindowEvent, ElementState, VirtualKeyCode, ModifiersState};
+
+    /// Creates a root with focusable children with the given tab indices and returns their focus controllers
+    fn gen_root(tab_indices: &[i32]) -> (Box<View>, Vec<Rc<RefCell<FocusController>>>) {
+        let mut root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        let mut controllers = Vec::new();
+        for tab_index in tab_indices {
+            let child = View::new(gen_set_update_info(0.0, 0.5), Some(Rc::clone(&scheduler)));
+            let controller = child.get_focus_controller();
+            controller.borrow_mut().set_focusable(true);
+            controller.borrow_mut().set_tab_index(*tab_index);
+            controllers.push(controller);
+            scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+        }
+        root.resolve_children();
+
+        (root, controllers)
+    }
+
+    /// Gets the position of the focused view in a list of controllers
+    fn get_focused(scheduler: &RefCell<FocusScheduler>, controllers: &[Rc<RefCell<FocusController>>]) -> Option<usize> {
+        let focused = scheduler.borrow().get_focused()?;
+        controllers.iter().position(|controller| Rc::ptr_eq(controller, &focused))
+    }
+
+    #[test]
+    fn tab_order() {
+        let (root, views) = gen_root(&[0, 2, -1, 1, 0]);
+        let scheduler = RefCell::new(FocusScheduler::default());
+
+        // Positive indices come first, then the tree order, negative indices are skipped
+        let mut order = Vec::new();
+        for _ in 0..5 {
+            scheduler.borrow_mut().push_operation(FocusOperation::Next).unwrap();
+            resolve(&scheduler, &root);
+            order.push(get_focused(&scheduler, &views).unwrap());
+        }
+        assert_eq!(vec![3, 1, 0, 4, 3], order);
+
+        // Backwards wraps around the other way
+        scheduler.borrow_mut().push_operation(FocusOperation::Prev).unwrap();
+        resolve(&scheduler, &root);
+        assert_eq!(Some(4), get_focused(&scheduler, &views));
+
+        // Negative indices can still be focused directly, tab moves on from the start
+        scheduler.borrow_mut().push_operation(FocusOperation::Set(Rc::clone(&views[2]))).unwrap();
+        resolve(&scheduler, &root);
+        assert!(views[2].borrow().is_focused() && !views[4].borrow().is_focused());
+        scheduler.borrow_mut().push_operation(FocusOperation::Next).unwrap();
+        resolve(&scheduler, &root);
+        assert_eq!(Some(3), get_focused(&scheduler, &views));
+
+        // Views which cannot be focused are rejected
+        let other = View::new_root();
+        assert_eq!(Err(FocusError::NotFocusable), scheduler.borrow_mut().push_operation(FocusOperation::Set(other.get_focus_controller())));
+        scheduler.borrow_mut().push_operation(FocusOperation::Clear).unwrap();
+        resolve(&scheduler, &root);
+        assert!(scheduler.borrow().get_focused().is_none());
+    }
+
+    #[test]
+    fn notify() {
+        let (root, views) = gen_root(&[0, 0]);
+        let scheduler = Rc::new(RefCell::new(FocusScheduler::default()));
+        let events = Rc::new(RefCell::new(Vec::new()));
+        for (pos, view) in views.iter().enumerate() {
+            let events = Rc::clone(&events);
+            view.borrow_mut().add_focus_callback(move |event| events.borrow_mut().push((pos, event)));
+        }
+
+        // The old view is notified before the new one
+        scheduler.borrow_mut().push_operation(FocusOperation::Set(Rc::clone(&views[0]))).unwrap();
+        resolve(&scheduler, &root);
+        scheduler.borrow_mut().push_operation(FocusOperation::Next).unwrap();
+        resolve(&scheduler, &root);
+        assert_eq!(vec![(0, FocusEvent::In), (0, FocusEvent::Out), (1, FocusEvent::In)], *events.borrow());
+
+        // Focusing the focused view again sends nothing
+        scheduler.borrow_mut().push_operation(FocusOperation::Set(Rc::clone(&views[1]))).unwrap();
+        resolve(&scheduler, &root);
+        assert_eq!(3, events.borrow().len());
+
+        // A callback can move the focus on right away
+        let redirect = Rc::clone(&scheduler);
+        let target = Rc::clone(&views[1]);
+        views[0].borrow_mut().add_focus_callback(move |event| if event == FocusEvent::In {
+            redirect.borrow_mut().push_operation(FocusOperation::Set(Rc::clone(&target))).unwrap();
+        });
+        scheduler.borrow_mut().push_operation(FocusOperation::Set(Rc::clone(&views[0]))).unwrap();
+        resolve(&scheduler, &root);
+        assert_eq!(Some(1), get_focused(&scheduler, &views));
+        assert!(!scheduler.borrow().has_operations());
+    }
+
+    #[test]
+    fn bubble() {
+        let (root, views) = gen_root(&[0, 0]);
+        let scheduler = RefCell::new(FocusScheduler::default());
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let (child_received, root_received) = (Rc::clone(&received), Rc::clone(&received));
+        views[0].borrow_mut().add_key_handler(move |event| {
+            child_received.borrow_mut().push(("child", *event));
+            *event == KeyEvent::Character('a')
+        });
+        root.get_focus_controller().borrow_mut().add_key_handler(move |event| {
+            root_received.borrow_mut().push(("root", *event));
+            true
+        });
+
+        // Without focus nothing receives the events
+        assert!(!dispatch(&scheduler.borrow().get_path(&root), &KeyEvent::Character('a')));
+
+        // Handled events stop at the focused view and the others bubble up
+        scheduler.borrow_mut().push_operation(FocusOperation::Set(Rc::clone(&views[0]))).unwrap();
+        resolve(&scheduler, &root);
+        let path = scheduler.borrow().get_path(&root);
+        assert_eq!(2, path.len());
+        assert!(dispatch(&path, &KeyEvent::Character('a')));
+        assert!(dispatch(&path, &KeyEvent::Character('b')));
+        assert_eq!(vec![("child", KeyEvent::Character('a')), ("child", KeyEvent::Character('b')), ("root", KeyEvent::Character('b'))], *received.borrow());
+
+        // A view without handlers passes everything on
+        scheduler.borrow_mut().push_operation(FocusOperation::Next).unwrap();
+        resolve(&scheduler, &root);
+        assert!(dispatch(&scheduler.borrow().get_path(&root), &KeyEvent::Character('a')));
+        assert_eq!(("root", KeyEvent::Character('a')), *received.borrow().last().unwrap());
+    }
+
+    #[test]
+    fn tree_changes() {
+        let (mut root, views) = gen_root(&[0, 0, 0, 0]);
+        let children = root.get_children_scheduler();
+        let scheduler = RefCell::new(FocusScheduler::default());
+        scheduler.borrow_mut().push_operation(FocusOperation::Set(Rc::clone(&views[1]))).unwrap();
+        resolve(&scheduler, &root);
+
+        // A moved view keeps the focus and tab follows the new tree order
+        children.borrow_mut().push_operation(children::ChildrenScheduleOperation::Move(1, 3)).unwrap();
+        root.resolve_children();
+        resolve(&scheduler, &root);
+        assert_eq!(Some(1), get_focused(&scheduler, &views));
+        scheduler.borrow_mut().push_operation(FocusOperation::Prev).unwrap();
+        resolve(&scheduler, &root);
+        assert_eq!(Some(3), get_focused(&scheduler, &views));
+
+        // Deleting the focused view passes the focus to the view following it
+        children.borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(2)).unwrap();
+        root.resolve_children();
+        resolve(&scheduler, &root);
+        assert_eq!(Some(1), get_focused(&scheduler, &views));
+        assert!(views[1].borrow().is_focused() && !views[3].borrow().is_focused());
+
+        // Or to the one before it if it was the last one
+        children.borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(2)).unwrap();
+        root.resolve_children();
+        resolve(&scheduler, &root);
+        assert_eq!(Some(2), get_focused(&scheduler, &views));
+
+        // A view which is no longer focusable passes the focus on as well
+        views[2].borrow_mut().set_focusable(false);
+        resolve(&scheduler, &root);
+        assert_eq!(Some(0), get_focused(&scheduler, &views));
+        children.borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(0)).unwrap();
+        children.borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(0)).unwrap();
+        root.resolve_children();
+        resolve(&scheduler, &root);
+        assert!(scheduler.borrow().get_focused().is_none());
+    }
+
+    #[test]
+    fn event_loop() {
+        let mut event_loop = gen_event_loop(&[gen_set_update_info(0.0, 0.5), gen_set_update_info(0.5, 0.5)]);
+        event_loop.run_frame(&mut |_, _, _| ());
+
+        // Focusing a view restyles it in the same frame
+        let views: Vec<_> = event_loop.get_root().get_children().iter().map(|child| child.get_focus_controller()).collect();
+        for (view, style) in views.iter().zip(event_loop.get_root().get_children().iter().map(|child| child.get_style_controller())) {
+            view.borrow_mut().set_focusable(true);
+            view.borrow_mut().add_focus_callback(move |event| {
+                let opacity = if event == FocusEvent::In { 0.5 } else { 1.0 };
+                style.borrow_mut().set_style(style::Style { opacity, ..Default::default() });
+            });
+        }
+        let focus = event_loop.get_focus_scheduler();
+        focus.borrow_mut().push_operation(FocusOperation::Set(Rc::clone(&views[0]))).unwrap();
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(focus.borrow().is_focused(&event_loop.get_root().get_children()[0]));
+        assert!(event_loop.get_root().get_children()[0].get_style_controller().borrow().get_state().contains(style::State::FOCUSED));
+        assert_eq!(0.5, event_loop.get_root().get_children()[0].get_style().opacity);
+
+        // Tab moves forwards, shift tab backwards and releasing the key does nothing
+        event_loop.get_backend_mut().push_window_event(gen_key_input(VirtualKeyCode::Tab, ElementState::Pressed));
+        event_loop.get_backend_mut().push_window_event(gen_key_input(VirtualKeyCode::Tab, ElementState::Released));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(views[1].borrow().is_focused());
+        assert!(event_loop.get_root().get_children()[0].get_style_controller().borrow().get_state().is_empty());
+        assert_eq!(1.0, event_loop.get_root().get_children()[0].get_style().opacity);
+        assert_eq!(0.5, event_loop.get_root().get_children()[1].get_style().opacity);
+        event_loop.get_backend_mut().push_window_event(WindowEvent::ModifiersChanged(ModifiersState::SHIFT));
+        event_loop.get_backend_mut().push_window_event(gen_key_input(VirtualKeyCode::Tab, ElementState::Pressed));
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(views[0].borrow().is_focused());
+
+        // Deleting the focused view passes the focus on
+        event_loop.get_root().get_children_scheduler().borrow_mut().push_operation(children::ChildrenScheduleOperation::Delete(0)).unwrap();
+        event_loop.run_frame(&mut |_, _, _| ());
+        assert!(views[1].borrow().is_focused());
+        assert_eq!(0.5, event_loop.get_root().get_children()[0].get_style().opacity);
+    }
+}
@@ -1,17 +1,36 @@
 pub mod extent;
 pub mod children;
+pub mod style;
+pub mod svg;
+pub mod focus;
 
-use std::{cell::RefCell, rc::Rc};
+use crate::render;
+use std::{cell::RefCell, rc::{Rc, Weak}, time::Instant};
+use thiserror::Error;
 
 /// A view struct containing all the information of a single view
 #[derive(Clone, Debug)]
 pub struct View { 
     /// A vector containing all of the children of the view, children cannot be removed only added
     children: children::Children,
-    /// The current extent of the view, this is relative to its parent, (0, 0) to (1, 1) would be the entire parent extent
+    /// The current extent of the view, this is relative to its parent, (0, 0) to (1, 1) would be the entire parent extent inside its padding
     extent: extent::Extent,
+    /// The style of the view
+    style: Rc<RefCell<style::StyleController>>,
+    /// Whether the view can be focused and where it is in the tab order
+    focus: Rc<RefCell<focus::FocusController>>,
     /// The position of this view in the parents child list
     sibling_id: Option<usize>,
+    /// The content drawn in the extent of the view
+    drawable: Rc<RefCell<render::DrawableController>>,
+    /// True if the children should not be drawn outside the extent of this view
+    clip: bool,
+    /// True if the clipping changed since the last update
+    dirty: bool,
+    /// What the view covered in the window at the last update, None if it has not been updated yet
+    painted: Option<Painted>,
+    /// A token only the view holds, it is dropped together with the view even while its controllers are still shared
+    owner: Owner,
 }
 
 impl View {
@@ -45,8 +64,15 @@ impl View {
         let children = children::Children::new(parent_scheduler);
         let extent = extent::Extent::new(update_info);
         let sibling_id = None;
+        let style = Rc::new(RefCell::new(style::StyleController::default()));
+        let focus = Rc::new(RefCell::new(focus::FocusController::new(Rc::downgrade(&style))));
+        let drawable = Rc::new(RefCell::new(render::DrawableController::default()));
+        let clip = false;
+        let dirty = true;
+        let painted = None;
+        let owner = Owner::default();
 
-        Box::new(Self { children, extent, sibling_id })
+        Box::new(Self { children, extent, style, focus, sibling_id, drawable, clip, dirty, painted, owner })
     }
 
     /// Gets the extent controller
@@ -54,28 +80,477 @@ impl View {
         self.extent.get_controller()
     }
 
+    /// Gets a weak reference which is only alive as long as the view exists, things tied to the view stop once it is gone
+    pub(crate) fn get_owner(&self) -> Weak<()> {
+        Rc::downgrade(&self.owner.0)
+    }
+
     /// Gets the children scheduler
     pub fn get_children_scheduler(&self) -> Rc<RefCell<children::ChildrenScheduler>> {
         self.children.get_scheduler()
     }
 
+    /// Gets the style controller
+    pub fn get_style_controller(&self) -> Rc<RefCell<style::StyleController>> {
+        Rc::clone(&self.style)
+    }
+
+    /// Gets the focus controller
+    pub fn get_focus_controller(&self) -> Rc<RefCell<focus::FocusController>> {
+        Rc::clone(&self.focus)
+    }
+
+    /// Gets a copy of the current style
+    pub fn get_style(&self) -> style::Style {
+        self.style.borrow().get_style()
+    }
+
+    /// Gets the extent relative to the parent as (x, y, w, h), (0, 0, 1, 1) would be the entire parent extent inside its padding
+    pub fn get_extent(&self) -> (f32, f32, f32, f32) {
+        self.extent.get()
+    }
+
+    /// Gets the position of this view in the parents child list as of the last update, None for the root
+    pub fn get_sibling_id(&self) -> Option<usize> {
+        self.sibling_id
+    }
+
+    /// Gets the drawable controller, the content can be changed through it at any time
+    pub fn get_drawable_controller(&self) -> Rc<RefCell<render::DrawableController>> {
+        Rc::clone(&self.drawable)
+    }
+
+    /// Gets the content drawn in the extent of the view
+    pub fn get_drawable(&self) -> Option<Rc<dyn render::Drawable>> {
+        self.drawable.borrow().get_drawable()
+    }
+
+    /// Checks if the children are clipped to the extent of this view when drawn
+    pub fn get_clip(&self) -> bool {
+        self.clip
+    }
+
+    /// Sets whether the children are clipped to the extent of this view when drawn
+    /// 
+    /// # Parameters
+    /// 
+    /// clip: True if nothing from the children should be drawn outside this view
+    pub fn set_clip(&mut self, clip: bool) {
+        if clip != self.clip {
+            self.clip = clip;
+            self.dirty = true;
+        }
+    }
+
+    /// Gets the area of the window the view covered at the last update in pixels, None if it has not been updated yet
+    pub(crate) fn get_rect(&self) -> Option<render::Rect> {
+        self.painted.map(|painted| painted.rect)
+    }
+
+    /// Gets the area of the window the children were placed in at the last update in pixels, this is the area inside the padding
+    pub(crate) fn get_content_rect(&self) -> Option<render::Rect> {
+        let padding = self.get_style().padding.content_extent();
+        self.get_rect().map(|rect| rect.sub_rect(padding))
+    }
+
+    /// Gets the list of current children, scheduled changes are not visible until they have been resolved
+    pub(crate) fn get_children(&self) -> &[Box<View>] {
+        self.children.get_list()
+    }
+
     /// Resolves all updates to the children
     pub(crate) fn resolve_children(&mut self) {
         self.children.resolve()
     }
 
-    /// Updates the list extent and graphics, should be run once in the event loop once all the user events are handled
-    pub(crate) fn update(&self) {
-        todo!();
+    /// Updates the list extent and graphics, should be run once in the event loop once all the user events are handled.
+    /// The view is treated as the root.
+    /// 
+    /// If a view has a missing reference the update stops with an error, that view and all views not yet updated keep their old extent
+    /// 
+    /// # Parameters
+    /// 
+    /// window_size: The size of the window in pixels as (w, h)
+    /// 
+    /// time: The time of the frame, animated extents are shown as they are at this time
+    /// 
+    /// damage: The damage to add the areas of the window which must be repainted to
+    pub(crate) fn update(&mut self, window_size: (f32, f32), time: Instant, damage: &mut render::Damage) -> Result<(), UpdateError> {
+        self.resolve_children();
+        self.update_tree(&[], render::Rect::new(0.0, 0.0, window_size.0, window_size.1), &mut Vec::new(), time, damage)?;
+
+        Ok(())
+    }
+
+    /// Updates the extent of this view and all of its children.
+    /// 
+    /// Returns the area of the window covered by this view and its children
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: All the older siblings
+    /// 
+    /// parent_rect: The area of the parent this view is placed in, in pixels of the window
+    /// 
+    /// path: The child indices from the root to this view
+    /// 
+    /// time: The time of the frame
+    /// 
+    /// damage: The damage to add the areas of the window which must be repainted to
+    fn update_tree(&mut self, siblings: &[Box<View>], parent_rect: render::Rect, path: &mut Vec<usize>, time: Instant, damage: &mut render::Damage) -> Result<render::Rect, UpdateError> {
+        // The children are placed inside the padding
+        let mut style = self.style.borrow_mut();
+        let padding = style.get_style().padding.content_extent();
+        let dirty = style.is_dirty() || self.dirty;
+        style.clear_dirty();
+        drop(style);
+        let mut drawable = self.drawable.borrow_mut();
+        let dirty = dirty || drawable.is_dirty();
+        drawable.clear_dirty();
+        let drawable = drawable.get_drawable();
+        self.dirty = false;
+
+        let content = extent::Content::new(drawable.as_deref(), (parent_rect.w, parent_rect.h), (padding.2, padding.3));
+        if let Err(err) = self.extent.update(siblings, &content, time) {
+            return Err(UpdateError::InvalidReference(path.clone(), err));
+        }
+        let rect = parent_rect.sub_rect(self.extent.get());
+        let content_rect = rect.sub_rect(padding);
+
+        // Update the children in order so all older siblings are ready
+        let mut children_bounds = None;
+        let children = self.children.get_list_mut();
+        for pos in 0..children.len() {
+            let (older, younger) = children.split_at_mut(pos);
+            younger[0].sibling_id = Some(pos);
+            path.push(pos);
+            let child_bounds = younger[0].update_tree(older, content_rect, path, time, damage)?;
+            path.pop();
+            children_bounds = Some(children_bounds.map_or(child_bounds, |bounds: render::Rect| bounds.union(&child_bounds)));
+        }
+
+        // The children may reach outside of the view unless they are clipped
+        let bounds = match children_bounds {
+            Some(children_bounds) if self.clip => rect.union(&children_bounds.intersect(&rect)),
+            Some(children_bounds) => rect.union(&children_bounds),
+            None => rect,
+        };
+
+        // Repaint everything the view affects if it changed, a view which only moved affects its own area and the children take care of themselves
+        let children_changed = self.children.take_changed();
+        match self.painted {
+            Some(painted) if dirty || children_changed => {
+                damage.add(painted.bounds);
+                damage.add(bounds);
+            }
+            Some(painted) if painted.rect != rect => {
+                damage.add(painted.rect);
+                damage.add(rect);
+            }
+            Some(_) => (),
+            None => damage.add(bounds),
+        }
+        self.painted = Some(Painted { rect, bounds });
+
+        Ok(bounds)
+    }
+
+    /// Checks if the view or any of its descendants changed since the last update in a way which needs a new layout or repaint.
+    /// Changes to the content of a drawable itself are not seen, the drawable must be set again or marked dirty through its controller
+    pub(crate) fn needs_update(&self) -> bool {
+        let style = self.style.borrow();
+        let changed = self.dirty || style.is_dirty() || self.drawable.borrow().is_dirty() || style.needs_restyle() || self.extent.needs_update() || self.children.is_pending();
+
+        changed || self.get_children().iter().any(|child| child.needs_update())
+    }
+
+    /// Checks if the extent of the view or any of its resolved children is animating, the views must be updated every frame until it is done
+    pub fn is_animating(&self) -> bool {
+        self.extent.borrow_controller().is_animating() || self.get_children().iter().any(|child| child.is_animating())
+    }
+
+    /// Finds all problems in the view and all of its children, unlike validate this does not stop at the first problem.
+    /// The view is treated as the root and only resolved children are checked
+    pub fn diagnose(&self) -> extent::DiagnosticReport {
+        let mut report = extent::DiagnosticReport::new();
+        self.diagnose_tree(&[], &mut Vec::new(), &mut report.diagnostics);
+
+        report
     }
 
-    /// Validates the view
-    pub(crate) fn validate(&self, siblings: &[Rc<RefCell<extent::ExtentController>>]) -> Result<(), extent::ValidateError> {
-        self.extent.borrow_controller().validate(siblings)
+    /// Adds all problems in the view and its children to the list of diagnostics
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: The extent controllers of all the older siblings
+    /// 
+    /// path: The child indices from the root to this view
+    /// 
+    /// diagnostics: The list to add the problems to
+    fn diagnose_tree(&self, siblings: &[Rc<RefCell<extent::ExtentController>>], path: &mut Vec<usize>, diagnostics: &mut Vec<extent::Diagnostic>) {
+        diagnostics.extend(self.extent.borrow_controller().diagnose(siblings, path));
+
+        // Check all the children
+        let children_controllers: Vec<Rc<RefCell<extent::ExtentController>>> = self.get_children().iter().map(|child| child.get_extent_controller()).collect();
+        for (pos, child) in self.get_children().iter().enumerate() {
+            path.push(pos);
+            child.diagnose_tree(&children_controllers[..pos], path, diagnostics);
+            path.pop();
+        }
     }
+
+    /// Validates the view and all of its children
+    /// 
+    /// # Parameters
+    /// 
+    /// siblings: The extent controllers of all the older siblings
+    pub(crate) fn validate(&self, siblings: &[Rc<RefCell<extent::ExtentController>>]) -> Result<(), children::ValidateError> {
+        self.extent.borrow_controller().validate(siblings)?;
+        self.validate_children(&mut Vec::new())
+    }
+
+    /// Validates all the children and their children
+    /// 
+    /// # Parameters
+    /// 
+    /// path: The child indices from this view to the current view
+    fn validate_children(&self, path: &mut Vec<usize>) -> Result<(), children::ValidateError> {
+        let children_controllers: Vec<Rc<RefCell<extent::ExtentController>>> = self.get_children().iter().map(|child| child.get_extent_controller()).collect();
+        for (pos, child) in self.get_children().iter().enumerate() {
+            path.push(pos);
+
+            if let Err(err) = child.extent.borrow_controller().validate(&children_controllers[..pos]) {
+                return Err(children::ValidateError::InvalidDescendant(path.clone(), err));
+            }
+            child.validate_children(path)?;
+
+            path.pop();
+        }
+
+        Ok(())
+    }
+}
+
+/// The area of the window a view covered when it was last updated
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Painted {
+    /// The extent of the view in pixels of the window
+    rect: render::Rect,
+    /// The area covered by the view and all of its children in pixels of the window
+    bounds: render::Rect,
+}
+
+/// The token held by a single view, weak references to it tell whether the view still exists
+#[derive(Debug, Default)]
+struct Owner(Rc<()>);
+
+impl Clone for Owner {
+    // A copy of a view is a different view, so it gets its own token
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum UpdateError {
+    #[error("The view at {:?} could not be updated because of an invalid reference: {:?}", .0, .1)]
+    InvalidReference(Vec<usize>, extent::ValidateError),
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::fixtures::{gen_update_info, gen_locate, gen_ratio};
+
+    fn push_child(parent: &View, update_info: extent::ExtentUpdate) {
+        let scheduler = parent.get_children_scheduler();
+        let child = View::new(update_info, Some(Rc::clone(&scheduler)));
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+    }
+
+    #[test]
+    fn validate() {
+        let mut root = View::new_root();
+        push_child(&root, gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(0.5)));
+        root.resolve_children();
+        push_child(&root.get_children()[0], gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(0.5)));
+        root.resolve_children();
+        push_child(&root.get_children()[0].get_children()[0], gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(0.5)));
+        root.resolve_children();
+        assert!(root.validate(&[]).is_ok());
+
+        // Break the reference of a grandchild
+        let update_info_invalid = gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Relative(extent::RefView::Prev));
+        root.get_children()[0].get_children()[0].get_children()[0].get_extent_controller().borrow_mut().set_update_info(update_info_invalid);
+        assert!(matches!(root.validate(&[]), Err(children::ValidateError::InvalidDescendant(path, extent::ValidateError::NoPrev)) if path == vec![0, 0, 0]));
+
+        // An invalid subtree cannot be attached
+        let other = View::new_root();
+        let scheduler = other.get_children_scheduler();
+        let subtree = root.get_children()[0].clone();
+        assert!(matches!(scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(subtree)), Err(children::ValidateError::InvalidDescendant(path, extent::ValidateError::NoPrev)) if path == vec![0, 0]));
+    }
+
+    #[test]
+    fn update() {
+        let mut root = View::new_root();
+        push_child(&root, gen_update_info(extent::PositionType::Set(0.25), extent::SizeType::Set(0.5)));
+        push_child(&root, gen_update_info(extent::PositionType::Anchor(extent::AnchorPoint { ref_view: extent::RefView::Prev, ref_point: 1.0 }), extent::SizeType::Relative(extent::RefView::Id(0))));
+        root.update((2.0, 1.0), Instant::now(), &mut render::Damage::new()).unwrap();
+        assert_eq!((0.0, 0.0, 1.0, 1.0), root.get_extent());
+        assert_eq!((0.25, 0.25, 0.5, 0.5), root.get_children()[0].get_extent());
+        assert_eq!((0.75, 0.75, 0.5, 0.5), root.get_children()[1].get_extent());
+        assert_eq!(Some(1), root.get_children()[1].get_sibling_id());
+
+        // A missing reference gives an error instead of panicking
+        let update_info_invalid = gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Relative(extent::RefView::Id(3)));
+        root.get_children()[1].get_extent_controller().borrow_mut().set_update_info(update_info_invalid);
+        assert_eq!(Err(UpdateError::InvalidReference(vec![1], extent::ValidateError::InvalidId(3, 1))), root.update((2.0, 1.0), Instant::now(), &mut render::Damage::new()));
+        assert_eq!((0.75, 0.75, 0.5, 0.5), root.get_children()[1].get_extent());
+
+        // Children of a view without any area are laid out as if it was square
+        let update_info_ratio = extent::ExtentUpdate { x: gen_ratio(extent::PositionType::Set(0.0), extent::RatioSource::Square), ..gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(0.5)) };
+        root.get_children()[0].get_extent_controller().borrow_mut().set_update_info(gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(0.0)));
+        push_child(&root.get_children()[0], update_info_ratio);
+        root.get_children()[1].get_extent_controller().borrow_mut().set_update_info(gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(0.0)));
+        root.update((2.0, 1.0), Instant::now(), &mut render::Damage::new()).unwrap();
+        assert_eq!((0.0, 0.0, 0.0, 0.0), root.get_children()[0].get_extent());
+        assert_eq!((0.0, 0.0, 0.5, 0.5), root.get_children()[0].get_children()[0].get_extent());
+
+        // Padding changes the ratio of the space the children are placed in and marks the view until the next update
+        root.get_children()[0].get_extent_controller().borrow_mut().set_update_info(gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(1.0)));
+        let padding = style::Insets::new(0.0, 0.0, 0.5, 0.0);
+        root.get_children()[0].get_style_controller().borrow_mut().set_style(style::Style { padding, ..style::Style::default() });
+        assert!(root.get_children()[0].get_style_controller().borrow().is_dirty());
+        root.update((2.0, 1.0), Instant::now(), &mut render::Damage::new()).unwrap();
+        assert!(!root.get_children()[0].get_style_controller().borrow().is_dirty());
+        assert_eq!((0.0, 0.0, 0.5, 0.5), root.get_children()[0].get_children()[0].get_extent());
+        root.get_children()[0].get_style_controller().borrow_mut().set_style(style::Style { padding: style::Insets::new(0.0, 0.0, 0.0, 0.5), ..style::Style::default() });
+        root.update((2.0, 1.0), Instant::now(), &mut render::Damage::new()).unwrap();
+        assert_eq!((0.0, 0.0, 0.125, 0.5), root.get_children()[0].get_children()[0].get_extent());
+    }
+
+    #[test]
+    fn animate() {
+        use crate::animation::{Motion, Transition, Easing};
+        use std::time::Duration;
+
+        let mut root = View::new_root();
+        push_child(&root, gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(0.5)));
+        push_child(&root, gen_update_info(extent::PositionType::Anchor(extent::AnchorPoint { ref_view: extent::RefView::Prev, ref_point: 1.0 }), extent::SizeType::Set(0.25)));
+        let start = Instant::now();
+        root.update((1.0, 1.0), start, &mut render::Damage::new()).unwrap();
+
+        // Changing the update info moves the extent over the duration of the transition
+        let controller = root.get_children()[0].get_extent_controller();
+        controller.borrow_mut().set_motion(Some(Motion::Transition(Transition::new(Duration::from_millis(100), Easing::Linear))));
+        controller.borrow_mut().set_update_info(gen_update_info(extent::PositionType::Set(0.5), extent::SizeType::Set(0.5)));
+        root.update((1.0, 1.0), start, &mut render::Damage::new()).unwrap();
+        assert_eq!((0.0, 0.0, 0.5, 0.5), root.get_children()[0].get_extent());
+        assert!(root.is_animating());
+
+        // Siblings anchored to the animating view follow it
+        let mut damage = render::Damage::new();
+        root.update((1.0, 1.0), start + Duration::from_millis(50), &mut damage).unwrap();
+        assert_eq!((0.25, 0.25, 0.5, 0.5), root.get_children()[0].get_extent());
+        assert_eq!((0.75, 0.75, 0.25, 0.25), root.get_children()[1].get_extent());
+        assert!(!damage.is_empty());
+
+        // Retargeting starts from where the view is shown
+        controller.borrow_mut().set_update_info(gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(0.25)));
+        root.update((1.0, 1.0), start + Duration::from_millis(50), &mut render::Damage::new()).unwrap();
+        assert_eq!((0.25, 0.25, 0.5, 0.5), root.get_children()[0].get_extent());
+        root.update((1.0, 1.0), start + Duration::from_millis(100), &mut render::Damage::new()).unwrap();
+        assert_eq!((0.125, 0.125, 0.375, 0.375), root.get_children()[0].get_extent());
 
+        // The animation ends at the new extent
+        root.update((1.0, 1.0), start + Duration::from_millis(150), &mut render::Damage::new()).unwrap();
+        assert_eq!((0.0, 0.0, 0.25, 0.25), root.get_children()[0].get_extent());
+        assert!(!root.is_animating());
+
+        // Cancelling jumps to the new extent
+        controller.borrow_mut().set_update_info(gen_update_info(extent::PositionType::Set(0.5), extent::SizeType::Set(0.5)));
+        root.update((1.0, 1.0), start + Duration::from_millis(200), &mut render::Damage::new()).unwrap();
+        controller.borrow_mut().cancel_animation();
+        root.update((1.0, 1.0), start + Duration::from_millis(210), &mut render::Damage::new()).unwrap();
+        assert_eq!((0.5, 0.5, 0.5, 0.5), root.get_children()[0].get_extent());
+        assert!(!root.is_animating());
+
+        // Without a transition the extent jumps
+        controller.borrow_mut().set_motion(None);
+        controller.borrow_mut().set_update_info(gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(0.5)));
+        root.update((1.0, 1.0), start + Duration::from_millis(220), &mut render::Damage::new()).unwrap();
+        assert_eq!((0.0, 0.0, 0.5, 0.5), root.get_children()[0].get_extent());
+    }
+
+    #[test]
+    fn spring() {
+        use crate::animation::{Motion, Spring};
+        use std::time::Duration;
+
+        let mut root = View::new_root();
+        push_child(&root, gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(0.5)));
+        let start = Instant::now();
+        root.update((1.0, 1.0), start, &mut render::Damage::new()).unwrap();
+
+        // The spring pulls the view towards the new extent until it settles
+        let controller = root.get_children()[0].get_extent_controller();
+        controller.borrow_mut().set_motion(Some(Motion::Spring(Spring::critically_damped(400.0, 1.0))));
+        controller.borrow_mut().set_update_info(gen_update_info(extent::PositionType::Set(0.5), extent::SizeType::Set(0.5)));
+        let mut frame = 0;
+        let mut last = 0.0;
+        while frame == 0 || root.is_animating() {
+            frame += 1;
+            root.update((1.0, 1.0), start + Duration::from_millis(16 * frame), &mut render::Damage::new()).unwrap();
+            let x = root.get_children()[0].get_extent().0;
+            assert!(x >= last && x <= 0.5);
+            last = x;
+        }
+        assert!(frame > 5 && frame < 120);
+        assert_eq!((0.5, 0.5, 0.5, 0.5), root.get_children()[0].get_extent());
+        assert_eq!((0.0, 0.0, 0.0, 0.0), controller.borrow().get_velocity());
+
+        // A handed off speed moves the view from the next frame on even without a new target
+        controller.borrow_mut().set_velocity((5.0, 0.0, 0.0, 0.0));
+        root.update((1.0, 1.0), start + Duration::from_millis(16 * frame), &mut render::Damage::new()).unwrap();
+        assert_eq!(0.5, root.get_children()[0].get_extent().0);
+        frame += 1;
+        root.update((1.0, 1.0), start + Duration::from_millis(16 * frame), &mut render::Damage::new()).unwrap();
+        assert!(root.get_children()[0].get_extent().0 > 0.5);
+        assert!(controller.borrow().get_velocity().0 > 0.0);
+        assert!(root.is_animating());
+
+        // Retargeting keeps the speed
+        controller.borrow_mut().set_update_info(gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(0.5)));
+        frame += 1;
+        root.update((1.0, 1.0), start + Duration::from_millis(16 * frame), &mut render::Damage::new()).unwrap();
+        assert!(root.get_children()[0].get_extent().0 > 0.5);
+        controller.borrow_mut().cancel_animation();
+        root.update((1.0, 1.0), start + Duration::from_millis(16 * frame), &mut render::Damage::new()).unwrap();
+        assert_eq!((0.0, 0.0, 0.5, 0.5), root.get_children()[0].get_extent());
+    }
+
+    #[test]
+    fn diagnose() {
+        let mut root = View::new_root();
+        assert!(root.diagnose().is_empty());
+
+        // Add two children and break the references of the second one
+        let update_info = gen_update_info(extent::PositionType::Set(0.0), extent::SizeType::Set(1.0));
+        let scheduler = root.get_children_scheduler();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(View::new(update_info, Some(Rc::clone(&scheduler))))).unwrap();
+        scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(View::new(update_info, Some(Rc::clone(&scheduler))))).unwrap();
+        root.resolve_children();
+
+        let locate_invalid = extent::ExtentLocate { pos: extent::PositionType::Anchor(extent::AnchorPoint { ref_view: extent::RefView::Id(4), ref_point: 0.0 }), size: extent::SizeType::Relative(extent::RefView::Id(1)) };
+        let update_info_invalid = extent::ExtentUpdate { x: update_info.x, y: gen_locate(locate_invalid.pos, locate_invalid.size) };
+        root.get_children()[1].get_extent_controller().borrow_mut().set_update_info(update_info_invalid);
+
+        let report = root.diagnose();
+        assert_eq!(2, report.diagnostics.len());
+        assert_eq!(vec![1], report.diagnostics[0].path);
+        assert_eq!("y.extent_type.Locate.pos.Anchor.ref_view", report.diagnostics[0].field_path());
+        assert_eq!(extent::DiagnosticKind::Error(extent::ValidateError::InvalidId(4, 1)), report.diagnostics[0].kind);
+        assert_eq!("y.extent_type.Locate.size.Relative", report.diagnostics[1].field_path());
+    }
 }
\ No newline at end of file
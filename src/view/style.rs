@@ -0,0 +1,425 @@
+use crate::render::Color;
+use serde::{Serialize, Deserialize};
+
+/// Describes how the view itself is drawn around its content and how much space its children get
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Style {
+    /// The color covering the extent of the view below the content, None to draw nothing
+    pub background: Option<Color>,
+    /// The width of the border in pixels, it is drawn inside the extent of the view
+    pub border_width: f32,
+    /// The color of the border
+    pub border_color: Color,
+    /// The radius of the corners of the background and border in pixels
+    pub corner_radius: f32,
+    /// The opacity of the view and all of its children, 0 is invisible and 1 is fully opaque
+    pub opacity: f32,
+    /// The space between the edges of the view and the area the content and the children are placed in
+    pub padding: Insets,
+    /// The color of text in the view, this is inherited by the children when styled by a theme
+    pub text_color: Color,
+}
+
+impl Style {
+    /// Creates the style a child starts out with before its own properties are applied, only the inheritable properties are kept
+    pub fn inherited(&self) -> Self {
+        Self { text_color: self.text_color, ..Self::default() }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self { background: None, border_width: 0.0, border_color: Color::TRANSPARENT, corner_radius: 0.0, opacity: 1.0, padding: Insets::default(), text_color: Color::BLACK }
+    }
+}
+
+/// A set of style properties where each property is optional, only the properties which are set are changed when applied to a style
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StyleProperties {
+    /// The color covering the extent of the view below the content
+    pub background: Option<Color>,
+    /// The width of the border in pixels
+    pub border_width: Option<f32>,
+    /// The color of the border
+    pub border_color: Option<Color>,
+    /// The radius of the corners in pixels
+    pub corner_radius: Option<f32>,
+    /// The opacity of the view and all of its children
+    pub opacity: Option<f32>,
+    /// The space between the edges of the view and its content
+    pub padding: Option<Insets>,
+    /// The color of text in the view
+    pub text_color: Option<Color>,
+}
+
+impl StyleProperties {
+    /// Replaces the properties of the style which are set
+    /// 
+    /// # Parameters
+    /// 
+    /// style: The style to change
+    pub fn apply_to(&self, style: &mut Style) {
+        if let Some(background) = self.background {
+            style.background = Some(background);
+        }
+        if let Some(border_width) = self.border_width {
+            style.border_width = border_width;
+        }
+        if let Some(border_color) = self.border_color {
+            style.border_color = border_color;
+        }
+        if let Some(corner_radius) = self.corner_radius {
+            style.corner_radius = corner_radius;
+        }
+        if let Some(opacity) = self.opacity {
+            style.opacity = opacity;
+        }
+        if let Some(padding) = self.padding {
+            style.padding = padding;
+        }
+        if let Some(text_color) = self.text_color {
+            style.text_color = text_color;
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// The interaction states of a view which themes can select on
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct State: u8 {
+        /// The pointer is above the view
+        const HOVERED = 1 << 0;
+        /// The view receives keyboard input
+        const FOCUSED = 1 << 1;
+        /// The view is being pressed
+        const PRESSED = 1 << 2;
+        /// The view is being dragged
+        const DRAGGED = 1 << 3;
+        /// The view is being dragged to a place it cannot be dropped at
+        const REJECTED = 1 << 4;
+    }
+}
+
+/// Distances from each edge of a view as fractions of the size of the view, left and right are relative to the width and top and bottom to the height
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Insets {
+    /// The distance from the left edge
+    pub left: f32,
+    /// The distance from the top edge
+    pub top: f32,
+    /// The distance from the right edge
+    pub right: f32,
+    /// The distance from the bottom edge
+    pub bottom: f32,
+}
+
+impl Insets {
+    /// Creates new insets
+    /// 
+    /// # Parameters
+    /// 
+    /// left: The distance from the left edge
+    /// 
+    /// top: The distance from the top edge
+    /// 
+    /// right: The distance from the right edge
+    /// 
+    /// bottom: The distance from the bottom edge
+    pub const fn new(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        Self { left, top, right, bottom }
+    }
+
+    /// Creates insets with the same distance from every edge
+    /// 
+    /// # Parameters
+    /// 
+    /// value: The distance from each edge
+    pub const fn uniform(value: f32) -> Self {
+        Self::new(value, value, value, value)
+    }
+
+    /// Gets the area inside the insets as (x, y, w, h) relative to the view, non-finite insets are treated as 0 and the area never gets a negative size
+    pub fn content_extent(&self) -> (f32, f32, f32, f32) {
+        let sanitize = |value: f32| if value.is_finite() { value } else { 0.0 };
+        let (left, top, right, bottom) = (sanitize(self.left), sanitize(self.top), sanitize(self.right), sanitize(self.bottom));
+
+        (left, top, (1.0 - left - right).max(0.0), (1.0 - top - bottom).max(0.0))
+    }
+}
+
+/// A container for the style of a view and the information themes use to select it, changing the style marks the view as dirty
+#[derive(Clone, Debug, PartialEq)]
+pub struct StyleController {
+    /// The current style without the animated properties
+    style: Style,
+    /// The properties which are applied on top of the theme
+    overrides: StyleProperties,
+    /// The properties set by timelines, they are applied on top of the style and are kept when a theme restyles the view
+    animated: StyleProperties,
+    /// The kind of view, for example "button"
+    tag: Option<String>,
+    /// The classes of the view
+    classes: Vec<String>,
+    /// The current interaction state
+    state: State,
+    /// True if the style has changed since the last update
+    dirty: bool,
+    /// True if the overrides, tag, classes or state have changed since the last update so a theme may style the view differently
+    restyle: bool,
+}
+
+impl StyleController {
+    /// Creates a new style controller, it starts out dirty since it has never been drawn
+    /// 
+    /// # Parameters
+    /// 
+    /// style: The initial style
+    pub(crate) fn new(style: Style) -> Self {
+        Self { style, overrides: StyleProperties::default(), animated: StyleProperties::default(), tag: None, classes: Vec::new(), state: State::empty(), dirty: true, restyle: true }
+    }
+
+    /// Gets a copy of the current style with the animated properties applied
+    pub fn get_style(&self) -> Style {
+        let mut style = self.style;
+        self.animated.apply_to(&mut style);
+
+        style
+    }
+
+    /// Replaces the style and marks the view as dirty if it changed, the animated properties stay on top of it.
+    /// If a theme is used the style is replaced by the theme during the next update, use the overrides instead
+    /// 
+    /// # Parameters
+    /// 
+    /// style: The new style
+    pub fn set_style(&mut self, style: Style) {
+        if style != self.style {
+            self.set_themed_style(style);
+            self.restyle = true;
+        }
+    }
+
+    /// Replaces the style with the one resolved by a theme and marks the view as dirty if the shown style changed
+    /// 
+    /// # Parameters
+    /// 
+    /// style: The new style
+    pub(crate) fn set_themed_style(&mut self, style: Style) {
+        let old = self.get_style();
+        self.style = style;
+        self.dirty |= self.get_style() != old;
+    }
+
+    /// Gets the properties set by timelines
+    pub fn get_animated(&self) -> StyleProperties {
+        self.animated
+    }
+
+    /// Sets the properties which are applied on top of the style, timelines animate the view through them.
+    /// They are kept until they are changed again, set them to the default to show the style again
+    /// 
+    /// # Parameters
+    /// 
+    /// animated: The new properties
+    pub fn set_animated(&mut self, animated: StyleProperties) {
+        let old = self.get_style();
+        self.animated = animated;
+        self.dirty |= self.get_style() != old;
+    }
+
+    /// Gets the properties which are applied on top of the theme
+    pub fn get_overrides(&self) -> StyleProperties {
+        self.overrides
+    }
+
+    /// Sets the properties which are applied on top of the theme, they take effect the next time a theme is applied
+    /// 
+    /// # Parameters
+    /// 
+    /// overrides: The new properties
+    pub fn set_overrides(&mut self, overrides: StyleProperties) {
+        if overrides != self.overrides {
+            self.overrides = overrides;
+            self.restyle = true;
+        }
+    }
+
+    /// Gets the kind of view
+    pub fn get_tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    /// Sets the kind of view, for example "button"
+    /// 
+    /// # Parameters
+    /// 
+    /// tag: The new tag, None to remove it
+    pub fn set_tag(&mut self, tag: Option<String>) {
+        if tag != self.tag {
+            self.tag = tag;
+            self.restyle = true;
+        }
+    }
+
+    /// Gets all the classes of the view
+    pub fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    /// Checks if the view has a class
+    /// 
+    /// # Parameters
+    /// 
+    /// class: The class to look for
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes.iter().any(|own| own == class)
+    }
+
+    /// Adds a class to the view if it does not already have it
+    /// 
+    /// # Parameters
+    /// 
+    /// class: The class to add
+    pub fn add_class(&mut self, class: &str) {
+        if !self.has_class(class) {
+            self.classes.push(class.to_string());
+            self.restyle = true;
+        }
+    }
+
+    /// Replaces all the classes of the view
+    /// 
+    /// # Parameters
+    /// 
+    /// classes: The new classes
+    pub fn set_classes(&mut self, classes: Vec<String>) {
+        if classes != self.classes {
+            self.classes = classes;
+            self.restyle = true;
+        }
+    }
+
+    /// Removes a class from the view
+    /// 
+    /// # Parameters
+    /// 
+    /// class: The class to remove
+    pub fn remove_class(&mut self, class: &str) {
+        if self.has_class(class) {
+            self.classes.retain(|own| own != class);
+            self.restyle = true;
+        }
+    }
+
+    /// Gets the current interaction state
+    pub fn get_state(&self) -> State {
+        self.state
+    }
+
+    /// Turns interaction states on or off
+    /// 
+    /// # Parameters
+    /// 
+    /// state: The states to change
+    /// 
+    /// value: True to turn them on, false to turn them off
+    pub fn set_state(&mut self, state: State, value: bool) {
+        let old = self.state;
+        self.state.set(state, value);
+        self.restyle |= old != self.state;
+    }
+
+    /// Checks if the style has changed since the last update
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Checks if the overrides, tag, classes or state have changed since the last update, a theme must style the view again
+    pub fn needs_restyle(&self) -> bool {
+        self.restyle
+    }
+
+    /// Marks the style as handled, done during the update
+    pub(crate) fn clear_dirty(&mut self) {
+        self.dirty = false;
+        self.restyle = false;
+    }
+}
+
+impl Default for StyleController {
+    fn default() -> Self {
+        Self::new(Style::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_extent() {
+        assert_eq!((0.0, 0.0, 1.0, 1.0), Insets::default().content_extent());
+        assert_eq!((0.25, 0.0, 0.5, 0.75), Insets::new(0.25, 0.0, 0.25, 0.25).content_extent());
+
+        // Insets which overlap leave no space, non-finite insets are ignored
+        assert_eq!((0.75, 0.0, 0.0, 1.0), Insets::new(0.75, f32::NAN, 0.5, f32::INFINITY).content_extent());
+    }
+
+    #[test]
+    fn dirty() {
+        let mut controller = StyleController::default();
+        assert!(controller.is_dirty());
+        controller.clear_dirty();
+
+        // Setting the same style does not mark it
+        controller.set_style(Style::default());
+        assert!(!controller.is_dirty());
+        controller.set_style(Style { background: Some(Color::RED), ..Style::default() });
+        assert!(controller.is_dirty());
+        assert_eq!(Some(Color::RED), controller.get_style().background);
+
+        // Anything a theme selects on asks for a restyle, but only if it changed
+        controller.clear_dirty();
+        assert!(!controller.needs_restyle());
+        controller.set_state(State::HOVERED, false);
+        controller.remove_class("missing");
+        assert!(!controller.needs_restyle());
+        controller.set_state(State::HOVERED, true);
+        assert!(controller.needs_restyle());
+        controller.clear_dirty();
+        controller.add_class("primary");
+        assert!(controller.needs_restyle());
+        assert!(!controller.is_dirty());
+    }
+
+    #[test]
+    fn classes() {
+        let mut controller = StyleController::default();
+        controller.add_class("primary");
+        controller.add_class("primary");
+        controller.add_class("large");
+        assert_eq!(vec!["primary".to_string(), "large".to_string()], controller.get_classes());
+        controller.remove_class("primary");
+        assert!(!controller.has_class("primary"));
+        assert!(controller.has_class("large"));
+
+        controller.set_state(State::HOVERED | State::PRESSED, true);
+        controller.set_state(State::PRESSED, false);
+        assert_eq!(State::HOVERED, controller.get_state());
+    }
+
+    #[test]
+    fn apply_to() {
+        let mut style = Style { border_width: 2.0, text_color: Color::RED, ..Style::default() };
+        StyleProperties { background: Some(Color::BLUE), border_width: Some(1.0), ..StyleProperties::default() }.apply_to(&mut style);
+        assert_eq!(Some(Color::BLUE), style.background);
+        assert_eq!(1.0, style.border_width);
+        assert_eq!(Color::RED, style.text_color);
+
+        // Only the inheritable properties are kept for the children
+        assert_eq!(Style { text_color: Color::RED, ..Style::default() }, style.inherited());
+    }
+}
@@ -0,0 +1,231 @@
+use super::{View, children, focus};
+use crate::render::{Rect, Color, Damage};
+use std::{cell::RefCell, rc::Rc, fmt::Write, time::Instant};
+
+/// The font size of the labels in pixels
+const LABEL_SIZE: f32 = 10.0;
+
+/// Decides what is drawn when exporting the layout as SVG
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// True if every view is labeled with its path from the root followed by its tag and classes
+    pub labels: bool,
+    /// True if arrows are drawn from every view to the siblings its extent is anchored to
+    pub dependencies: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self { labels: true, dependencies: true }
+    }
+}
+
+impl View {
+    /// Exports the layout of the view and all of its resolved children as an SVG document, the view is treated as the root.
+    /// The layout is done on a copy for a virtual window of the given size, so this works without a window and does not affect the view.
+    ///
+    /// Each view is drawn as a rectangle filled with its background, arrows to siblings referenced with Prev are dashed and arrows to siblings referenced by Id are solid.
+    /// If a view has a missing reference that view and all views after it are drawn where they were last laid out
+    ///
+    /// # Parameters
+    ///
+    /// window_size: The size of the virtual window in pixels as (w, h)
+    ///
+    /// options: Decides what is drawn
+    pub fn export_svg(&self, window_size: (f32, f32), options: &SvgOptions) -> String {
+        // A missing reference stops the layout, the views from there on keep the extents copied from the original
+        let mut root = self.detached();
+        let _ = root.update(window_size, Instant::now(), &mut Damage::new());
+
+        let mut svg = String::new();
+        let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="monospace" font-size="{2}">"#, window_size.0, window_size.1, LABEL_SIZE);
+        let _ = writeln!(svg, r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="blue"/></marker></defs>"#);
+
+        // Draw the views first so the arrows are on top of them
+        let mut arrows = String::new();
+        let window = Rect::new(0.0, 0.0, window_size.0, window_size.1);
+        root.export_svg_tree(window, &mut Vec::new(), options, &mut svg, &mut arrows);
+        svg.push_str(&arrows);
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+
+    /// Writes the rectangles, labels and arrows of this view and all of its children
+    ///
+    /// # Parameters
+    ///
+    /// parent_rect: The area of the parent this view is placed in, in pixels of the window
+    ///
+    /// path: The child indices from the root to this view
+    ///
+    /// options: Decides what is drawn
+    ///
+    /// svg: The document to write the rectangles and labels to
+    ///
+    /// arrows: The document to write the arrows to
+    fn export_svg_tree(&self, parent_rect: Rect, path: &mut Vec<usize>, options: &SvgOptions, svg: &mut String, arrows: &mut String) {
+        let rect = parent_rect.sub_rect(self.get_extent());
+        let style = self.get_style();
+        let content_rect = rect.sub_rect(style.padding.content_extent());
+        let name = if path.is_empty() { "root".to_string() } else { path.iter().map(|pos| pos.to_string()).collect::<Vec<String>>().join(".") };
+
+        if rect.is_finite() {
+            let fill = style.background.map_or("none".to_string(), svg_color);
+            let _ = writeln!(svg, r#"<rect data-path="{}" x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="black" stroke-width="1"/>"#, name, rect.x, rect.y, rect.w, rect.h, fill);
+
+            if options.labels {
+                let controller = self.style.borrow();
+                let mut label = name;
+                if let Some(tag) = controller.get_tag() {
+                    label.push(' ');
+                    label.push_str(tag);
+                }
+                for class in controller.get_classes() {
+                    label.push('.');
+                    label.push_str(class);
+                }
+                let _ = writeln!(svg, r#"<text x="{}" y="{}">{}</text>"#, rect.x + 2.0, rect.y + LABEL_SIZE, escape(&label));
+            }
+        }
+
+        let children = self.get_children();
+        let rects: Vec<Rect> = children.iter().map(|child| content_rect.sub_rect(child.get_extent())).collect();
+
+        // Point from every child to the siblings it depends on
+        if options.dependencies {
+            for (pos, child) in children.iter().enumerate() {
+                let controller = child.extent.borrow_controller();
+                let prev = (pos > 0 && controller.check_prev()).then(|| pos - 1);
+                let ids = (0..pos).filter(|id| controller.check_id(*id));
+                for (target, class) in prev.map(|prev| (prev, "prev")).into_iter().chain(ids.map(|id| (id, "id"))) {
+                    let (from, to) = (center(&rects[pos]), center(&rects[target]));
+                    if from.0.is_finite() && from.1.is_finite() && to.0.is_finite() && to.1.is_finite() {
+                        let dash = if class == "prev" { r#" stroke-dasharray="4 2""# } else { "" };
+                        let _ = writeln!(arrows, r#"<line class="{}" x1="{}" y1="{}" x2="{}" y2="{}" stroke="blue"{} marker-end="url(#arrow)"/>"#, class, from.0, from.1, to.0, to.1, dash);
+                    }
+                }
+            }
+        }
+
+        for (pos, child) in children.iter().enumerate() {
+            path.push(pos);
+            child.export_svg_tree(content_rect, path, options, svg, arrows);
+            path.pop();
+        }
+    }
+
+    /// Copies the view and all of its resolved children without sharing any controllers, scheduled changes are not copied.
+    /// The extents are copied as they were last laid out and updating the copy does not affect the original
+    fn detached(&self) -> Box<View> {
+        let list = self.get_children().iter().map(|child| child.detached()).collect();
+        let children = children::Children::from_list(list);
+        let extent = self.extent.detached();
+        let style = Rc::new(RefCell::new(self.style.borrow().clone()));
+        let focus = Rc::new(RefCell::new(focus::FocusController::default()));
+        let drawable = Rc::new(RefCell::new(self.drawable.borrow().clone()));
+
+        Box::new(View { children, extent, style, focus, sibling_id: self.sibling_id, drawable, clip: self.clip, dirty: true, painted: None, owner: Default::default() })
+    }
+}
+
+/// Gets the center of a rectangle
+///
+/// # Parameters
+///
+/// rect: The rectangle to get the center of
+fn center(rect: &Rect) -> (f32, f32) {
+    (rect.x + rect.w / 2.0, rect.y + rect.h / 2.0)
+}
+
+/// Formats a color as an SVG color
+///
+/// # Parameters
+///
+/// color: The color to format
+fn svg_color(color: Color) -> String {
+    format!("rgba({},{},{},{})", color.r, color.g, color.b, color.a as f32 / 255.0)
+}
+
+/// Escapes the characters which have a meaning in XML
+///
+/// # Parameters
+///
+/// text: The text to escape
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::gen_update_info;
+    use crate::view::{style, extent};
+
+    /// A root with three children in a diagonal, the second is anchored to the previous one and the third to the first by id
+    fn gen_root() -> Box<View> {
+        let root = View::new_root();
+        let scheduler = root.get_children_scheduler();
+        let anchor = |ref_view| extent::PositionType::Anchor(extent::AnchorPoint { ref_view, ref_point: 1.0 });
+        for pos in [extent::PositionType::Set(0.0), anchor(extent::RefView::Prev), anchor(extent::RefView::Id(0))] {
+            let child = View::new(gen_update_info(pos, extent::SizeType::Set(0.25)), Some(Rc::clone(&scheduler)));
+            scheduler.borrow_mut().push_operation(children::ChildrenScheduleOperation::Push(child)).unwrap();
+        }
+
+        root
+    }
+
+    #[test]
+    fn export() {
+        let mut root = gen_root();
+        root.resolve_children();
+        let first_style = root.get_children()[0].get_style_controller();
+        first_style.borrow_mut().set_tag(Some("button".to_string()));
+        first_style.borrow_mut().add_class("a<b");
+        first_style.borrow_mut().set_style(style::Style { background: Some(Color::RED), ..style::Style::default() });
+
+        let svg = root.export_svg((200.0, 100.0), &SvgOptions::default());
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="100""#));
+        assert!(svg.ends_with("</svg>\n"));
+
+        // The views are laid out for the virtual window
+        assert!(svg.contains(r#"<rect data-path="root" x="0" y="0" width="200" height="100" fill="none""#));
+        assert!(svg.contains(r#"<rect data-path="0" x="0" y="0" width="50" height="25" fill="rgba(255,0,0,1)""#));
+        assert!(svg.contains(r#"<rect data-path="1" x="50" y="25" width="50" height="25""#));
+        assert!(svg.contains(r#"<rect data-path="2" x="50" y="25" width="50" height="25""#));
+        assert!(svg.contains("<text x=\"2\" y=\"10\">0 button.a&lt;b</text>"));
+
+        // One dashed arrow for the previous sibling and one solid arrow for the id
+        assert!(svg.contains(r#"<line class="prev" x1="75" y1="37.5" x2="25" y2="12.5" stroke="blue" stroke-dasharray="4 2" marker-end="url(#arrow)"/>"#));
+        assert!(svg.contains(r#"<line class="id" x1="75" y1="37.5" x2="25" y2="12.5" stroke="blue" marker-end="url(#arrow)"/>"#));
+        assert_eq!(2, svg.matches("<line").count());
+
+        // Labels and arrows can be left out
+        let svg = root.export_svg((200.0, 100.0), &SvgOptions { labels: false, dependencies: false });
+        assert!(!svg.contains("<text") && !svg.contains("<line"));
+    }
+
+    #[test]
+    fn detached() {
+        // Exporting does not resolve scheduled children or clear the dirty styles of the original
+        let root = gen_root();
+        root.get_style_controller().borrow_mut().set_tag(Some("root".to_string()));
+        let svg = root.export_svg((100.0, 100.0), &SvgOptions::default());
+        assert!(!svg.contains(r#"data-path="0""#));
+        assert!(root.get_children().is_empty());
+        assert!(root.get_style_controller().borrow().is_dirty());
+        assert_eq!((0.0, 0.0, 1.0, 1.0), root.get_extent());
+    }
+
+    #[test]
+    fn missing_reference() {
+        let mut root = gen_root();
+        root.update((200.0, 100.0), Instant::now(), &mut Damage::new()).unwrap();
+
+        // The view with the missing reference is drawn where it was last laid out
+        let anchor = extent::PositionType::Anchor(extent::AnchorPoint { ref_view: extent::RefView::Id(5), ref_point: 1.0 });
+        root.get_children()[2].get_extent_controller().borrow_mut().set_update_info(gen_update_info(anchor, extent::SizeType::Set(0.25)));
+        let svg = root.export_svg((200.0, 100.0), &SvgOptions::default());
+        assert!(svg.contains(r#"<rect data-path="2" x="50" y="25" width="50" height="25""#));
+    }
+}